@@ -0,0 +1,125 @@
+use std::io::{BufRead, Seek};
+use std::rc::Rc;
+use super::{
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxInfo,
+
+    IsoBoxParser,
+    IsoBoxSerializer,
+
+    utils::parse_children,
+};
+
+/// Size of the fields of a version 0 audio sample entry before its contained
+/// boxes.
+const AUDIO_SAMPLE_ENTRY_HEADER_SIZE: u64 = 28;
+
+/// Sample entry of an audio track (e.g. `mp4a`, `ac-3` or `Opus`), whose
+/// contained boxes describe the coding parameters.
+pub struct AudioSampleEntry {
+    reserved: [u8; 6],
+    data_reference_index: u16,
+
+    /// Version of the entry. QuickTime version 1 and 2 sound descriptions
+    /// have additional fields, which are kept as is.
+    entry_version: u16,
+    /// Revision level and vendor for QuickTime, reserved otherwise.
+    revision_and_vendor: [u8; 6],
+    channelcount: u16,
+    samplesize: u16,
+    pre_defined: u32,
+    samplerate: u32,
+    quicktime_fields: Vec<u8>,
+    content: Vec<super::IsoBoxData>,
+}
+
+impl IsoBoxParser for AudioSampleEntry {
+    fn parse<T: BufRead + Seek>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        box_info: &Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let mut reserved = [0u8; 6];
+        reserved.copy_from_slice(&reader.read_bytes(6)?);
+        let data_reference_index = reader.read_u16()?;
+        let entry_version = reader.read_u16()?;
+        let mut revision_and_vendor = [0u8; 6];
+        revision_and_vendor.copy_from_slice(&reader.read_bytes(6)?);
+        let channelcount = reader.read_u16()?;
+        let samplesize = reader.read_u16()?;
+        // pre_defined and reserved
+        let pre_defined = reader.read_u32()?;
+        let samplerate = reader.read_u32()?;
+        let quicktime_fields = match entry_version {
+            1 => reader.read_bytes(16)?,
+            2 => reader.read_bytes(36)?,
+            _ => vec![],
+        };
+        let header_size = AUDIO_SAMPLE_ENTRY_HEADER_SIZE + quicktime_fields.len() as u64;
+        let content = parse_children(
+            reader,
+            content_size.map(|size| size.saturating_sub(header_size)),
+            Some(box_info))?;
+        Ok(Self {
+            reserved,
+            data_reference_index,
+            entry_version,
+            revision_and_vendor,
+            channelcount,
+            samplesize,
+            pre_defined,
+            samplerate,
+            quicktime_fields,
+            content,
+        })
+    }
+
+    fn get_inner_boxes_mut(&mut self) -> Option<&mut Vec<super::IsoBoxData>> {
+        Some(&mut self.content)
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<super::ContainedBoxInfo<'_>>> {
+        Some(self.content.iter().map(|c|
+                (c.0.as_ref(), c.1.as_ref().map(|boxed| { std::boxed::Box::as_ref(boxed) }))
+        ).collect())
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        let mut values = vec![
+            ("reserved", BoxValue::from(self.reserved.as_ref())),
+            ("data_reference_index", BoxValue::from(self.data_reference_index)),
+            ("entry_version", BoxValue::from(self.entry_version)),
+            ("channelcount", BoxValue::from(self.channelcount)),
+            ("samplesize", BoxValue::from(self.samplesize)),
+            ("samplerate", BoxValue::UFixedPoint16_16(self.samplerate)),
+        ];
+        if !self.quicktime_fields.is_empty() {
+            values.push(("quicktime_fields", BoxValue::Hex(&self.quicktime_fields)));
+        }
+        values
+    }
+
+    /// Audio sample entries are named after their coding, which is only
+    /// known from the box information.
+    fn get_short_name() -> &'static str {
+        "soun"
+    }
+}
+
+impl IsoBoxSerializer for AudioSampleEntry {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_bytes(&self.reserved)?;
+        writer.write_u16(self.data_reference_index)?;
+        writer.write_u16(self.entry_version)?;
+        writer.write_bytes(&self.revision_and_vendor)?;
+        writer.write_u16(self.channelcount)?;
+        writer.write_u16(self.samplesize)?;
+        writer.write_u32(self.pre_defined)?;
+        writer.write_u32(self.samplerate)?;
+        writer.write_bytes(&self.quicktime_fields)?;
+        writer.write_boxes(&self.content)
+    }
+}
@@ -0,0 +1,130 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub struct Av1c {
+    marker: bool,
+    version: u8,
+    seq_profile: u8,
+    seq_level_idx_0: u8,
+    seq_tier_0: u8,
+    high_bitdepth: bool,
+    twelve_bit: bool,
+    monochrome: bool,
+    chroma_subsampling_x: bool,
+    chroma_subsampling_y: bool,
+    chroma_sample_position: u8,
+    initial_presentation_delay_minus_one: Option<u8>,
+    config_obus: Vec<u8>,
+}
+
+impl Av1c {
+    /// Returns the OBUs, such as the sequence header, stored in this box.
+    pub fn get_config_obus(&self) -> &[u8] {
+        &self.config_obus
+    }
+}
+
+impl IsoBoxParser for Av1c {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let byte = reader.read_u8()?;
+        let marker = byte & 0x80 != 0;
+        let version = byte & 0x7F;
+        let byte = reader.read_u8()?;
+        let seq_profile = byte >> 5;
+        let seq_level_idx_0 = byte & 0x1F;
+        let byte = reader.read_u8()?;
+        let seq_tier_0 = byte >> 7;
+        let high_bitdepth = byte & 0x40 != 0;
+        let twelve_bit = byte & 0x20 != 0;
+        let monochrome = byte & 0x10 != 0;
+        let chroma_subsampling_x = byte & 0x08 != 0;
+        let chroma_subsampling_y = byte & 0x04 != 0;
+        let chroma_sample_position = byte & 0x03;
+        let byte = reader.read_u8()?;
+        let initial_presentation_delay_minus_one = if byte & 0x10 != 0 {
+            Some(byte & 0x0F)
+        } else {
+            None
+        };
+        let config_obus = match content_size {
+            Some(size) => reader.read_bytes(size.saturating_sub(4) as usize)?,
+            None => reader.read_to_end()?,
+        };
+        Ok(Self {
+            marker,
+            version,
+            seq_profile,
+            seq_level_idx_0,
+            seq_tier_0,
+            high_bitdepth,
+            twelve_bit,
+            monochrome,
+            chroma_subsampling_x,
+            chroma_subsampling_y,
+            chroma_sample_position,
+            initial_presentation_delay_minus_one,
+            config_obus,
+        })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        let mut values = vec![
+            ("marker", BoxValue::from(self.marker)),
+            ("version", BoxValue::from(self.version)),
+            ("seq_profile", BoxValue::from(self.seq_profile)),
+            ("seq_level_idx_0", BoxValue::from(self.seq_level_idx_0)),
+            ("seq_tier_0", BoxValue::from(self.seq_tier_0)),
+            ("high_bitdepth", BoxValue::from(self.high_bitdepth)),
+            ("twelve_bit", BoxValue::from(self.twelve_bit)),
+            ("monochrome", BoxValue::from(self.monochrome)),
+            ("chroma_subsampling_x", BoxValue::from(self.chroma_subsampling_x)),
+            ("chroma_subsampling_y", BoxValue::from(self.chroma_subsampling_y)),
+            ("chroma_sample_position", BoxValue::from(self.chroma_sample_position)),
+        ];
+        if let Some(val) = self.initial_presentation_delay_minus_one {
+            values.push(("initial_presentation_delay_minus_one", BoxValue::from(val)));
+        }
+        if !self.config_obus.is_empty() {
+            values.push(("config_obus", BoxValue::Hex(&self.config_obus)));
+        }
+        values
+    }
+
+    fn get_short_name() -> &'static str {
+        "av1C"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Av1c {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8((self.marker as u8) << 7 | self.version)?;
+        writer.write_u8(self.seq_profile << 5 | self.seq_level_idx_0)?;
+        writer.write_u8(
+            self.seq_tier_0 << 7 |
+            (self.high_bitdepth as u8) << 6 |
+            (self.twelve_bit as u8) << 5 |
+            (self.monochrome as u8) << 4 |
+            (self.chroma_subsampling_x as u8) << 3 |
+            (self.chroma_subsampling_y as u8) << 2 |
+            self.chroma_sample_position)?;
+        writer.write_u8(self.initial_presentation_delay_minus_one.map_or(0, |delay| 0x10 | delay))?;
+        writer.write_bytes(&self.config_obus)
+    }
+}
@@ -0,0 +1,197 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+/// Fields only present for the High profiles (`profile_idc` 100, 110, 122
+/// and 144).
+pub struct AvccHighProfileFields {
+    chroma_format: u8,
+    bit_depth_luma_minus8: u8,
+    bit_depth_chroma_minus8: u8,
+    sequence_parameter_set_ext: Vec<Vec<u8>>,
+}
+
+pub struct Avcc {
+    configuration_version: u8,
+    avc_profile_indication: u8,
+    profile_compatibility: u8,
+    avc_level_indication: u8,
+    length_size_minus_one: u8,
+    sequence_parameter_sets: Vec<Vec<u8>>,
+    picture_parameter_sets: Vec<Vec<u8>>,
+    high_profile_fields: Option<AvccHighProfileFields>,
+    /// Data found after the announced fields, kept as is.
+    trailing_data: Vec<u8>,
+}
+
+impl Avcc {
+    /// Returns the size in bytes of the length field preceding each NAL unit
+    /// in the samples.
+    pub fn get_nal_length_size(&self) -> u8 {
+        self.length_size_minus_one + 1
+    }
+
+    /// Returns the sequence and picture parameter set NAL units.
+    pub fn get_parameter_sets(&self) -> Vec<&[u8]> {
+        self.sequence_parameter_sets
+            .iter()
+            .chain(self.picture_parameter_sets.iter())
+            .map(|ps| ps.as_slice())
+            .collect()
+    }
+}
+
+/// Read `count` parameter sets, each preceded by its 16-bit length.
+fn read_parameter_sets<T: BufRead>(
+    reader: &mut BoxReader<T>,
+    count: u8
+) -> Result<Vec<Vec<u8>>, std::io::Error> {
+    let mut parameter_sets = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let length = reader.read_u16()?;
+        parameter_sets.push(reader.read_bytes(length as usize)?);
+    }
+    Ok(parameter_sets)
+}
+
+fn write_parameter_sets(
+    writer: &mut BoxWriter,
+    parameter_sets: &[Vec<u8>]
+) -> Result<(), std::io::Error> {
+    for parameter_set in parameter_sets {
+        writer.write_u16(parameter_set.len() as u16)?;
+        writer.write_bytes(parameter_set)?;
+    }
+    Ok(())
+}
+
+fn get_parameter_sets_value(parameter_sets: &[Vec<u8>]) -> BoxValue {
+    BoxValue::Collection(
+        parameter_sets.iter().map(|parameter_set| {
+            vec![
+                ("length", BoxValue::from(parameter_set.len() as u16)),
+                ("nal_unit", BoxValue::Hex(parameter_set)),
+            ]
+        }).collect()
+    )
+}
+
+impl IsoBoxParser for Avcc {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let configuration_version = reader.read_u8()?;
+        let avc_profile_indication = reader.read_u8()?;
+        let profile_compatibility = reader.read_u8()?;
+        let avc_level_indication = reader.read_u8()?;
+        let length_size_minus_one = reader.read_u8()? & 0x03;
+        let num_sps = reader.read_u8()? & 0x1F;
+        let sequence_parameter_sets = read_parameter_sets(reader, num_sps)?;
+        let num_pps = reader.read_u8()?;
+        let picture_parameter_sets = read_parameter_sets(reader, num_pps)?;
+        let mut parsed_size = 7 + sequence_parameter_sets
+            .iter()
+            .chain(picture_parameter_sets.iter())
+            .fold(0, |acc, ps| acc + 2 + ps.len() as u64);
+
+        // Many encoders omit the High profile fields, so only read them when
+        // the box has room left for them
+        let high_profile_fields = if
+            [100, 110, 122, 144].contains(&avc_profile_indication) &&
+            content_size.map_or(!reader.is_empty()?, |size| size >= parsed_size + 4)
+        {
+            let chroma_format = reader.read_u8()? & 0x03;
+            let bit_depth_luma_minus8 = reader.read_u8()? & 0x07;
+            let bit_depth_chroma_minus8 = reader.read_u8()? & 0x07;
+            let num_sps_ext = reader.read_u8()?;
+            let sequence_parameter_set_ext = read_parameter_sets(reader, num_sps_ext)?;
+            parsed_size += 4 + sequence_parameter_set_ext
+                .iter()
+                .fold(0, |acc, ps| acc + 2 + ps.len() as u64);
+            Some(AvccHighProfileFields {
+                chroma_format,
+                bit_depth_luma_minus8,
+                bit_depth_chroma_minus8,
+                sequence_parameter_set_ext,
+            })
+        } else {
+            None
+        };
+        let trailing_data = match content_size {
+            Some(size) => reader.read_bytes(size.saturating_sub(parsed_size) as usize)?,
+            None => vec![],
+        };
+        Ok(Self {
+            configuration_version,
+            avc_profile_indication,
+            profile_compatibility,
+            avc_level_indication,
+            length_size_minus_one,
+            sequence_parameter_sets,
+            picture_parameter_sets,
+            high_profile_fields,
+            trailing_data,
+        })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        let mut values = vec![
+            ("configuration_version", BoxValue::from(self.configuration_version)),
+            ("avc_profile_indication", BoxValue::from(self.avc_profile_indication)),
+            ("profile_compatibility", BoxValue::from(self.profile_compatibility)),
+            ("avc_level_indication", BoxValue::from(self.avc_level_indication)),
+            ("length_size_minus_one", BoxValue::from(self.length_size_minus_one)),
+            ("sequence_parameter_sets", get_parameter_sets_value(&self.sequence_parameter_sets)),
+            ("picture_parameter_sets", get_parameter_sets_value(&self.picture_parameter_sets)),
+        ];
+        if let Some(fields) = &self.high_profile_fields {
+            values.push(("chroma_format", BoxValue::from(fields.chroma_format)));
+            values.push(("bit_depth_luma_minus8", BoxValue::from(fields.bit_depth_luma_minus8)));
+            values.push(("bit_depth_chroma_minus8", BoxValue::from(fields.bit_depth_chroma_minus8)));
+            values.push(("sequence_parameter_set_ext",
+                get_parameter_sets_value(&fields.sequence_parameter_set_ext)));
+        }
+        values
+    }
+
+    fn get_short_name() -> &'static str {
+        "avcC"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Avcc {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.configuration_version)?;
+        writer.write_u8(self.avc_profile_indication)?;
+        writer.write_u8(self.profile_compatibility)?;
+        writer.write_u8(self.avc_level_indication)?;
+        // reserved bits are all set to 1
+        writer.write_u8(0xFC | self.length_size_minus_one)?;
+        writer.write_u8(0xE0 | self.sequence_parameter_sets.len() as u8)?;
+        write_parameter_sets(writer, &self.sequence_parameter_sets)?;
+        writer.write_u8(self.picture_parameter_sets.len() as u8)?;
+        write_parameter_sets(writer, &self.picture_parameter_sets)?;
+        if let Some(fields) = &self.high_profile_fields {
+            writer.write_u8(0xFC | fields.chroma_format)?;
+            writer.write_u8(0xF8 | fields.bit_depth_luma_minus8)?;
+            writer.write_u8(0xF8 | fields.bit_depth_chroma_minus8)?;
+            writer.write_u8(fields.sequence_parameter_set_ext.len() as u8)?;
+            write_parameter_sets(writer, &fields.sequence_parameter_set_ext)?;
+        }
+        writer.write_bytes(&self.trailing_data)
+    }
+}
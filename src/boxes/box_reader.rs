@@ -1,13 +1,26 @@
 use std::io::{BufRead, Seek};
 use super::error;
+use super::context::ParsingContext;
 
 pub struct BoxReader<T: BufRead> {
     reader: T,
+    context: ParsingContext,
 }
 
 impl<T : BufRead> BoxReader<T> {
     pub fn create(reader: T) -> BoxReader<T> {
-        Self { reader }
+        Self { reader, context: ParsingContext::default() }
+    }
+
+    /// Returns information gathered on the boxes already parsed.
+    pub fn get_context(&self) -> &ParsingContext {
+        &self.context
+    }
+
+    /// Returns a mutable reference to the information gathered on the boxes
+    /// already parsed, so a parser can complete it.
+    pub fn get_context_mut(&mut self) -> &mut ParsingContext {
+        &mut self.context
     }
 
     /// Read the next N bytes as an utf8 string.
@@ -24,6 +37,35 @@ impl<T : BufRead> BoxReader<T> {
         Ok(String::from_utf8(buffer)?)
     }
 
+    /// Read a four-character code, such as a box type.
+    /// Unlike `read_str`, this never fails on non-UTF-8 data: each byte is
+    /// interpreted as a Latin-1 character, which is how codes like the
+    /// iTunes metadata "\u{a9}nam" are meant to be read.
+    pub fn read_4cc(&mut self) -> Result<String, std::io::Error> {
+        let mut buffer = [0; 4];
+        self.reader.read_exact(&mut buffer)?;
+        Ok(buffer.iter().map(|b| *b as char).collect())
+    }
+
+    /// Read the next N bytes as is.
+    pub fn read_bytes(&mut self, nb_bytes: usize) -> Result<Vec<u8>, std::io::Error> {
+        let mut buffer = vec![0; nb_bytes];
+        self.reader.read_exact(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    /// Read bytes up to a null byte, which is consumed but not returned.
+    pub fn read_null_terminated(&mut self) -> Result<Vec<u8>, std::io::Error> {
+        let mut buffer = vec![];
+        self.reader.read_until(0, &mut buffer)?;
+        if buffer.last() == Some(&0) {
+            buffer.pop();
+        } else {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof));
+        }
+        Ok(buffer)
+    }
+
     /// Get the next four bytes as an i32.
     pub fn read_i32(&mut self) -> Result<i32, std::io::Error> {
         let mut buffer = [0; 4];
@@ -98,6 +140,14 @@ impl<T : BufRead + Seek> BoxReader<T> {
         }
     }
 
+    /// Returns the next N bytes without consuming them.
+    pub fn peek_bytes(&mut self, nb_bytes: usize) -> Result<Vec<u8>, std::io::Error> {
+        let pos = self.get_pos()?;
+        let bytes = self.read_bytes(nb_bytes);
+        self.reader.seek(std::io::SeekFrom::Start(pos))?;
+        bytes
+    }
+
     pub fn get_pos(&mut self) -> Result<u64, std::io::Error> {
         self.reader.seek(std::io::SeekFrom::Current(0))
     }
@@ -3,6 +3,7 @@ use std::rc::Rc;
 
 use super::error;
 use super::box_reader::BoxReader;
+use super::box_writer::BoxWriter;
 
 #[derive(Debug)]
 pub struct IsoBoxInfo {
@@ -10,6 +11,9 @@ pub struct IsoBoxInfo {
     pub offset: u64,
     /// Size of the box.
     pub size: u64,
+    /// Size of the box's header, which is made of its size, its type and its
+    /// extended type for `uuid` boxes.
+    pub header_size: u64,
     /// Short name of the box, as indicated in the ISOBMFF file.
     pub short_name: String,
     /// When the box is an `uuid` box, this is the defined extended name.
@@ -22,6 +26,9 @@ pub struct IsoBoxInfo {
 #[derive(Copy, Clone, Debug)]
 pub struct Flags(u32);
 
+/// Associates the bit mask of each flag defined for a given box to its name.
+pub type FlagNames = &'static [(u32, &'static str)];
+
 impl Flags {
     pub fn new(data: [u8; 3]) -> Self {
         Self(
@@ -39,6 +46,15 @@ impl Flags {
         self.0 & flag > 0
     }
 
+    /// Returns the names, in `flag_names`, of the flags which are set.
+    pub fn get_set_flag_names(&self, flag_names: FlagNames) -> Vec<&'static str> {
+        flag_names
+            .iter()
+            .filter(|(mask, _)| self.has_flag(*mask))
+            .map(|(_, name)| *name)
+            .collect()
+    }
+
     pub fn to_hex_string(&self) -> String {
         use std::fmt::Write;
         let mut s = String::new();
@@ -49,6 +65,12 @@ impl Flags {
     }
 }
 
+impl From<u32> for Flags {
+    fn from(val: u32) -> Flags {
+        Self(val & 0xFFFFFF)
+    }
+}
+
 impl From<Flags> for u32 {
     fn from(val: Flags) -> u32 {
         val.0
@@ -75,6 +97,82 @@ impl From<Flags> for [u8; 3] {
     }
 }
 
+/// Sample flags, as found in `trun`, `tfhd` and `trex` boxes.
+///
+/// Those are 32 bits organized as follow:
+///   - 4 reserved bits
+///   - 2 bits for `is_leading`
+///   - 2 bits for `sample_depends_on`
+///   - 2 bits for `sample_is_depended_on`
+///   - 2 bits for `sample_has_redundancy`
+///   - 3 bits for `sample_padding_value`
+///   - 1 bit for `sample_is_non_sync_sample`
+///   - 16 bits for `sample_degradation_priority`
+#[derive(Copy, Clone, Debug)]
+pub struct SampleFlags(u32);
+
+impl SampleFlags {
+    pub fn new(val: u32) -> Self {
+        Self(val)
+    }
+
+    pub fn read(reader: &mut BoxReader<impl BufRead>) -> Result<Self, std::io::Error> {
+        Ok(SampleFlags::new(reader.read_u32()?))
+    }
+
+    pub fn is_leading(&self) -> u8 {
+        ((self.0 >> 26) & 0x03) as u8
+    }
+
+    pub fn sample_depends_on(&self) -> u8 {
+        ((self.0 >> 24) & 0x03) as u8
+    }
+
+    pub fn sample_is_depended_on(&self) -> u8 {
+        ((self.0 >> 22) & 0x03) as u8
+    }
+
+    pub fn sample_has_redundancy(&self) -> u8 {
+        ((self.0 >> 20) & 0x03) as u8
+    }
+
+    pub fn sample_padding_value(&self) -> u8 {
+        ((self.0 >> 17) & 0x07) as u8
+    }
+
+    pub fn sample_is_non_sync_sample(&self) -> bool {
+        (self.0 >> 16) & 0x01 != 0
+    }
+
+    pub fn sample_degradation_priority(&self) -> u16 {
+        (self.0 & 0xFFFF) as u16
+    }
+
+    /// Returns each decoded field of those sample flags, by name.
+    pub fn get_values(&self) -> Vec<(&'static str, BoxValue<'static>)> {
+        vec![
+            ("is_leading", BoxValue::from(self.is_leading())),
+            ("sample_depends_on", BoxValue::from(self.sample_depends_on())),
+            ("sample_is_depended_on", BoxValue::from(self.sample_is_depended_on())),
+            ("sample_has_redundancy", BoxValue::from(self.sample_has_redundancy())),
+            ("sample_padding_value", BoxValue::from(self.sample_padding_value())),
+            ("sample_is_non_sync_sample", BoxValue::from(self.sample_is_non_sync_sample())),
+            ("sample_degradation_priority",
+             BoxValue::from(self.sample_degradation_priority())),
+        ]
+    }
+
+    pub fn to_hex_string(self) -> String {
+        format!("{:#010X}", self.0)
+    }
+}
+
+impl From<SampleFlags> for u32 {
+    fn from(val: SampleFlags) -> u32 {
+        val.0
+    }
+}
+
 /// Enum defining in a generic way a box's value.
 ///
 /// The ISOBMFF specification defines multiple type for properties.
@@ -88,25 +186,56 @@ pub enum BoxValue<'iso_box_entry> {
     UInt16(u16),
     UInt32(u32),
     UInt64(u64),
+    Int16(i16),
     Int32(i32),
     Int64(i64),
     Flags(Flags),
+    SampleFlags(SampleFlags),
     Bool(bool),
 
+    // Time values, displayed both raw and in a human-readable form
+    /// Number of seconds since midnight, Jan. 1, 1904, in UTC time.
+    Date(u64),
+    /// Number of ticks (first value) in a given timescale (second value).
+    Ticks(u64, u32),
+    /// Same as `Ticks`, but for values which might be negative.
+    SignedTicks(i64, u32),
+    /// 64-bit NTP timestamp: seconds since midnight, Jan. 1, 1900, in UTC
+    /// time in the upper 32 bits and a fraction of second in the lower ones.
+    NtpTimestamp(u64),
+
     // Fixed point floats (no IEEE754 in ISOBMFF), still Copy
-    FixedPoint8([u8; 2]),
-    FixedPoint16([u16; 2]),
-    // FixedPoint32([u32; 2]),
+    /// Signed 8.8 fixed-point value.
+    FixedPoint8_8(i16),
+    /// Signed 16.16 fixed-point value.
+    FixedPoint16_16(i32),
+    /// Unsigned 16.16 fixed-point value.
+    UFixedPoint16_16(u32),
+    /// Signed 2.30 fixed-point value.
+    FixedPoint2_30(i32),
+
+    /// Unsigned value expressed in units of 1/N, N being the second value,
+    /// displayed both raw and converted.
+    Scaled(u32, u32),
+
+    /// ISO-639-2/T language code, packed as three 5-bit characters.
+    Language(u16),
 
     // More complex types, linked to the corresponding IsoBoxEntry lifetime
 
     // Slices-based
     UInt8Arr(&'iso_box_entry [u8]),
+    /// Opaque binary data (e.g. key ids, initialization vectors), displayed
+    /// as an hexadecimal string.
+    Hex(&'iso_box_entry [u8]),
     // UInt16Arr(&'iso_box_entry [u16]),
     UInt32Arr(&'iso_box_entry [u32]),
     UInt64Arr(&'iso_box_entry [u64]),
 
     // Matrix
+    /// Transformation matrix, as found in `mvhd` and `tkhd` boxes.
+    /// Its `u`, `v` and `w` values (third column) are 2.30 fixed-point values,
+    /// all others are 16.16 fixed-point values.
     Matrix3_3(&'iso_box_entry [u32; 9]),
 
     // Strings
@@ -117,6 +246,26 @@ pub enum BoxValue<'iso_box_entry> {
     Collection(Vec<Vec<(&'iso_box_entry str, BoxValue<'iso_box_entry>)>>),
 }
 
+impl<'a> BoxValue<'a> {
+    /// Creates a `BoxValue` for a number of ticks in a timescale which might
+    /// not be known.
+    pub fn from_ticks(ticks: u64, timescale: Option<u32>) -> Self {
+        match timescale {
+            Some(ts) if ts > 0 => BoxValue::Ticks(ticks, ts),
+            _ => BoxValue::UInt64(ticks),
+        }
+    }
+
+    /// Creates a `BoxValue` for a signed number of ticks in a timescale which
+    /// might not be known.
+    pub fn from_signed_ticks(ticks: i64, timescale: Option<u32>) -> Self {
+        match timescale {
+            Some(ts) if ts > 0 => BoxValue::SignedTicks(ticks, ts),
+            _ => BoxValue::Int64(ticks),
+        }
+    }
+}
+
 impl<'a> From<u8> for BoxValue<'a> {
     fn from(val: u8) -> Self {
         BoxValue::UInt8(val)
@@ -141,6 +290,12 @@ impl<'a> From<u64> for BoxValue<'a> {
     }
 }
 
+impl<'a> From<i16> for BoxValue<'a> {
+    fn from(val: i16) -> Self {
+        BoxValue::Int16(val)
+    }
+}
+
 impl<'a> From<i32> for BoxValue<'a> {
     fn from(val: i32) -> Self {
         BoxValue::Int32(val)
@@ -159,6 +314,12 @@ impl<'a> From<Flags> for BoxValue<'a> {
     }
 }
 
+impl<'a> From<SampleFlags> for BoxValue<'a> {
+    fn from(val: SampleFlags) -> Self {
+        BoxValue::SampleFlags(val)
+    }
+}
+
 impl<'a> From<bool> for BoxValue<'a> {
     fn from(val: bool) -> Self {
         BoxValue::Bool(val)
@@ -217,6 +378,21 @@ pub type ContainedBoxInfo<'a> = (
 ///      `None` if we could not parse it (e.g. no parser were available).
 pub type IsoBoxData = (Rc<IsoBoxInfo>, Option<Box<dyn IsoBoxEntry>>);
 
+/// Wrap a box created from scratch, rather than parsed, so it can be written
+/// like parsed boxes.
+/// Its offset is unknown and its size is computed again when writing it.
+pub fn create_box_data(entry: impl IsoBoxEntry + 'static) -> IsoBoxData {
+    let info = IsoBoxInfo {
+        offset: 0,
+        size: 8,
+        header_size: 8,
+        short_name: entry.get_short_name().to_owned(),
+        user_type: None,
+        parent_box_info: None,
+    };
+    (Rc::new(info), Some(Box::new(entry)))
+}
+
 /// Trait for implementing ISOBMFF box parsers.
 ///
 /// This is the trait you should implement on any new struct defining the parsing
@@ -240,16 +416,32 @@ pub trait IsoBoxParser {
     /// Returns the short 4-characters version of the box' name.
     fn get_short_name() -> &'static str where Self: Sized;
 
-    /// Returns a long version of the box' name.
-    fn get_long_name() -> &'static str where Self: Sized;
+    /// Returns the name of each flag defined for this box.
+    /// Empty for boxes which do not define any flag.
+    fn get_flag_names() -> FlagNames where Self: Sized {
+        &[]
+    }
 
     fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)>;
 
     fn get_inner_boxes_ref(&self) -> Option<Vec<ContainedBoxInfo>>;
 
-    /// Consumes the IsoBoxEntry and return ownership of the inner parsed boxes.
-    /// `None` if that box is not a container box.
-    fn get_inner_boxes(self) -> Option<Vec<IsoBoxData>>;
+    /// Returns a mutable reference to the inner parsed boxes, so they can be
+    /// edited. `None` if that box is not a container box.
+    fn get_inner_boxes_mut(&mut self) -> Option<&mut Vec<IsoBoxData>> {
+        None
+    }
+}
+
+/// Trait for implementing ISOBMFF box serializers, the counterpart of
+/// `IsoBoxParser`.
+///
+/// Serializing a box just parsed should give back the exact same bytes.
+pub trait IsoBoxSerializer {
+    /// Write the content of the box, that is everything following its header.
+    /// The box header is written by `BoxWriter::write_box`, which computes
+    /// its size from that content.
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error>;
 }
 
 /// Trait for defining an ISOBMFF box.
@@ -270,22 +462,33 @@ pub trait IsoBoxEntry {
     /// Returns the short 4-characters version of the box' name.
     fn get_short_name(&self) -> &'static str;
 
-    /// Returns a long version of the box' name.
-    fn get_long_name(&self) -> &'static str;
+    /// Returns the name of each flag defined for this box.
+    fn get_flag_names(&self) -> FlagNames;
 
     fn get_inner_boxes_ref(&self) -> Option<Vec<ContainedBoxInfo>>;
 
-    /// Consumes the IsoBoxEntry and return ownership of the inner parsed boxes.
+    /// Returns a mutable reference to the inner parsed boxes.
     /// `None` if that box is not a container box.
-    fn get_inner_boxes(self) -> Option<Vec<IsoBoxData>>;
+    fn get_inner_boxes_mut(&mut self) -> Option<&mut Vec<IsoBoxData>>;
+
+    /// Allows to downcast the IsoBoxEntry to its concrete parser type, e.g.
+    /// to combine the information of multiple boxes.
+    fn as_any(&self) -> &dyn std::any::Any;
+
+    /// Same as `as_any` but allowing to modify the box, e.g. before writing
+    /// it back.
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any;
+
+    /// Write the content of the box, following its header.
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error>;
 }
 
-impl<T: IsoBoxParser> IsoBoxEntry for T {
+impl<T: IsoBoxParser + IsoBoxSerializer + 'static> IsoBoxEntry for T {
     fn get_short_name(&self) -> &'static str {
         T::get_short_name()
     }
-    fn get_long_name(&self) -> &'static str {
-        T::get_long_name()
+    fn get_flag_names(&self) -> FlagNames {
+        T::get_flag_names()
     }
     fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> { self.get_inner_values_ref() }
 
@@ -293,7 +496,19 @@ impl<T: IsoBoxParser> IsoBoxEntry for T {
         self.get_inner_boxes_ref()
     }
 
-    fn get_inner_boxes(self) -> Option<Vec<IsoBoxData>> {
-        self.get_inner_boxes()
+    fn get_inner_boxes_mut(&mut self) -> Option<&mut Vec<IsoBoxData>> {
+        IsoBoxParser::get_inner_boxes_mut(self)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        IsoBoxSerializer::serialize_content(self, writer)
     }
 }
@@ -0,0 +1,280 @@
+use std::io::{Read, Seek, SeekFrom, Write};
+use super::{Flags, IsoBoxData, IsoBoxEntry, IsoBoxInfo};
+
+/// A source the data of boxes not kept in memory once parsed (e.g. `mdat`)
+/// can be copied from.
+pub trait BoxSource: Read + Seek {}
+
+impl<T: Read + Seek> BoxSource for T {}
+
+enum Destination<'a> {
+    /// Only count the bytes which would have been written.
+    Counter(u64),
+    Output(&'a mut dyn Write),
+}
+
+/// Writes ISOBMFF boxes, the counterpart of `BoxReader`.
+///
+/// Box contents which were not kept in memory by their parser are copied
+/// from the file they were parsed from, given as a `source`.
+pub struct BoxWriter<'a> {
+    destination: Destination<'a>,
+    source: Option<&'a mut dyn BoxSource>,
+}
+
+impl<'a> BoxWriter<'a> {
+    pub fn create(output: &'a mut dyn Write, source: Option<&'a mut dyn BoxSource>) -> Self {
+        Self { destination: Destination::Output(output), source }
+    }
+
+    /// Creates a `BoxWriter` which writes nothing, only to compute sizes.
+    fn create_counter() -> BoxWriter<'static> {
+        BoxWriter { destination: Destination::Counter(0), source: None }
+    }
+
+    /// Returns the size a box would have once serialized, header included.
+    pub fn get_box_size(info: &IsoBoxInfo, entry: Option<&dyn IsoBoxEntry>) -> std::io::Result<u64> {
+        let content_size = Self::get_content_size(info, entry)?;
+        Ok(get_header_size(info, content_size) + content_size)
+    }
+
+    fn get_content_size(info: &IsoBoxInfo, entry: Option<&dyn IsoBoxEntry>) -> std::io::Result<u64> {
+        match entry {
+            Some(entry) => {
+                let mut counter = Self::create_counter();
+                entry.serialize_content(&mut counter)?;
+                Ok(counter.get_written_size())
+            },
+            None => Ok(info.size.saturating_sub(info.header_size)),
+        }
+    }
+
+    fn get_written_size(&self) -> u64 {
+        match self.destination {
+            Destination::Counter(count) => count,
+            Destination::Output(_) => 0,
+        }
+    }
+
+    /// Write a whole box: its header, with a size computed from its content,
+    /// then its content.
+    /// Boxes which could not be parsed (`entry` set to `None`) are copied as
+    /// is from the source.
+    pub fn write_box(
+        &mut self,
+        info: &IsoBoxInfo,
+        entry: Option<&dyn IsoBoxEntry>
+    ) -> std::io::Result<()> {
+        let content_size = match self.destination {
+            // Sizes are only needed once in the output, don't compute them
+            // for each level of nesting
+            Destination::Counter(_) => {
+                let before = self.get_written_size();
+                self.write_content(info, entry)?;
+                let content_size = self.get_written_size() - before;
+                self.skip(get_header_size(info, content_size));
+                return Ok(());
+            },
+            // boxes extending to the end of the file do not announce a size
+            Destination::Output(_) if info.size == 0 => 0,
+            Destination::Output(_) => Self::get_content_size(info, entry)?,
+        };
+        let header_size = get_header_size(info, content_size);
+        let box_type = encode_4cc(&info.short_name);
+        if info.size == 0 {
+            // box extending to the end of the file
+            self.write_u32(0)?;
+            self.write_bytes(&box_type)?;
+        } else if header_size - info.user_type.map_or(0, |_| 16) == 16 {
+            self.write_u32(1)?;
+            self.write_bytes(&box_type)?;
+            self.write_u64(header_size + content_size)?;
+        } else {
+            self.write_u32((header_size + content_size) as u32)?;
+            self.write_bytes(&box_type)?;
+        }
+        if let Some(user_type) = info.user_type {
+            self.write_bytes(&user_type)?;
+        }
+        self.write_content(info, entry)
+    }
+
+    /// Write each of the given boxes, in order.
+    pub fn write_boxes(&mut self, boxes: &[IsoBoxData]) -> std::io::Result<()> {
+        for (info, entry) in boxes {
+            self.write_box(info, entry.as_deref())?;
+        }
+        Ok(())
+    }
+
+    fn write_content(
+        &mut self,
+        info: &IsoBoxInfo,
+        entry: Option<&dyn IsoBoxEntry>
+    ) -> std::io::Result<()> {
+        match entry {
+            Some(entry) => entry.serialize_content(self),
+            None if info.size == 0 => self.copy_from_source(info.offset + info.header_size, None),
+            None => self.copy_from_source(
+                info.offset + info.header_size,
+                Some(info.size.saturating_sub(info.header_size))),
+        }
+    }
+
+    /// Copy `size` bytes found at `offset` in the source, or every byte
+    /// until its end if `size` is `None`.
+    /// As the source is not known when only computing sizes, data until the
+    /// end of the source is then considered empty.
+    pub fn copy_from_source(&mut self, offset: u64, size: Option<u64>) -> std::io::Result<()> {
+        let output = match &mut self.destination {
+            Destination::Counter(count) => {
+                *count += size.unwrap_or(0);
+                return Ok(());
+            },
+            Destination::Output(output) => output,
+        };
+        let source = self.source.as_mut().ok_or_else(|| std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "no source to copy the data of unparsed boxes from"))?;
+        source.seek(SeekFrom::Start(offset))?;
+        let copied = std::io::copy(&mut (&mut **source).take(size.unwrap_or(u64::MAX)), output)?;
+        if matches!(size, Some(size) if copied < size) {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof));
+        }
+        Ok(())
+    }
+
+    fn skip(&mut self, nb_bytes: u64) {
+        if let Destination::Counter(count) = &mut self.destination {
+            *count += nb_bytes;
+        }
+    }
+
+    /// Write the given bytes as is.
+    pub fn write_bytes(&mut self, data: &[u8]) -> std::io::Result<()> {
+        match &mut self.destination {
+            Destination::Counter(count) => {
+                *count += data.len() as u64;
+                Ok(())
+            },
+            Destination::Output(output) => output.write_all(data),
+        }
+    }
+
+    /// Write a four-character code, such as a box type.
+    pub fn write_4cc(&mut self, code: &str) -> std::io::Result<()> {
+        self.write_bytes(&encode_4cc(code))
+    }
+
+    pub fn write_flags(&mut self, flags: Flags) -> std::io::Result<()> {
+        let bytes: [u8; 3] = flags.into();
+        self.write_bytes(&bytes)
+    }
+
+    pub fn write_i32(&mut self, val: i32) -> std::io::Result<()> {
+        self.write_bytes(&val.to_be_bytes())
+    }
+
+    pub fn write_u64(&mut self, val: u64) -> std::io::Result<()> {
+        self.write_bytes(&val.to_be_bytes())
+    }
+
+    pub fn write_u32(&mut self, val: u32) -> std::io::Result<()> {
+        self.write_bytes(&val.to_be_bytes())
+    }
+
+    pub fn write_u16(&mut self, val: u16) -> std::io::Result<()> {
+        self.write_bytes(&val.to_be_bytes())
+    }
+
+    pub fn write_u8(&mut self, val: u8) -> std::io::Result<()> {
+        self.write_bytes(&[val])
+    }
+}
+
+/// Returns the size of the header of a box whose content has the given size.
+/// The 64-bit size form is kept if the box was using it, and used when the
+/// size does not fit on 32 bits.
+fn get_header_size(info: &IsoBoxInfo, content_size: u64) -> u64 {
+    let user_type_size = info.user_type.map_or(0, |_| 16);
+    let was_large = info.header_size == 16 + user_type_size;
+    if was_large || 8 + user_type_size + content_size > u32::MAX as u64 {
+        16 + user_type_size
+    } else {
+        8 + user_type_size
+    }
+}
+
+/// Encode a four-character code the way `BoxReader::read_4cc` decodes it:
+/// each character being a Latin-1 byte.
+fn encode_4cc(code: &str) -> [u8; 4] {
+    let mut encoded = [b' '; 4];
+    for (i, c) in code.chars().take(4).enumerate() {
+        encoded[i] = c as u32 as u8;
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use super::BoxWriter;
+    use super::super::parse_isobmff;
+
+    /// Fixtures, all of which should be serialized back to the exact same
+    /// bytes.
+    const FIXTURES: &[&str] = &[
+        "audio.mp4",
+        "av1.mp4",
+        "elst.mp4",
+        "enc.mp4",
+        "encbad.mp4",
+        "frag.mp4",
+        "frags.mp4",
+        "groups.mp4",
+        "heif.avif",
+        "meta.mp4",
+        "nal.mp4",
+        "one_stpp.mp4",
+        "one_tx3g.mp4",
+        "one_wvtt.mp4",
+        "prft.mp4",
+        "progressive.mp4",
+        "progressive_all_sync.mp4",
+        "sei.mp4",
+        "sidx.mp4",
+        "ssix.mp4",
+        "subs.mp4",
+        "subsamples.mp4",
+        "tref.mp4",
+        "vse.mp4",
+    ];
+
+    /// Parse then serialize back the given fixture.
+    /// Returns a description of the first difference with the original file.
+    fn check_round_trip(file_name: &str) -> Option<String> {
+        let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), file_name);
+        let input = std::fs::read(&path).unwrap();
+        let data = parse_isobmff(Cursor::new(&input)).unwrap();
+        let mut source = Cursor::new(&input);
+        let mut output = vec![];
+        BoxWriter::create(&mut output, Some(&mut source))
+            .write_boxes(&data)
+            .unwrap();
+        if output.len() != input.len() {
+            return Some(format!("{}: size differs", file_name));
+        }
+        output.iter()
+            .zip(input.iter())
+            .position(|(a, b)| a != b)
+            .map(|pos| format!("{}: first difference at byte {}", file_name, pos))
+    }
+
+    #[test]
+    fn round_trip() {
+        let failures: Vec<String> = FIXTURES.iter()
+            .filter_map(|file_name| check_round_trip(file_name))
+            .collect();
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+}
@@ -0,0 +1,54 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub struct Btrt {
+    buffer_size_db: u32,
+    max_bitrate: u32,
+    avg_bitrate: u32,
+}
+
+impl IsoBoxParser for Btrt {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let buffer_size_db = reader.read_u32()?;
+        let max_bitrate = reader.read_u32()?;
+        let avg_bitrate = reader.read_u32()?;
+        Ok(Self { buffer_size_db, max_bitrate, avg_bitrate })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("buffer_size_db", BoxValue::from(self.buffer_size_db)),
+            ("max_bitrate", BoxValue::from(self.max_bitrate)),
+            ("avg_bitrate", BoxValue::from(self.avg_bitrate)),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "btrt"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Btrt {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u32(self.buffer_size_db)?;
+        writer.write_u32(self.max_bitrate)?;
+        writer.write_u32(self.avg_bitrate)
+    }
+}
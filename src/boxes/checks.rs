@@ -0,0 +1,275 @@
+use std::io::{Read, Seek, SeekFrom};
+use super::{
+    IsoBoxData,
+    IsoBoxEntry,
+    IsoBoxInfo,
+    TrackSamples,
+    co64::Co64,
+    saio::Saio,
+    saiz::Saiz,
+    senc::Senc,
+    sidx::Sidx,
+    ssix::Ssix,
+    stco::Stco,
+    stsz::Stsz,
+    tkhd::Tkhd,
+    traf::Traf,
+    trak::Trak,
+    tref::Tref,
+    trun::Trun,
+    utils::{collect_boxes, find_in},
+};
+
+/// Verify that the information found in the different parsed boxes is
+/// coherent.
+/// Returns a description of each issue found.
+pub fn check_isobmff(results: &[IsoBoxData]) -> Vec<String> {
+    let mut warnings = vec![];
+    check_sidx_references(results, &mut warnings);
+    check_ssix_subsegments(results, &mut warnings);
+    check_track_references(results, &mut warnings);
+    check_auxiliary_information_counts(results, &mut warnings);
+    warnings
+}
+
+/// Check that the byte ranges referenced by each top-level `sidx` box
+/// correspond to the boxes actually found at those offsets.
+fn check_sidx_references(results: &[IsoBoxData], warnings: &mut Vec<String>) {
+    let top_level_boxes: Vec<&IsoBoxInfo> = results.iter().map(|r| r.0.as_ref()).collect();
+    let get_sidx_at = |offset: u64| results
+        .iter()
+        .find(|r| r.0.offset == offset)
+        .and_then(|r| r.1.as_ref())
+        .and_then(|p| p.as_any().downcast_ref::<Sidx>());
+    for (sidx_info, parsed) in results.iter() {
+        let sidx = match parsed.as_ref().and_then(|p| p.as_any().downcast_ref::<Sidx>()) {
+            Some(sidx) => sidx,
+            None => continue,
+        };
+        for (i, reference) in sidx.resolve_references().iter().enumerate() {
+            let first_box = top_level_boxes
+                .iter()
+                .find(|b| b.offset == reference.start_offset);
+            let last_box = top_level_boxes
+                .iter()
+                .find(|b| b.offset + b.size == reference.end_offset);
+            match first_box {
+                None => warnings.push(format!(
+                    "sidx at offset {}: reference {} starts at offset {}, where no box starts",
+                    sidx_info.offset, i + 1, reference.start_offset)),
+                Some(b) if reference.is_sidx_reference && b.short_name != "sidx" =>
+                    warnings.push(format!(
+                        "sidx at offset {}: reference {} should point to a sidx box \
+                        but points to a {} box",
+                        sidx_info.offset, i + 1, b.short_name)),
+                Some(b) if !reference.is_sidx_reference && b.short_name == "sidx" =>
+                    warnings.push(format!(
+                        "sidx at offset {}: reference {} should point to media content \
+                        but points to a sidx box",
+                        sidx_info.offset, i + 1)),
+                _ => {},
+            }
+            if reference.is_sidx_reference {
+                if let Some(child) = get_sidx_at(reference.start_offset) {
+                    if child.get_timescale() == sidx.get_timescale() &&
+                        child.get_earliest_presentation_time() != reference.start_time
+                    {
+                        warnings.push(format!(
+                            "sidx at offset {}: reference {} starts at time {} but the \
+                            referenced sidx announces an earliest presentation time of {}",
+                            sidx_info.offset, i + 1, reference.start_time,
+                            child.get_earliest_presentation_time()));
+                    }
+                }
+            }
+            if last_box.is_none() {
+                warnings.push(format!(
+                    "sidx at offset {}: reference {} ends at offset {}, where no box ends",
+                    sidx_info.offset, i + 1, reference.end_offset));
+            }
+        }
+    }
+}
+
+/// Check that each top-level `ssix` box follows a `sidx` box and that its
+/// subsegments correspond to that `sidx`'s references.
+fn check_ssix_subsegments(results: &[IsoBoxData], warnings: &mut Vec<String>) {
+    for (i, (ssix_info, parsed)) in results.iter().enumerate() {
+        let ssix = match parsed.as_ref().and_then(|p| p.as_any().downcast_ref::<Ssix>()) {
+            Some(ssix) => ssix,
+            None => continue,
+        };
+        let sidx = i.checked_sub(1)
+            .and_then(|prev| results[prev].1.as_ref())
+            .and_then(|p| p.as_any().downcast_ref::<Sidx>());
+        let sidx = match sidx {
+            Some(sidx) => sidx,
+            None => {
+                warnings.push(format!(
+                    "ssix at offset {}: not directly preceded by a sidx box",
+                    ssix_info.offset));
+                continue;
+            },
+        };
+        let references = sidx.resolve_references();
+        if references.len() != ssix.get_subsegment_count() as usize {
+            warnings.push(format!(
+                "ssix at offset {}: {} subsegments announced but the preceding sidx \
+                has {} references",
+                ssix_info.offset, ssix.get_subsegment_count(), references.len()));
+        }
+        let sizes = ssix.get_subsegment_sizes();
+        for (j, (size, reference)) in sizes.iter().zip(references.iter()).enumerate() {
+            let referenced_size = reference.end_offset - reference.start_offset;
+            if *size != referenced_size {
+                warnings.push(format!(
+                    "ssix at offset {}: ranges of subsegment {} cover {} bytes but \
+                    the corresponding sidx reference has a size of {}",
+                    ssix_info.offset, j + 1, size, referenced_size));
+            }
+        }
+    }
+}
+
+/// Check that each track referenced by a `tref` box is declared by a `tkhd`.
+fn check_track_references(results: &[IsoBoxData], warnings: &mut Vec<String>) {
+    let mut tkhds: Vec<(&IsoBoxInfo, &Tkhd)> = vec![];
+    let mut trefs: Vec<(&IsoBoxInfo, &Tref)> = vec![];
+    for (info, parsed) in results.iter() {
+        collect_boxes(info, parsed.as_deref(), &mut tkhds);
+        collect_boxes(info, parsed.as_deref(), &mut trefs);
+    }
+    let track_ids: Vec<u32> = tkhds.iter().map(|(_, tkhd)| tkhd.get_track_id()).collect();
+    for (tref_info, tref) in trefs {
+        for reference in tref.get_references() {
+            for track_id in reference.get_track_ids() {
+                // 0 is allowed as a placeholder for a removed reference
+                if *track_id != 0 && !track_ids.contains(track_id) {
+                    warnings.push(format!(
+                        "tref at offset {}: {} reference to track {}, which has no tkhd",
+                        tref_info.offset, reference.get_reference_type(), track_id));
+                }
+            }
+        }
+    }
+}
+
+/// Check that the `saiz`, `saio` and `senc` boxes of each track and track
+/// fragment describe as many samples, chunks or track runs as there are.
+fn check_auxiliary_information_counts(results: &[IsoBoxData], warnings: &mut Vec<String>) {
+    let mut trafs: Vec<(&IsoBoxInfo, &Traf)> = vec![];
+    let mut traks: Vec<(&IsoBoxInfo, &Trak)> = vec![];
+    for (info, parsed) in results.iter() {
+        collect_boxes(info, parsed.as_deref(), &mut trafs);
+        collect_boxes(info, parsed.as_deref(), &mut traks);
+    }
+    for (trak_info, trak) in traks {
+        let sample_count = find_in::<Stsz>(trak_info, trak)
+            .first()
+            .map(|(_, stsz)| stsz.get_sample_count())
+            .unwrap_or(0);
+        let chunk_count = find_in::<Stco>(trak_info, trak)
+            .first()
+            .map(|(_, stco)| stco.get_chunk_offsets().len())
+            .or_else(|| find_in::<Co64>(trak_info, trak)
+                .first()
+                .map(|(_, co64)| co64.get_chunk_offsets().len()))
+            .unwrap_or(0);
+        check_auxiliary_boxes(trak_info, trak, sample_count, chunk_count, "chunks", warnings);
+    }
+    for (traf_info, traf) in trafs {
+        let truns = find_in::<Trun>(traf_info, traf);
+        let sample_count: u32 = truns.iter().map(|(_, trun)| trun.get_sample_count()).sum();
+        check_auxiliary_boxes(traf_info, traf, sample_count, truns.len(), "trun boxes", warnings);
+    }
+}
+
+/// Check the `saiz`, `saio` and `senc` boxes found in a `traf` or `trak` box
+/// against its number of samples and its number of track runs or chunks.
+fn check_auxiliary_boxes(
+    info: &IsoBoxInfo,
+    entry: &dyn IsoBoxEntry,
+    sample_count: u32,
+    group_count: usize,
+    group_name: &str,
+    warnings: &mut Vec<String>
+) {
+    for (saiz_info, saiz) in find_in::<Saiz>(info, entry) {
+        if saiz.get_sample_count() != sample_count {
+            warnings.push(format!(
+                "saiz at offset {}: {} samples announced but its {} has {}",
+                saiz_info.offset, saiz.get_sample_count(), info.short_name, sample_count));
+        }
+    }
+    for (saio_info, saio) in find_in::<Saio>(info, entry) {
+        let offset_count = saio.get_offsets().len();
+        if offset_count != 1 && offset_count != group_count {
+            warnings.push(format!(
+                "saio at offset {}: {} offsets given but its {} has {} {}",
+                saio_info.offset, offset_count, info.short_name, group_count, group_name));
+        }
+    }
+    for (senc_info, senc) in find_in::<Senc>(info, entry) {
+        if senc.get_sample_count() != sample_count {
+            warnings.push(format!(
+                "senc at offset {}: {} samples announced but its {} has {}",
+                senc_info.offset, senc.get_sample_count(), info.short_name, sample_count));
+        }
+    }
+}
+
+/// Returns `true` if the file has `saiz`, `saio` or `senc` boxes, and so
+/// auxiliary information to check with `check_auxiliary_information`.
+pub fn has_auxiliary_information(results: &[IsoBoxData]) -> bool {
+    let mut saizs: Vec<(&IsoBoxInfo, &Saiz)> = vec![];
+    let mut saios: Vec<(&IsoBoxInfo, &Saio)> = vec![];
+    let mut sencs: Vec<(&IsoBoxInfo, &Senc)> = vec![];
+    for (info, parsed) in results.iter() {
+        collect_boxes(info, parsed.as_deref(), &mut saizs);
+        collect_boxes(info, parsed.as_deref(), &mut saios);
+        collect_boxes(info, parsed.as_deref(), &mut sencs);
+    }
+    !saizs.is_empty() || !saios.is_empty() || !sencs.is_empty()
+}
+
+/// Read the auxiliary information located by the `saiz` and `saio` boxes of
+/// each track and check that it is identical to the corresponding entries of
+/// the `senc` boxes.
+/// Returns a description of each issue found.
+pub fn check_auxiliary_information(
+    reader: &mut (impl Read + Seek),
+    tracks: &[TrackSamples]
+) -> Vec<String> {
+    let mut warnings = vec![];
+    for track in tracks {
+        let mut mismatch_count = 0;
+        let mut first_mismatch = None;
+        for sample in track.samples.iter() {
+            let ((offset, size), encryption) =
+                match (sample.auxiliary_information, sample.encryption.as_ref()) {
+                    (Some(aux_info), Some(encryption)) => (aux_info, encryption),
+                    _ => continue,
+                };
+            let mut data = vec![0; size as usize];
+            let read = reader.seek(SeekFrom::Start(offset))
+                .and_then(|_| reader.read_exact(&mut data));
+            if let Err(e) = read {
+                warnings.push(format!(
+                    "track {} sample {}: cannot read its auxiliary information at offset {}: {}",
+                    track.track_id, sample.sample_number, offset, e));
+                continue;
+            }
+            if data != encryption.data {
+                mismatch_count += 1;
+                first_mismatch.get_or_insert((sample.sample_number, offset));
+            }
+        }
+        if let Some((sample_number, offset)) = first_mismatch {
+            warnings.push(format!(
+                "track {}: the auxiliary information of {} samples differs from their \
+                senc entry, starting with sample {} at offset {}",
+                track.track_id, mismatch_count, sample_number, offset));
+        }
+    }
+    warnings
+}
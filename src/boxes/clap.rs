@@ -0,0 +1,75 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub struct Clap {
+    clean_aperture_width_n: u32,
+    clean_aperture_width_d: u32,
+    clean_aperture_height_n: u32,
+    clean_aperture_height_d: u32,
+    horiz_off_n: i32,
+    horiz_off_d: u32,
+    vert_off_n: i32,
+    vert_off_d: u32,
+}
+
+impl IsoBoxParser for Clap {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        Ok(Self {
+            clean_aperture_width_n: reader.read_u32()?,
+            clean_aperture_width_d: reader.read_u32()?,
+            clean_aperture_height_n: reader.read_u32()?,
+            clean_aperture_height_d: reader.read_u32()?,
+            horiz_off_n: reader.read_i32()?,
+            horiz_off_d: reader.read_u32()?,
+            vert_off_n: reader.read_i32()?,
+            vert_off_d: reader.read_u32()?,
+        })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("clean_aperture_width_n", BoxValue::from(self.clean_aperture_width_n)),
+            ("clean_aperture_width_d", BoxValue::from(self.clean_aperture_width_d)),
+            ("clean_aperture_height_n", BoxValue::from(self.clean_aperture_height_n)),
+            ("clean_aperture_height_d", BoxValue::from(self.clean_aperture_height_d)),
+            ("horiz_off_n", BoxValue::from(self.horiz_off_n)),
+            ("horiz_off_d", BoxValue::from(self.horiz_off_d)),
+            ("vert_off_n", BoxValue::from(self.vert_off_n)),
+            ("vert_off_d", BoxValue::from(self.vert_off_d)),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "clap"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Clap {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u32(self.clean_aperture_width_n)?;
+        writer.write_u32(self.clean_aperture_width_d)?;
+        writer.write_u32(self.clean_aperture_height_n)?;
+        writer.write_u32(self.clean_aperture_height_d)?;
+        writer.write_i32(self.horiz_off_n)?;
+        writer.write_u32(self.horiz_off_d)?;
+        writer.write_i32(self.vert_off_n)?;
+        writer.write_u32(self.vert_off_d)
+    }
+}
@@ -0,0 +1,51 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+/// Content light level information, in candelas per square meter.
+pub struct Clli {
+    max_content_light_level: u16,
+    max_pic_average_light_level: u16,
+}
+
+impl IsoBoxParser for Clli {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let max_content_light_level = reader.read_u16()?;
+        let max_pic_average_light_level = reader.read_u16()?;
+        Ok(Self { max_content_light_level, max_pic_average_light_level })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("max_content_light_level", BoxValue::from(self.max_content_light_level)),
+            ("max_pic_average_light_level", BoxValue::from(self.max_pic_average_light_level)),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "clli"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Clli {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u16(self.max_content_light_level)?;
+        writer.write_u16(self.max_pic_average_light_level)
+    }
+}
@@ -0,0 +1,80 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub struct Co64 {
+    version: u8,
+    flags: Flags,
+    entry_count: u32,
+    chunk_offsets: Vec<u64>,
+}
+
+impl Co64 {
+    pub fn new(chunk_offsets: Vec<u64>) -> Self {
+        Self {
+            version: 0,
+            flags: Flags::from(0),
+            entry_count: chunk_offsets.len() as u32,
+            chunk_offsets,
+        }
+    }
+
+    pub fn get_chunk_offsets(&self) -> Vec<u64> {
+        self.chunk_offsets.clone()
+    }
+}
+
+impl IsoBoxParser for Co64 {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let entry_count = reader.read_u32()?;
+        let mut chunk_offsets = Vec::with_capacity(entry_count as usize);
+        for _ in 0..entry_count {
+            chunk_offsets.push(reader.read_u64()?);
+        }
+        Ok(Self { version, flags, entry_count, chunk_offsets })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("entry_count", BoxValue::from(self.entry_count)),
+            ("chunk_offsets", BoxValue::from(self.chunk_offsets.as_slice())),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "co64"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Co64 {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_u32(self.entry_count)?;
+        for chunk_offset in self.chunk_offsets.iter() {
+            writer.write_u64(*chunk_offset)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub enum ColourInformation {
+    /// `nclx` (and QuickTime's `nclc`, without `full_range_flag`) colour
+    /// types, using the code points of ISO/IEC 23091-2.
+    Parameters {
+        colour_primaries: u16,
+        transfer_characteristics: u16,
+        matrix_coefficients: u16,
+        full_range_flag: Option<bool>,
+    },
+
+    /// `rICC` and `prof` colour types: an ICC profile.
+    IccProfile(Vec<u8>),
+
+    /// Any other colour type.
+    Unknown(Vec<u8>),
+}
+
+pub struct Colr {
+    colour_type: String,
+    info: ColourInformation,
+}
+
+impl IsoBoxParser for Colr {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let colour_type = reader.read_4cc()?;
+        let data = match content_size {
+            Some(size) => reader.read_bytes(size.saturating_sub(4) as usize)?,
+            None => reader.read_to_end()?,
+        };
+        let info = match (colour_type.as_str(), data.len()) {
+            ("nclx", 7) | ("nclc", 6) => ColourInformation::Parameters {
+                colour_primaries: u16::from_be_bytes([data[0], data[1]]),
+                transfer_characteristics: u16::from_be_bytes([data[2], data[3]]),
+                matrix_coefficients: u16::from_be_bytes([data[4], data[5]]),
+                full_range_flag: data.get(6).map(|b| b & 0x80 != 0),
+            },
+            ("rICC", _) | ("prof", _) => ColourInformation::IccProfile(data),
+            _ => ColourInformation::Unknown(data),
+        };
+        Ok(Self { colour_type, info })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        let mut values = vec![
+            ("colour_type", BoxValue::from(self.colour_type.as_str())),
+        ];
+        match &self.info {
+            ColourInformation::Parameters {
+                colour_primaries,
+                transfer_characteristics,
+                matrix_coefficients,
+                full_range_flag,
+            } => {
+                values.push(("colour_primaries", BoxValue::from(*colour_primaries)));
+                if let Some(name) = get_colour_primaries_name(*colour_primaries) {
+                    values.push(("colour_primaries_name", BoxValue::from(name)));
+                }
+                values.push(("transfer_characteristics",
+                             BoxValue::from(*transfer_characteristics)));
+                if let Some(name) = get_transfer_characteristics_name(*transfer_characteristics) {
+                    values.push(("transfer_characteristics_name", BoxValue::from(name)));
+                }
+                values.push(("matrix_coefficients", BoxValue::from(*matrix_coefficients)));
+                if let Some(name) = get_matrix_coefficients_name(*matrix_coefficients) {
+                    values.push(("matrix_coefficients_name", BoxValue::from(name)));
+                }
+                if let Some(val) = full_range_flag {
+                    values.push(("full_range_flag", BoxValue::from(*val)));
+                }
+            },
+            ColourInformation::IccProfile(profile) => {
+                values.push(("icc_profile_size", BoxValue::from(profile.len() as u64)));
+            },
+            ColourInformation::Unknown(data) => {
+                values.push(("data", BoxValue::Hex(data)));
+            },
+        }
+        values
+    }
+
+    fn get_short_name() -> &'static str {
+        "colr"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+fn get_colour_primaries_name(val: u16) -> Option<&'static str> {
+    match val {
+        1 => Some("BT.709"),
+        2 => Some("unspecified"),
+        4 => Some("BT.470M"),
+        5 => Some("BT.470BG"),
+        6 => Some("BT.601"),
+        7 => Some("SMPTE 240M"),
+        8 => Some("generic film"),
+        9 => Some("BT.2020"),
+        10 => Some("XYZ"),
+        11 => Some("DCI-P3"),
+        12 => Some("Display P3"),
+        22 => Some("EBU 3213"),
+        _ => None,
+    }
+}
+
+fn get_transfer_characteristics_name(val: u16) -> Option<&'static str> {
+    match val {
+        1 => Some("BT.709"),
+        2 => Some("unspecified"),
+        4 => Some("gamma 2.2"),
+        5 => Some("gamma 2.8"),
+        6 => Some("BT.601"),
+        7 => Some("SMPTE 240M"),
+        8 => Some("linear"),
+        13 => Some("sRGB"),
+        14 => Some("BT.2020 10-bit"),
+        15 => Some("BT.2020 12-bit"),
+        16 => Some("PQ"),
+        17 => Some("SMPTE 428"),
+        18 => Some("HLG"),
+        _ => None,
+    }
+}
+
+fn get_matrix_coefficients_name(val: u16) -> Option<&'static str> {
+    match val {
+        0 => Some("identity"),
+        1 => Some("BT.709"),
+        2 => Some("unspecified"),
+        5 => Some("BT.470BG"),
+        6 => Some("BT.601"),
+        7 => Some("SMPTE 240M"),
+        8 => Some("YCgCo"),
+        9 => Some("BT.2020 non-constant luminance"),
+        10 => Some("BT.2020 constant luminance"),
+        14 => Some("ICtCp"),
+        _ => None,
+    }
+}
+
+impl IsoBoxSerializer for Colr {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_4cc(&self.colour_type)?;
+        match &self.info {
+            ColourInformation::Parameters {
+                colour_primaries,
+                transfer_characteristics,
+                matrix_coefficients,
+                full_range_flag,
+            } => {
+                writer.write_u16(*colour_primaries)?;
+                writer.write_u16(*transfer_characteristics)?;
+                writer.write_u16(*matrix_coefficients)?;
+                if let Some(flag) = full_range_flag {
+                    writer.write_u8((*flag as u8) << 7)?;
+                }
+                Ok(())
+            },
+            ColourInformation::IccProfile(data) |
+            ColourInformation::Unknown(data) => writer.write_bytes(data),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+use super::leva::LevelAssignment;
+use super::sgpd::SampleGroupEntry;
+
+/// Information gathered while parsing, which might be needed to parse or to
+/// interpret boxes encountered later in the file.
+///
+/// For example, `tfdt` and `trun` boxes express their times in the timescale
+/// of their track, which is only found in the corresponding `mdhd` box.
+#[derive(Default)]
+pub struct ParsingContext {
+    /// Timescale of the whole presentation, as found in the `mvhd` box.
+    movie_timescale: Option<u32>,
+
+    /// `track_ID` of the track whose boxes are currently being parsed, as
+    /// announced by the last encountered `tkhd` or `tfhd` box.
+    current_track_id: Option<u32>,
+
+    /// Information on each track encountered, by `track_ID`.
+    tracks: HashMap<u32, TrackContext>,
+
+    /// Byte range, as start and end offsets in the file, of each reference of
+    /// the last parsed `sidx` box.
+    sidx_references: Vec<(u64, u64)>,
+
+    /// Level assignments announced by the `leva` box, the first element
+    /// describing level 1.
+    level_assignments: Vec<LevelAssignment>,
+
+    /// Keys announced by the `keys` box of the `meta` box currently parsed,
+    /// to which the items of its `ilst` box refer by 1-based index.
+    metadata_keys: Vec<String>,
+}
+
+/// Information gathered about a single track.
+#[derive(Default)]
+pub struct TrackContext {
+    /// Timescale of the track's media, as found in its `mdhd` box.
+    pub timescale: Option<u32>,
+
+    /// Coding name of the track, which is the name of its first sample entry
+    /// (e.g. "avc1", "hvc1", "mp4a"...).
+    pub codec: Option<String>,
+
+    /// Sample group descriptions found in the track's sample table, by
+    /// grouping type. Those may also be referenced by its track fragments.
+    pub sample_group_descriptions: HashMap<String, Vec<SampleGroupEntry>>,
+
+    /// Size of the initialization vector of each sample, as announced by the
+    /// track's `tenc` box. Needed to parse `senc` boxes.
+    pub per_sample_iv_size: Option<u8>,
+}
+
+impl ParsingContext {
+    pub fn get_movie_timescale(&self) -> Option<u32> {
+        self.movie_timescale
+    }
+
+    pub fn set_movie_timescale(&mut self, timescale: u32) {
+        self.movie_timescale = Some(timescale);
+    }
+
+    pub fn get_current_track_id(&self) -> Option<u32> {
+        self.current_track_id
+    }
+
+    /// Indicates that the boxes parsed from now on relate to the track with
+    /// the given `track_ID`.
+    pub fn set_current_track_id(&mut self, track_id: u32) {
+        self.current_track_id = Some(track_id);
+        self.tracks.entry(track_id).or_default();
+    }
+
+    /// Returns the information gathered on the track with the given
+    /// `track_ID`, if it has been encountered.
+    pub fn get_track(&self, track_id: u32) -> Option<&TrackContext> {
+        self.tracks.get(&track_id)
+    }
+
+    /// Returns the information gathered on the track currently parsed.
+    /// `None` if no track is currently parsed.
+    pub fn get_current_track(&self) -> Option<&TrackContext> {
+        self.current_track_id.and_then(|id| self.tracks.get(&id))
+    }
+
+    /// Returns a mutable reference to the information gathered on the track
+    /// currently parsed.
+    /// `None` if no track is currently parsed.
+    pub fn get_current_track_mut(&mut self) -> Option<&mut TrackContext> {
+        let tracks = &mut self.tracks;
+        self.current_track_id.and_then(move |id| tracks.get_mut(&id))
+    }
+
+    /// Returns the timescale of the media of the track currently parsed.
+    /// `None` if it is unknown.
+    pub fn get_current_track_timescale(&self) -> Option<u32> {
+        self.get_current_track().and_then(|t| t.timescale)
+    }
+
+    pub fn get_sidx_references(&self) -> &[(u64, u64)] {
+        &self.sidx_references
+    }
+
+    pub fn set_sidx_references(&mut self, references: Vec<(u64, u64)>) {
+        self.sidx_references = references;
+    }
+
+    pub fn get_level_assignments(&self) -> &[LevelAssignment] {
+        &self.level_assignments
+    }
+
+    pub fn set_level_assignments(&mut self, levels: Vec<LevelAssignment>) {
+        self.level_assignments = levels;
+    }
+
+    pub fn get_metadata_keys(&self) -> &[String] {
+        &self.metadata_keys
+    }
+
+    pub fn set_metadata_keys(&mut self, keys: Vec<String>) {
+        self.metadata_keys = keys;
+    }
+
+    /// Returns the coding name of the track currently parsed.
+    /// `None` if it is unknown.
+    pub fn get_current_track_codec(&self) -> Option<&str> {
+        self.get_current_track().and_then(|t| t.codec.as_deref())
+    }
+}
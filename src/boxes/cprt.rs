@@ -0,0 +1,66 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+    utils::decode_text,
+};
+
+pub struct Cprt {
+    version: u8,
+    flags: Flags,
+    language: u16,
+    notice: String,
+    /// The notice as encoded in the box.
+    notice_data: Vec<u8>,
+}
+
+impl IsoBoxParser for Cprt {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let language = reader.read_u16()? & 0x7FFF;
+        let data = match content_size {
+            Some(size) => reader.read_bytes(size.saturating_sub(6) as usize)?,
+            None => reader.read_to_end()?,
+        };
+        let notice = decode_text(&data);
+        Ok(Self { version, flags, language, notice, notice_data: data })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("language", BoxValue::Language(self.language)),
+            ("notice", BoxValue::from(self.notice.as_str())),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "cprt"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Cprt {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_u16(self.language)?;
+        writer.write_bytes(&self.notice_data)
+    }
+}
@@ -0,0 +1,115 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub struct CttsEntry {
+    sample_count: u32,
+
+    // Unsigned in version 0 and signed in version 1, see `TrunSample`
+    sample_offset: i64,
+}
+
+pub struct Ctts {
+    version: u8,
+    flags: Flags,
+    entry_count: u32,
+    entries: Vec<CttsEntry>,
+}
+
+impl Ctts {
+    /// Create a `ctts` box from the composition offset of each sample.
+    /// Version 1 is only used if one of them is negative.
+    pub fn new(offsets: &[i64]) -> Self {
+        let mut entries: Vec<CttsEntry> = vec![];
+        for offset in offsets {
+            match entries.last_mut() {
+                Some(entry) if entry.sample_offset == *offset => entry.sample_count += 1,
+                _ => entries.push(CttsEntry { sample_count: 1, sample_offset: *offset }),
+            }
+        }
+        let version = if offsets.iter().any(|o| *o < 0) { 1 } else { 0 };
+        Self {
+            version,
+            flags: Flags::from(0),
+            entry_count: entries.len() as u32,
+            entries,
+        }
+    }
+
+    /// Returns the difference between the composition and decoding times of
+    /// each sample, in order.
+    /// As for `Stts::get_sample_durations`, the entries are walked lazily.
+    pub fn get_composition_offsets(&self) -> impl Iterator<Item = i64> + '_ {
+        self.entries.iter()
+            .flat_map(|entry| (0..entry.sample_count).map(move |_| entry.sample_offset))
+    }
+}
+
+impl IsoBoxParser for Ctts {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let entry_count = reader.read_u32()?;
+        let mut entries = Vec::with_capacity(entry_count as usize);
+        for _ in 0..entry_count {
+            let sample_count = reader.read_u32()?;
+            let sample_offset = if version == 0 {
+                reader.read_u32()? as i64
+            } else {
+                reader.read_i32()? as i64
+            };
+            entries.push(CttsEntry { sample_count, sample_offset });
+        }
+        Ok(Self { version, flags, entry_count, entries })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("entry_count", BoxValue::from(self.entry_count)),
+            ("entries", BoxValue::Collection(
+                    self.entries.iter().map(|entry| {
+                        vec![
+                            ("sample_count", BoxValue::from(entry.sample_count)),
+                            ("sample_offset", BoxValue::from(entry.sample_offset)),
+                        ]
+                    }).collect()
+            )),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "ctts"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Ctts {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_u32(self.entry_count)?;
+        for entry in self.entries.iter() {
+            writer.write_u32(entry.sample_count)?;
+            writer.write_u32(entry.sample_offset as u32)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+/// Parameters of an AC-3 stream or of an E-AC-3 independent substream, as
+/// announced by its sample entry.
+#[derive(Clone, Copy)]
+pub struct Ac3StreamInfo {
+    pub fscod: u8,
+    pub bsid: u8,
+    pub bsmod: u8,
+    pub acmod: u8,
+    pub lfeon: bool,
+}
+
+impl Ac3StreamInfo {
+    pub fn get_values(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("fscod", BoxValue::from(self.fscod)),
+            ("bsid", BoxValue::from(self.bsid)),
+            ("bsmod", BoxValue::from(self.bsmod)),
+            ("acmod", BoxValue::from(self.acmod)),
+            ("lfeon", BoxValue::from(self.lfeon)),
+        ]
+    }
+}
+
+pub struct Dac3 {
+    stream_info: Ac3StreamInfo,
+    bit_rate_code: u8,
+}
+
+impl Dac3 {
+    pub fn get_stream_info(&self) -> Ac3StreamInfo {
+        self.stream_info
+    }
+}
+
+impl IsoBoxParser for Dac3 {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let bytes = reader.read_bytes(3)?;
+        let bits = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        let stream_info = Ac3StreamInfo {
+            fscod: (bits >> 22) as u8 & 0x03,
+            bsid: (bits >> 17) as u8 & 0x1F,
+            bsmod: (bits >> 14) as u8 & 0x07,
+            acmod: (bits >> 11) as u8 & 0x07,
+            lfeon: (bits >> 10) & 0x01 != 0,
+        };
+        let bit_rate_code = (bits >> 5) as u8 & 0x1F;
+        Ok(Self { stream_info, bit_rate_code })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        let mut values = self.stream_info.get_values();
+        values.push(("bit_rate_code", BoxValue::from(self.bit_rate_code)));
+        values
+    }
+
+    fn get_short_name() -> &'static str {
+        "dac3"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Dac3 {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        let info = &self.stream_info;
+        let bits = (info.fscod as u32) << 22 |
+            (info.bsid as u32) << 17 |
+            (info.bsmod as u32) << 14 |
+            (info.acmod as u32) << 11 |
+            (info.lfeon as u32) << 10 |
+            (self.bit_rate_code as u32) << 5;
+        writer.write_bytes(&bits.to_be_bytes()[1..])
+    }
+}
@@ -0,0 +1,128 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+    dac3::Ac3StreamInfo,
+};
+
+pub struct IndependentSubstream {
+    stream_info: Ac3StreamInfo,
+    asvc: bool,
+    num_dep_sub: u8,
+
+    /// Only present when there are dependent substreams.
+    chan_loc: Option<u16>,
+}
+
+pub struct Dec3 {
+    data_rate: u16,
+    num_ind_sub: u8,
+    substreams: Vec<IndependentSubstream>,
+    /// Optional extension fields, kept as is.
+    extension_data: Vec<u8>,
+}
+
+impl Dec3 {
+    /// Returns the parameters of the first independent substream.
+    pub fn get_stream_info(&self) -> Option<Ac3StreamInfo> {
+        self.substreams.first().map(|substream| substream.stream_info)
+    }
+}
+
+impl IsoBoxParser for Dec3 {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let value = reader.read_u16()?;
+        let data_rate = value >> 3;
+        let num_ind_sub = (value & 0x07) as u8 + 1;
+        let mut substreams = Vec::with_capacity(num_ind_sub as usize);
+        let mut parsed_size = 2;
+        for _ in 0..num_ind_sub {
+            let bytes = reader.read_bytes(3)?;
+            parsed_size += 3;
+            let bits = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+            let num_dep_sub = (bits >> 1) as u8 & 0x0F;
+            let chan_loc = if num_dep_sub > 0 {
+                parsed_size += 1;
+                Some((((bits & 0x01) as u16) << 8) | reader.read_u8()? as u16)
+            } else {
+                None
+            };
+            substreams.push(IndependentSubstream {
+                stream_info: Ac3StreamInfo {
+                    fscod: (bits >> 22) as u8 & 0x03,
+                    bsid: (bits >> 17) as u8 & 0x1F,
+                    bsmod: (bits >> 12) as u8 & 0x07,
+                    acmod: (bits >> 9) as u8 & 0x07,
+                    lfeon: (bits >> 8) & 0x01 != 0,
+                },
+                asvc: (bits >> 15) & 0x01 != 0,
+                num_dep_sub,
+                chan_loc,
+            });
+        }
+        // optional extension fields, such as Atmos (JOC) signaling
+        let extension_data = match content_size {
+            Some(size) => reader.read_bytes(size.saturating_sub(parsed_size) as usize)?,
+            None => vec![],
+        };
+        Ok(Self { data_rate, num_ind_sub, substreams, extension_data })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("data_rate", BoxValue::from(self.data_rate)),
+            ("num_ind_sub", BoxValue::from(self.num_ind_sub)),
+            ("substreams", BoxValue::Collection(
+                    self.substreams.iter().map(|substream| {
+                        let mut values = substream.stream_info.get_values();
+                        values.push(("asvc", BoxValue::from(substream.asvc)));
+                        values.push(("num_dep_sub", BoxValue::from(substream.num_dep_sub)));
+                        if let Some(chan_loc) = substream.chan_loc {
+                            values.push(("chan_loc", BoxValue::from(chan_loc)));
+                        }
+                        values
+                    }).collect()
+            )),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "dec3"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Dec3 {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u16(self.data_rate << 3 | (self.num_ind_sub.saturating_sub(1) & 0x07) as u16)?;
+        for substream in self.substreams.iter() {
+            let info = &substream.stream_info;
+            let bits = (info.fscod as u32) << 22 |
+                (info.bsid as u32) << 17 |
+                (substream.asvc as u32) << 15 |
+                (info.bsmod as u32) << 12 |
+                (info.acmod as u32) << 9 |
+                (info.lfeon as u32) << 8 |
+                (substream.num_dep_sub as u32) << 1 |
+                substream.chan_loc.map_or(0, |chan_loc| (chan_loc >> 8) as u32 & 0x01);
+            writer.write_bytes(&bits.to_be_bytes()[1..])?;
+            if let Some(chan_loc) = substream.chan_loc {
+                writer.write_u8(chan_loc as u8)?;
+            }
+        }
+        writer.write_bytes(&self.extension_data)
+    }
+}
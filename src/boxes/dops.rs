@@ -0,0 +1,108 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+/// Channel mapping table, present for channel mapping families other than 0.
+pub struct OpusChannelMapping {
+    stream_count: u8,
+    coupled_count: u8,
+    channel_mapping: Vec<u8>,
+}
+
+pub struct Dops {
+    version: u8,
+    output_channel_count: u8,
+    pre_skip: u16,
+    input_sample_rate: u32,
+    output_gain: i16,
+    channel_mapping_family: u8,
+    channel_mapping: Option<OpusChannelMapping>,
+}
+
+impl Dops {
+    pub fn get_output_channel_count(&self) -> u8 {
+        self.output_channel_count
+    }
+}
+
+impl IsoBoxParser for Dops {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let output_channel_count = reader.read_u8()?;
+        let pre_skip = reader.read_u16()?;
+        let input_sample_rate = reader.read_u32()?;
+        let output_gain = reader.read_u16()? as i16;
+        let channel_mapping_family = reader.read_u8()?;
+        let channel_mapping = if channel_mapping_family != 0 {
+            let stream_count = reader.read_u8()?;
+            let coupled_count = reader.read_u8()?;
+            let channel_mapping = reader.read_bytes(output_channel_count as usize)?;
+            Some(OpusChannelMapping { stream_count, coupled_count, channel_mapping })
+        } else {
+            None
+        };
+        Ok(Self {
+            version,
+            output_channel_count,
+            pre_skip,
+            input_sample_rate,
+            output_gain,
+            channel_mapping_family,
+            channel_mapping,
+        })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        let mut values = vec![
+            ("version", BoxValue::from(self.version)),
+            ("output_channel_count", BoxValue::from(self.output_channel_count)),
+            ("pre_skip", BoxValue::from(self.pre_skip)),
+            ("input_sample_rate", BoxValue::from(self.input_sample_rate)),
+            ("output_gain", BoxValue::FixedPoint8_8(self.output_gain)),
+            ("channel_mapping_family", BoxValue::from(self.channel_mapping_family)),
+        ];
+        if let Some(mapping) = &self.channel_mapping {
+            values.push(("stream_count", BoxValue::from(mapping.stream_count)));
+            values.push(("coupled_count", BoxValue::from(mapping.coupled_count)));
+            values.push(("channel_mapping", BoxValue::from(mapping.channel_mapping.as_slice())));
+        }
+        values
+    }
+
+    fn get_short_name() -> &'static str {
+        "dOps"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Dops {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_u8(self.output_channel_count)?;
+        writer.write_u16(self.pre_skip)?;
+        writer.write_u32(self.input_sample_rate)?;
+        writer.write_u16(self.output_gain as u16)?;
+        writer.write_u8(self.channel_mapping_family)?;
+        if let Some(mapping) = &self.channel_mapping {
+            writer.write_u8(mapping.stream_count)?;
+            writer.write_u8(mapping.coupled_count)?;
+            writer.write_bytes(&mapping.channel_mapping)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use std::io::{BufRead, Seek};
+use std::rc::Rc;
+use super::{
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxInfo,
+
+    IsoBoxParser,
+    IsoBoxSerializer,
+
+    utils::parse_children,
+};
+
+pub struct Edts {
+    content: Vec<super::IsoBoxData>,
+}
+
+impl Edts {
+    pub fn get_content(&self) -> &[super::IsoBoxData] {
+        &self.content
+    }
+}
+
+impl IsoBoxParser for Edts {
+    fn parse<T: BufRead + Seek>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        box_info: &Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let content = parse_children(reader, content_size, Some(box_info))?;
+        Ok(Self { content })
+    }
+
+    fn get_inner_boxes_mut(&mut self) -> Option<&mut Vec<super::IsoBoxData>> {
+        Some(&mut self.content)
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<super::ContainedBoxInfo<'_>>> {
+        Some(self.content.iter().map(|c|
+                (c.0.as_ref(), c.1.as_ref().map(|boxed| { std::boxed::Box::as_ref(boxed) }))
+        ).collect())
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![]
+    }
+
+    fn get_short_name() -> &'static str {
+        "edts"
+    }
+}
+
+impl IsoBoxSerializer for Edts {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_boxes(&self.content)
+    }
+}
@@ -0,0 +1,71 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub struct Elng {
+    version: u8,
+    flags: Flags,
+
+    /// RFC 4646 (BCP 47) language tag, e.g. "en-US".
+    extended_language: String,
+    /// Number of null characters terminating the string.
+    terminator_length: usize,
+}
+
+impl IsoBoxParser for Elng {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let mut extended_language = if let Some(size) = content_size {
+            reader.read_str(size.saturating_sub(4) as usize)?
+        } else {
+            String::from_utf8(reader.read_to_end()?)?
+        };
+
+        // The string is null-terminated
+        let mut terminator_length = 0;
+        while extended_language.ends_with('\0') {
+            extended_language.pop();
+            terminator_length += 1;
+        }
+        Ok(Self { version, flags, extended_language, terminator_length })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("extended_language", BoxValue::from(self.extended_language.as_str())),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "elng"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Elng {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_bytes(self.extended_language.as_bytes())?;
+        writer.write_bytes(&vec![0; self.terminator_length])
+    }
+}
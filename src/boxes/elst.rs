@@ -0,0 +1,197 @@
+use std::io::BufRead;
+use std::rc::Rc;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    FlagNames,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub struct ElstEntry {
+    segment_duration: u64,
+    media_time: i64,
+    media_rate: i32,
+}
+
+pub struct Elst {
+    version: u8,
+    flags: Flags,
+    entry_count: u32,
+    entries: Vec<ElstEntry>,
+
+    /// Timescale of the whole presentation, in which `segment_duration` is
+    /// expressed.
+    movie_timescale: Option<u32>,
+}
+
+const FLAG_NAMES: FlagNames = &[
+    (0x000001, "repeat-edits"),
+];
+
+/// A segment of a track's presentation timeline, resulting from an edit.
+pub struct EditSegment {
+    /// Start and end of the segment on the presentation timeline, in the
+    /// movie timescale.
+    presentation_start: u64,
+    presentation_end: u64,
+
+    /// Start and end of the corresponding media, in the media timescale.
+    /// `None` for empty edits.
+    media_range: Option<(i64, i64)>,
+    media_rate: i32,
+
+    movie_timescale: u32,
+    media_timescale: u32,
+}
+
+impl EditSegment {
+    pub fn get_values(&self) -> Vec<(&'static str, BoxValue)> {
+        let mut values = vec![
+            ("presentation_start",
+             BoxValue::Ticks(self.presentation_start, self.movie_timescale)),
+            ("presentation_end",
+             BoxValue::Ticks(self.presentation_end, self.movie_timescale)),
+        ];
+        match self.media_range {
+            Some((start, end)) => {
+                values.push(("media_start", BoxValue::SignedTicks(start, self.media_timescale)));
+                values.push(("media_end", BoxValue::SignedTicks(end, self.media_timescale)));
+                values.push(("media_rate", BoxValue::FixedPoint16_16(self.media_rate)));
+            },
+            None => {
+                values.push(("empty_edit", BoxValue::from(true)));
+            },
+        }
+        values
+    }
+}
+
+impl Elst {
+    /// Map the presentation timeline of the track to its media timeline, by
+    /// applying each edit in order.
+    /// Returns an empty vector if one of the timescales is unknown.
+    pub fn derive_timeline(
+        &self,
+        movie_timescale: Option<u32>,
+        media_timescale: Option<u32>
+    ) -> Vec<EditSegment> {
+        let (movie_timescale, media_timescale) = match (movie_timescale, media_timescale) {
+            (Some(movie), Some(media)) if movie > 0 && media > 0 => (movie, media),
+            _ => return vec![],
+        };
+        let mut presentation_start = 0u64;
+        self.entries.iter().map(|entry| {
+            let presentation_end = presentation_start.saturating_add(entry.segment_duration);
+            let media_range = if entry.media_time == -1 {
+                None
+            } else {
+                // Duration of media consumed, in the media timescale
+                let media_duration = entry.segment_duration as i128
+                    * media_timescale as i128
+                    * entry.media_rate as i128
+                    / movie_timescale as i128
+                    / 65536;
+                Some((entry.media_time, (entry.media_time as i128 + media_duration) as i64))
+            };
+            let segment = EditSegment {
+                presentation_start,
+                presentation_end,
+                media_range,
+                media_rate: entry.media_rate,
+                movie_timescale,
+                media_timescale,
+            };
+            presentation_start = presentation_end;
+            segment
+        }).collect()
+    }
+}
+
+impl IsoBoxParser for Elst {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        box_info: &Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        if version > 1 {
+            return Err(BoxParsingError::InvalidVersion {
+                box_info: Rc::clone(box_info),
+                expected: vec![0, 1],
+                actual: version,
+            });
+        }
+        let flags = Flags::read(reader)?;
+        let entry_count = reader.read_u32()?;
+        let mut entries = Vec::with_capacity(entry_count as usize);
+        for _ in 0..entry_count {
+            let (segment_duration, media_time) = if version == 1 {
+                (reader.read_u64()?, reader.read_u64()? as i64)
+            } else {
+                (reader.read_u32()? as u64, reader.read_i32()? as i64)
+            };
+            let media_rate = reader.read_i32()?;
+            entries.push(ElstEntry { segment_duration, media_time, media_rate });
+        }
+
+        let movie_timescale = reader.get_context().get_movie_timescale();
+        Ok(Self { version, flags, entry_count, entries, movie_timescale })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("entry_count", BoxValue::from(self.entry_count)),
+            ("entries", BoxValue::Collection(
+                    self.entries.iter().map(|entry| {
+                        vec![
+                            ("segment_duration",
+                             BoxValue::from_ticks(entry.segment_duration, self.movie_timescale)),
+                            // The media timescale is only known once the
+                            // `mdhd` following this box is parsed
+                            ("media_time", BoxValue::from(entry.media_time)),
+                            ("media_rate", BoxValue::FixedPoint16_16(entry.media_rate)),
+                        ]
+                    }).collect()
+            )),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "elst"
+    }
+
+    fn get_flag_names() -> FlagNames {
+        FLAG_NAMES
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Elst {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_u32(self.entry_count)?;
+        for entry in self.entries.iter() {
+            if self.version == 1 {
+                writer.write_u64(entry.segment_duration)?;
+                writer.write_u64(entry.media_time as u64)?;
+            } else {
+                writer.write_u32(entry.segment_duration as u32)?;
+                writer.write_i32(entry.media_time as i32)?;
+            }
+            writer.write_i32(entry.media_rate)?;
+        }
+        Ok(())
+    }
+}
@@ -40,6 +40,18 @@ pub enum BoxParsingError {
         actual: u8,
     },
 
+    /// A field of the box has a value not allowed by the specification, which
+    /// prevents from parsing the rest of it.
+    #[error("invalid value for {field}: {value}")]
+    InvalidFieldValue {
+        /// The box in which the error happened
+        box_info: Rc<IsoBoxInfo>,
+        /// Name of the field.
+        field: &'static str,
+        /// Its value.
+        value: u64,
+    },
+
     /// The size for the current box is too small to be properly
     /// parsed.
     #[error("data store discaonnected")]
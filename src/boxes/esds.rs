@@ -0,0 +1,239 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+/// Start of an MPEG-4 audio `AudioSpecificConfig`.
+pub struct AudioSpecificConfig {
+    audio_object_type: u8,
+    sampling_frequency_index: u8,
+
+    /// Only set when `sampling_frequency_index` is `0xF`.
+    sampling_frequency: Option<u32>,
+    channel_configuration: u8,
+}
+
+impl AudioSpecificConfig {
+    fn parse(data: &[u8]) -> Option<Self> {
+        // The fields needed fit in the first 8 bytes
+        let mut bytes = [0u8; 8];
+        let available_bytes = data.len().min(8);
+        bytes[..available_bytes].copy_from_slice(&data[..available_bytes]);
+        let bits = u64::from_be_bytes(bytes);
+        let mut position = 0;
+        let mut read = |nb_bits: u32| -> Option<u32> {
+            if position + nb_bits > 8 * available_bytes as u32 {
+                return None;
+            }
+            let value = (bits << position) >> (64 - nb_bits);
+            position += nb_bits;
+            Some(value as u32)
+        };
+        let mut audio_object_type = read(5)? as u8;
+        if audio_object_type == 31 {
+            audio_object_type = 32 + read(6)? as u8;
+        }
+        let sampling_frequency_index = read(4)? as u8;
+        let sampling_frequency = if sampling_frequency_index == 0xF {
+            Some(read(24)?)
+        } else {
+            None
+        };
+        let channel_configuration = read(4)? as u8;
+        Some(Self {
+            audio_object_type,
+            sampling_frequency_index,
+            sampling_frequency,
+            channel_configuration,
+        })
+    }
+}
+
+/// Returns the name of an MPEG-4 audio object type.
+fn get_audio_object_type_name(audio_object_type: u8) -> Option<&'static str> {
+    match audio_object_type {
+        1 => Some("AAC Main"),
+        2 => Some("AAC LC"),
+        3 => Some("AAC SSR"),
+        4 => Some("AAC LTP"),
+        5 => Some("SBR"),
+        6 => Some("AAC Scalable"),
+        23 => Some("ER AAC LD"),
+        29 => Some("PS"),
+        39 => Some("ER AAC ELD"),
+        42 => Some("USAC"),
+        _ => None,
+    }
+}
+
+pub struct Esds {
+    version: u8,
+    flags: Flags,
+    es_id: u16,
+    stream_priority: u8,
+    object_type_indication: u8,
+    stream_type: u8,
+    buffer_size_db: u32,
+    max_bitrate: u32,
+    avg_bitrate: u32,
+    decoder_specific_info: Vec<u8>,
+    audio_specific_config: Option<AudioSpecificConfig>,
+
+    /// The descriptors as encoded in the box, as their lengths may be coded
+    /// on more bytes than needed.
+    descriptors: Vec<u8>,
+}
+
+impl Esds {
+    /// Returns the `channelConfiguration` of the `AudioSpecificConfig`.
+    /// `None` if the stream is not MPEG-4 audio.
+    pub fn get_channel_configuration(&self) -> Option<u8> {
+        self.audio_specific_config.as_ref().map(|config| config.channel_configuration)
+    }
+}
+
+/// Read the tag and the size of the descriptor at the start of `data`,
+/// returning them with the size of that header.
+fn read_descriptor_header(data: &[u8]) -> Option<(u8, usize, usize)> {
+    let tag = *data.first()?;
+    let mut size = 0;
+    for i in 1..5 {
+        let byte = *data.get(i)?;
+        size = (size << 7) | (byte & 0x7F) as usize;
+        if byte & 0x80 == 0 {
+            return Some((tag, size, i + 1));
+        }
+    }
+    None
+}
+
+/// Returns the content of each descriptor found in `data`, with its tag.
+fn split_descriptors(mut data: &[u8]) -> Vec<(u8, &[u8])> {
+    let mut descriptors = vec![];
+    while let Some((tag, size, header_size)) = read_descriptor_header(data) {
+        let end = (header_size + size).min(data.len());
+        descriptors.push((tag, &data[header_size..end]));
+        data = &data[end..];
+    }
+    descriptors
+}
+
+impl IsoBoxParser for Esds {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let data = match content_size {
+            Some(size) => reader.read_bytes(size.saturating_sub(4) as usize)?,
+            None => reader.read_to_end()?,
+        };
+        let mut esds = Self {
+            version,
+            flags,
+            es_id: 0,
+            stream_priority: 0,
+            object_type_indication: 0,
+            stream_type: 0,
+            buffer_size_db: 0,
+            max_bitrate: 0,
+            avg_bitrate: 0,
+            decoder_specific_info: vec![],
+            audio_specific_config: None,
+            descriptors: vec![],
+        };
+        let es_descriptor = match split_descriptors(&data).into_iter().find(|d| d.0 == 0x03) {
+            Some((_, content)) if content.len() >= 3 => content,
+            _ => return Err(BoxParsingError::InvalidFieldValue {
+                box_info: box_info.clone(),
+                field: "ES_Descriptor",
+                value: data.first().copied().unwrap_or(0) as u64,
+            }),
+        };
+        esds.es_id = u16::from_be_bytes([es_descriptor[0], es_descriptor[1]]);
+        let es_flags = es_descriptor[2];
+        esds.stream_priority = es_flags & 0x1F;
+        let mut offset = 3;
+        if es_flags & 0x80 != 0 {
+            offset += 2;
+        }
+        if es_flags & 0x40 != 0 {
+            offset += 1 + es_descriptor.get(offset).copied().unwrap_or(0) as usize;
+        }
+        if es_flags & 0x20 != 0 {
+            offset += 2;
+        }
+        let sub_descriptors = split_descriptors(es_descriptor.get(offset..).unwrap_or_default());
+        if let Some((_, config)) = sub_descriptors.into_iter().find(|d| d.0 == 0x04) {
+            if config.len() >= 13 {
+                esds.object_type_indication = config[0];
+                esds.stream_type = config[1] >> 2;
+                esds.buffer_size_db = u32::from_be_bytes([0, config[2], config[3], config[4]]);
+                esds.max_bitrate = u32::from_be_bytes([config[5], config[6], config[7], config[8]]);
+                esds.avg_bitrate = u32::from_be_bytes([config[9], config[10], config[11], config[12]]);
+                if let Some((_, info)) = split_descriptors(&config[13..]).into_iter().find(|d| d.0 == 0x05) {
+                    esds.decoder_specific_info = info.to_vec();
+                }
+                // MPEG-4 audio
+                if esds.object_type_indication == 0x40 {
+                    esds.audio_specific_config = AudioSpecificConfig::parse(&esds.decoder_specific_info);
+                }
+            }
+        }
+        esds.descriptors = data;
+        Ok(esds)
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        let mut values = vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("es_id", BoxValue::from(self.es_id)),
+            ("stream_priority", BoxValue::from(self.stream_priority)),
+            ("object_type_indication", BoxValue::from(self.object_type_indication)),
+            ("stream_type", BoxValue::from(self.stream_type)),
+            ("buffer_size_db", BoxValue::from(self.buffer_size_db)),
+            ("max_bitrate", BoxValue::from(self.max_bitrate)),
+            ("avg_bitrate", BoxValue::from(self.avg_bitrate)),
+            ("decoder_specific_info", BoxValue::Hex(&self.decoder_specific_info)),
+        ];
+        if let Some(config) = &self.audio_specific_config {
+            values.push(("audio_object_type", BoxValue::from(config.audio_object_type)));
+            if let Some(name) = get_audio_object_type_name(config.audio_object_type) {
+                values.push(("audio_object_type_name", BoxValue::from(name)));
+            }
+            values.push(("sampling_frequency_index", BoxValue::from(config.sampling_frequency_index)));
+            if let Some(frequency) = config.sampling_frequency {
+                values.push(("sampling_frequency", BoxValue::from(frequency)));
+            }
+            values.push(("channel_configuration", BoxValue::from(config.channel_configuration)));
+        }
+        values
+    }
+
+    fn get_short_name() -> &'static str {
+        "esds"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Esds {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_bytes(&self.descriptors)
+    }
+}
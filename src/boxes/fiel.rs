@@ -0,0 +1,63 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+/// Field handling of interlaced video, as defined by QuickTime.
+pub struct Fiel {
+    fields: u8,
+    detail: u8,
+}
+
+impl IsoBoxParser for Fiel {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let fields = reader.read_u8()?;
+        let detail = reader.read_u8()?;
+        Ok(Self { fields, detail })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        let mut values = vec![
+            ("fields", BoxValue::from(self.fields)),
+            ("detail", BoxValue::from(self.detail)),
+        ];
+        let field_order = match (self.fields, self.detail) {
+            (1, _) => Some("progressive"),
+            (2, 1) => Some("separated, top field first"),
+            (2, 6) => Some("separated, bottom field first"),
+            (2, 9) => Some("interleaved, top field first"),
+            (2, 14) => Some("interleaved, bottom field first"),
+            _ => None,
+        };
+        if let Some(val) = field_order {
+            values.push(("field_order", BoxValue::from(val)));
+        }
+        values
+    }
+
+    fn get_short_name() -> &'static str {
+        "fiel"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Fiel {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.fields)?;
+        writer.write_u8(self.detail)
+    }
+}
@@ -0,0 +1,53 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub struct Frma {
+    data_format: String,
+}
+
+impl Frma {
+    /// Returns the coding name of the sample entry before it was protected.
+    pub fn get_data_format(&self) -> &str {
+        &self.data_format
+    }
+}
+
+impl IsoBoxParser for Frma {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let data_format = reader.read_4cc()?;
+        Ok(Self { data_format })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("data_format", BoxValue::from(self.data_format.as_str())),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "frma"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Frma {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_4cc(&self.data_format)
+    }
+}
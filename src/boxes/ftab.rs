@@ -0,0 +1,75 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+    utils::decode_text,
+};
+
+pub struct FontRecord {
+    font_id: u16,
+    font_name: String,
+    /// The font name as encoded in the box.
+    font_name_data: Vec<u8>,
+}
+
+pub struct Ftab {
+    entry_count: u16,
+    fonts: Vec<FontRecord>,
+}
+
+impl IsoBoxParser for Ftab {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let entry_count = reader.read_u16()?;
+        let mut fonts = Vec::with_capacity(entry_count as usize);
+        for _ in 0..entry_count {
+            let font_id = reader.read_u16()?;
+            let font_name_length = reader.read_u8()?;
+            let font_name_data = reader.read_bytes(font_name_length as usize)?;
+            let font_name = decode_text(&font_name_data);
+            fonts.push(FontRecord { font_id, font_name, font_name_data });
+        }
+        Ok(Self { entry_count, fonts })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("entry_count", BoxValue::from(self.entry_count)),
+            ("fonts", BoxValue::Collection(
+                    self.fonts.iter().map(|font| vec![
+                        ("font_id", BoxValue::from(font.font_id)),
+                        ("font_name", BoxValue::from(font.font_name.as_str())),
+                    ]).collect()
+            )),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "ftab"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Ftab {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u16(self.entry_count)?;
+        for font in self.fonts.iter() {
+            writer.write_u16(font.font_id)?;
+            writer.write_u8(font.font_name_data.len() as u8)?;
+            writer.write_bytes(&font.font_name_data)?;
+        }
+        Ok(())
+    }
+}
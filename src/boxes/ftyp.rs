@@ -4,9 +4,11 @@ use super::{
     IsoBoxInfo,
     BoxParsingError,
     BoxReader,
+    BoxWriter,
     BoxValue,
     IsoBoxEntry,
     IsoBoxParser,
+    IsoBoxSerializer,
 };
 
 pub struct Ftyp {
@@ -78,15 +80,18 @@ impl IsoBoxParser for Ftyp {
         "ftyp"
     }
 
-    fn get_long_name() -> &'static str {
-        "File Type Box"
-    }
-
-    fn get_inner_boxes(self) -> Option<Vec<super::IsoBoxData>> {
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
         None
     }
+}
 
-    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
-        None
+impl IsoBoxSerializer for Ftyp {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_bytes(self.major_brand.as_bytes())?;
+        writer.write_u32(self.minor_brand)?;
+        for brand in self.compatible_brands.iter() {
+            writer.write_bytes(brand.as_bytes())?;
+        }
+        Ok(())
     }
 }
@@ -4,19 +4,24 @@ use super::{
     IsoBoxInfo,
     BoxParsingError,
     BoxReader,
+    BoxWriter,
     BoxValue,
     Flags,
     IsoBoxEntry,
     IsoBoxParser,
+    IsoBoxSerializer,
+    utils::decode_text,
 };
 
 pub struct Hdlr {
     version: u8,
     flags: Flags,
     pre_defined: u32,
-    handler_type: u32,
-    reserved: [u8; 3],
+    handler_type: String,
+    reserved: [u32; 3],
     name: String,
+    /// The name as encoded in the box.
+    name_data: Vec<u8>,
 }
 
 impl IsoBoxParser for Hdlr {
@@ -35,14 +40,15 @@ impl IsoBoxParser for Hdlr {
         }
         let flags = Flags::read(reader)?;
         let pre_defined = reader.read_u32()?;
-        let handler_type = reader.read_u32()?;
-        let reserved = [reader.read_u8()?, reader.read_u8()?, reader.read_u8()?];
-        let name = if let Some(size) = content_size {
-            reader.read_str(size as usize)?
+        let handler_type = reader.read_4cc()?;
+        let reserved = [reader.read_u32()?, reader.read_u32()?, reader.read_u32()?];
+        let name_data = if let Some(size) = content_size {
+            reader.read_bytes(size.saturating_sub(24) as usize)?
         } else {
-            String::from_utf8(reader.read_to_end()?)?
+            reader.read_to_end()?
         };
-        Ok(Self { version, flags, handler_type, pre_defined, reserved, name })
+        let name = decode_text(&name_data);
+        Ok(Self { version, flags, handler_type, pre_defined, reserved, name, name_data })
     }
 
     fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
@@ -50,7 +56,7 @@ impl IsoBoxParser for Hdlr {
             ("version", BoxValue::from(self.version)),
             ("flags", BoxValue::from(self.flags)),
             ("pre_defined", BoxValue::from(self.pre_defined)),
-            ("handler_type", BoxValue::from(self.handler_type)),
+            ("handler_type", BoxValue::from(self.handler_type.as_str())),
             ("reserved", BoxValue::from(self.reserved.as_ref())),
             ("name", BoxValue::from(self.name.as_str()))
         ]
@@ -60,15 +66,20 @@ impl IsoBoxParser for Hdlr {
         "hdlr"
     }
 
-    fn get_long_name() -> &'static str {
-        "Handler Reference Box"
-    }
-
-    fn get_inner_boxes(self) -> Option<Vec<super::IsoBoxData>> {
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
         None
     }
+}
 
-    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
-        None
+impl IsoBoxSerializer for Hdlr {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_u32(self.pre_defined)?;
+        writer.write_4cc(&self.handler_type)?;
+        for reserved in self.reserved.iter() {
+            writer.write_u32(*reserved)?;
+        }
+        writer.write_bytes(&self.name_data)
     }
 }
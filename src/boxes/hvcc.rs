@@ -0,0 +1,210 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub struct HvccNalArray {
+    array_completeness: bool,
+    nal_unit_type: u8,
+    num_nalus: u16,
+    nal_units: Vec<Vec<u8>>,
+}
+
+pub struct Hvcc {
+    configuration_version: u8,
+    general_profile_space: u8,
+    general_tier_flag: bool,
+    general_profile_idc: u8,
+    general_profile_compatibility_flags: u32,
+    general_constraint_indicator_flags: u64,
+    general_level_idc: u8,
+    min_spatial_segmentation_idc: u16,
+    parallelism_type: u8,
+    chroma_format_idc: u8,
+    bit_depth_luma_minus8: u8,
+    bit_depth_chroma_minus8: u8,
+    avg_frame_rate: u16,
+    constant_frame_rate: u8,
+    num_temporal_layers: u8,
+    temporal_id_nested: bool,
+    length_size_minus_one: u8,
+    num_of_arrays: u8,
+    arrays: Vec<HvccNalArray>,
+}
+
+impl Hvcc {
+    /// Returns the size in bytes of the length field preceding each NAL unit
+    /// in the samples.
+    pub fn get_nal_length_size(&self) -> u8 {
+        self.length_size_minus_one + 1
+    }
+
+    /// Returns the NAL units of every array, parameter sets and SEI.
+    pub fn get_nal_units(&self) -> Vec<&[u8]> {
+        self.arrays
+            .iter()
+            .flat_map(|array| array.nal_units.iter().map(|nal_unit| nal_unit.as_slice()))
+            .collect()
+    }
+}
+
+impl IsoBoxParser for Hvcc {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let configuration_version = reader.read_u8()?;
+        let byte = reader.read_u8()?;
+        let general_profile_space = byte >> 6;
+        let general_tier_flag = byte & 0x20 != 0;
+        let general_profile_idc = byte & 0x1F;
+        let general_profile_compatibility_flags = reader.read_u32()?;
+        let general_constraint_indicator_flags =
+            ((reader.read_u16()? as u64) << 32) | reader.read_u32()? as u64;
+        let general_level_idc = reader.read_u8()?;
+        let min_spatial_segmentation_idc = reader.read_u16()? & 0x0FFF;
+        let parallelism_type = reader.read_u8()? & 0x03;
+        let chroma_format_idc = reader.read_u8()? & 0x03;
+        let bit_depth_luma_minus8 = reader.read_u8()? & 0x07;
+        let bit_depth_chroma_minus8 = reader.read_u8()? & 0x07;
+        let avg_frame_rate = reader.read_u16()?;
+        let byte = reader.read_u8()?;
+        let constant_frame_rate = byte >> 6;
+        let num_temporal_layers = (byte >> 3) & 0x07;
+        let temporal_id_nested = byte & 0x04 != 0;
+        let length_size_minus_one = byte & 0x03;
+        let num_of_arrays = reader.read_u8()?;
+        let mut arrays = Vec::with_capacity(num_of_arrays as usize);
+        for _ in 0..num_of_arrays {
+            let byte = reader.read_u8()?;
+            let num_nalus = reader.read_u16()?;
+            let mut nal_units = Vec::with_capacity(num_nalus as usize);
+            for _ in 0..num_nalus {
+                let nal_unit_length = reader.read_u16()?;
+                nal_units.push(reader.read_bytes(nal_unit_length as usize)?);
+            }
+            arrays.push(HvccNalArray {
+                array_completeness: byte & 0x80 != 0,
+                nal_unit_type: byte & 0x3F,
+                num_nalus,
+                nal_units,
+            });
+        }
+        Ok(Self {
+            configuration_version,
+            general_profile_space,
+            general_tier_flag,
+            general_profile_idc,
+            general_profile_compatibility_flags,
+            general_constraint_indicator_flags,
+            general_level_idc,
+            min_spatial_segmentation_idc,
+            parallelism_type,
+            chroma_format_idc,
+            bit_depth_luma_minus8,
+            bit_depth_chroma_minus8,
+            avg_frame_rate,
+            constant_frame_rate,
+            num_temporal_layers,
+            temporal_id_nested,
+            length_size_minus_one,
+            num_of_arrays,
+            arrays,
+        })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("configuration_version", BoxValue::from(self.configuration_version)),
+            ("general_profile_space", BoxValue::from(self.general_profile_space)),
+            ("general_tier_flag", BoxValue::from(self.general_tier_flag)),
+            ("general_profile_idc", BoxValue::from(self.general_profile_idc)),
+            ("general_profile_compatibility_flags",
+             BoxValue::from(self.general_profile_compatibility_flags)),
+            ("general_constraint_indicator_flags",
+             BoxValue::from(self.general_constraint_indicator_flags)),
+            ("general_level_idc", BoxValue::from(self.general_level_idc)),
+            ("min_spatial_segmentation_idc", BoxValue::from(self.min_spatial_segmentation_idc)),
+            ("parallelism_type", BoxValue::from(self.parallelism_type)),
+            ("chroma_format_idc", BoxValue::from(self.chroma_format_idc)),
+            ("bit_depth_luma_minus8", BoxValue::from(self.bit_depth_luma_minus8)),
+            ("bit_depth_chroma_minus8", BoxValue::from(self.bit_depth_chroma_minus8)),
+            ("avg_frame_rate", BoxValue::from(self.avg_frame_rate)),
+            ("constant_frame_rate", BoxValue::from(self.constant_frame_rate)),
+            ("num_temporal_layers", BoxValue::from(self.num_temporal_layers)),
+            ("temporal_id_nested", BoxValue::from(self.temporal_id_nested)),
+            ("length_size_minus_one", BoxValue::from(self.length_size_minus_one)),
+            ("num_of_arrays", BoxValue::from(self.num_of_arrays)),
+            ("arrays", BoxValue::Collection(
+                    self.arrays.iter().map(|array| {
+                        vec![
+                            ("array_completeness", BoxValue::from(array.array_completeness)),
+                            ("nal_unit_type", BoxValue::from(array.nal_unit_type)),
+                            ("num_nalus", BoxValue::from(array.num_nalus)),
+                            ("nal_units", BoxValue::Collection(
+                                    array.nal_units.iter().map(|nal_unit| {
+                                        vec![
+                                            ("nal_unit_length",
+                                             BoxValue::from(nal_unit.len() as u16)),
+                                            ("nal_unit", BoxValue::Hex(nal_unit)),
+                                        ]
+                                    }).collect()
+                            )),
+                        ]
+                    }).collect()
+            )),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "hvcC"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Hvcc {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.configuration_version)?;
+        writer.write_u8(
+            self.general_profile_space << 6 |
+            (self.general_tier_flag as u8) << 5 |
+            self.general_profile_idc)?;
+        writer.write_u32(self.general_profile_compatibility_flags)?;
+        writer.write_u16((self.general_constraint_indicator_flags >> 32) as u16)?;
+        writer.write_u32(self.general_constraint_indicator_flags as u32)?;
+        writer.write_u8(self.general_level_idc)?;
+        // reserved bits are all set to 1
+        writer.write_u16(0xF000 | self.min_spatial_segmentation_idc)?;
+        writer.write_u8(0xFC | self.parallelism_type)?;
+        writer.write_u8(0xFC | self.chroma_format_idc)?;
+        writer.write_u8(0xF8 | self.bit_depth_luma_minus8)?;
+        writer.write_u8(0xF8 | self.bit_depth_chroma_minus8)?;
+        writer.write_u16(self.avg_frame_rate)?;
+        writer.write_u8(
+            self.constant_frame_rate << 6 |
+            self.num_temporal_layers << 3 |
+            (self.temporal_id_nested as u8) << 2 |
+            self.length_size_minus_one)?;
+        writer.write_u8(self.num_of_arrays)?;
+        for array in self.arrays.iter() {
+            writer.write_u8((array.array_completeness as u8) << 7 | array.nal_unit_type)?;
+            writer.write_u16(array.num_nalus)?;
+            for nal_unit in array.nal_units.iter() {
+                writer.write_u16(nal_unit.len() as u16)?;
+                writer.write_bytes(nal_unit)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+use std::io::{BufRead, Seek};
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub struct Idat {
+    data: Vec<u8>,
+
+    /// Offset in the file of the first byte of `data`.
+    data_offset: u64,
+}
+
+impl Idat {
+    pub fn get_data_offset(&self) -> u64 {
+        self.data_offset
+    }
+
+    pub fn get_data_size(&self) -> u64 {
+        self.data.len() as u64
+    }
+}
+
+impl IsoBoxParser for Idat {
+    fn parse<T: BufRead + Seek>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let data_offset = reader.get_pos()?;
+        let data = match content_size {
+            Some(size) => reader.read_bytes(size as usize)?,
+            None => reader.read_to_end()?,
+        };
+        Ok(Self { data, data_offset })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("data", BoxValue::Hex(&self.data)),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "idat"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Idat {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_bytes(&self.data)
+    }
+}
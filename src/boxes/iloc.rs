@@ -0,0 +1,320 @@
+use std::io::BufRead;
+use std::rc::Rc;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub struct IlocExtent {
+    item_reference_index: Option<u64>,
+    extent_offset: u64,
+    extent_length: u64,
+}
+
+pub struct IlocItem {
+    item_id: u32,
+    construction_method: Option<u8>,
+    data_reference_index: u16,
+    base_offset: u64,
+    extent_count: u16,
+    extents: Vec<IlocExtent>,
+}
+
+impl IlocItem {
+    pub fn get_item_id(&self) -> u32 {
+        self.item_id
+    }
+
+    /// Returns the construction method: 0 for file offsets, 1 for offsets in
+    /// the `idat` box, 2 for offsets in another item.
+    pub fn get_construction_method(&self) -> u8 {
+        self.construction_method.unwrap_or(0)
+    }
+
+    /// Returns the offset and length of each extent, relative to the start of
+    /// the data designated by the construction method.
+    /// A length of `0` means that the extent goes until the end of that data.
+    /// `None` if an offset does not fit in 64 bits.
+    pub fn get_extents(&self) -> Option<Vec<(u64, u64)>> {
+        self.extents
+            .iter()
+            .map(|e| Some((self.base_offset.checked_add(e.extent_offset)?, e.extent_length)))
+            .collect()
+    }
+}
+
+pub struct Iloc {
+    version: u8,
+    flags: Flags,
+    offset_size: u8,
+    length_size: u8,
+    base_offset_size: u8,
+    index_size: Option<u8>,
+    item_count: u32,
+    items: Vec<IlocItem>,
+}
+
+/// Offset fields of an item whose data is in this file, each given as its
+/// position relative to the start of the `iloc` box's content, its size and
+/// its value.
+pub struct IlocFileOffsets {
+    /// `None` if `base_offset_size` is `0`.
+    pub base_offset: Option<(u64, u8, u64)>,
+    /// Empty if `offset_size` is `0`.
+    pub extent_offsets: Vec<(u64, u8, u64)>,
+}
+
+impl Iloc {
+    pub fn get_items(&self) -> &[IlocItem] {
+        &self.items
+    }
+
+    /// Returns the offset fields of each item whose data is found at file
+    /// offsets (construction method 0) in this file (data reference index 0).
+    pub fn get_file_offsets(&self) -> Vec<IlocFileOffsets> {
+        let index_size = self.index_size.unwrap_or(0) as u64;
+        let extent_size = index_size + self.offset_size as u64 + self.length_size as u64;
+        // Version, flags, sizes and item count
+        let mut position = if self.version < 2 { 8 } else { 10 };
+        let mut offsets = vec![];
+        for item in self.items.iter() {
+            position += if self.version < 2 { 2 } else { 4 };
+            if item.construction_method.is_some() {
+                position += 2;
+            }
+            // Data reference index
+            position += 2;
+            let base_offset_position = position;
+            // Base offset and extent count
+            position += self.base_offset_size as u64 + 2;
+            let extents_position = position;
+            position += item.extents.len() as u64 * extent_size;
+            if item.get_construction_method() != 0 || item.data_reference_index != 0 {
+                continue;
+            }
+            let base_offset = if self.base_offset_size > 0 {
+                Some((base_offset_position, self.base_offset_size, item.base_offset))
+            } else {
+                None
+            };
+            let extent_offsets = if self.offset_size > 0 {
+                item.extents.iter().enumerate().map(|(i, extent)| {
+                    let extent_position = extents_position + i as u64 * extent_size + index_size;
+                    (extent_position, self.offset_size, extent.extent_offset)
+                }).collect()
+            } else {
+                vec![]
+            };
+            offsets.push(IlocFileOffsets { base_offset, extent_offsets });
+        }
+        offsets
+    }
+}
+
+/// Read an unsigned integer whose size in bytes is given by one of the size
+/// fields of the `iloc` box.
+fn read_sized_value<T: BufRead>(
+    reader: &mut BoxReader<T>,
+    size: u8,
+    field: &'static str,
+    box_info: &Rc<IsoBoxInfo>
+) -> Result<u64, BoxParsingError> {
+    match size {
+        0 => Ok(0),
+        4 => Ok(reader.read_u32()? as u64),
+        8 => Ok(reader.read_u64()?),
+        _ => Err(BoxParsingError::InvalidFieldValue {
+            box_info: Rc::clone(box_info),
+            field,
+            value: size as u64,
+        }),
+    }
+}
+
+/// Write a value on `size` bytes, `size` being either 0, 4 or 8.
+fn write_sized_value(writer: &mut BoxWriter, size: u8, value: u64) -> Result<(), std::io::Error> {
+    match size {
+        0 => Ok(()),
+        4 => writer.write_u32(value as u32),
+        _ => writer.write_u64(value),
+    }
+}
+
+impl IsoBoxParser for Iloc {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        box_info: &Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        if version > 2 {
+            return Err(BoxParsingError::InvalidVersion {
+                box_info: Rc::clone(box_info),
+                expected: vec![0, 1, 2],
+                actual: version,
+            });
+        }
+        let flags = Flags::read(reader)?;
+        let sizes = reader.read_u16()?;
+        let offset_size = (sizes >> 12) as u8;
+        let length_size = ((sizes >> 8) & 0x0F) as u8;
+        let base_offset_size = ((sizes >> 4) & 0x0F) as u8;
+        let index_size = if version >= 1 {
+            Some((sizes & 0x0F) as u8)
+        } else {
+            None
+        };
+        let item_count = if version < 2 {
+            reader.read_u16()? as u32
+        } else {
+            reader.read_u32()?
+        };
+
+        let mut items = Vec::with_capacity(item_count as usize);
+        for _ in 0..item_count {
+            let item_id = if version < 2 {
+                reader.read_u16()? as u32
+            } else {
+                reader.read_u32()?
+            };
+            let construction_method = if version >= 1 {
+                Some((reader.read_u16()? & 0x0F) as u8)
+            } else {
+                None
+            };
+            let data_reference_index = reader.read_u16()?;
+            let base_offset = read_sized_value(
+                reader, base_offset_size, "base_offset_size", box_info)?;
+            let extent_count = reader.read_u16()?;
+            let mut extents = Vec::with_capacity(extent_count as usize);
+            for _ in 0..extent_count {
+                let item_reference_index = match index_size {
+                    Some(size) if size > 0 =>
+                        Some(read_sized_value(reader, size, "index_size", box_info)?),
+                    _ => None,
+                };
+                let extent_offset = read_sized_value(
+                    reader, offset_size, "offset_size", box_info)?;
+                let extent_length = read_sized_value(
+                    reader, length_size, "length_size", box_info)?;
+                extents.push(IlocExtent { item_reference_index, extent_offset, extent_length });
+            }
+            items.push(IlocItem {
+                item_id,
+                construction_method,
+                data_reference_index,
+                base_offset,
+                extent_count,
+                extents,
+            });
+        }
+        Ok(Self {
+            version,
+            flags,
+            offset_size,
+            length_size,
+            base_offset_size,
+            index_size,
+            item_count,
+            items,
+        })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        let mut values = vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("offset_size", BoxValue::from(self.offset_size)),
+            ("length_size", BoxValue::from(self.length_size)),
+            ("base_offset_size", BoxValue::from(self.base_offset_size)),
+        ];
+        if let Some(val) = self.index_size {
+            values.push(("index_size", BoxValue::from(val)));
+        }
+        values.push(("item_count", BoxValue::from(self.item_count)));
+        values.push(
+            ("items", BoxValue::Collection(
+                    self.items.iter().map(|item| {
+                        let mut item_values = vec![
+                            ("item_id", BoxValue::from(item.item_id)),
+                        ];
+                        if let Some(val) = item.construction_method {
+                            item_values.push(("construction_method", BoxValue::from(val)));
+                        }
+                        item_values.push(("data_reference_index",
+                                          BoxValue::from(item.data_reference_index)));
+                        item_values.push(("base_offset", BoxValue::from(item.base_offset)));
+                        item_values.push(("extent_count", BoxValue::from(item.extent_count)));
+                        item_values.push(("extents", BoxValue::Collection(
+                                    item.extents.iter().map(|extent| {
+                                        let mut extent_values = vec![];
+                                        if let Some(val) = extent.item_reference_index {
+                                            extent_values.push(
+                                                ("item_reference_index", BoxValue::from(val)));
+                                        }
+                                        extent_values.push(
+                                            ("extent_offset", BoxValue::from(extent.extent_offset)));
+                                        extent_values.push(
+                                            ("extent_length", BoxValue::from(extent.extent_length)));
+                                        extent_values
+                                    }).collect()
+                        )));
+                        item_values
+                    }).collect()
+            )));
+        values
+    }
+
+    fn get_short_name() -> &'static str {
+        "iloc"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Iloc {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_u16(
+            (self.offset_size as u16) << 12 |
+            (self.length_size as u16) << 8 |
+            (self.base_offset_size as u16) << 4 |
+            self.index_size.unwrap_or(0) as u16)?;
+        if self.version < 2 {
+            writer.write_u16(self.item_count as u16)?;
+        } else {
+            writer.write_u32(self.item_count)?;
+        }
+        for item in self.items.iter() {
+            if self.version < 2 {
+                writer.write_u16(item.item_id as u16)?;
+            } else {
+                writer.write_u32(item.item_id)?;
+            }
+            if let Some(construction_method) = item.construction_method {
+                writer.write_u16(construction_method as u16)?;
+            }
+            writer.write_u16(item.data_reference_index)?;
+            write_sized_value(writer, self.base_offset_size, item.base_offset)?;
+            writer.write_u16(item.extent_count)?;
+            for extent in item.extents.iter() {
+                if let (Some(size), Some(index)) = (self.index_size, extent.item_reference_index) {
+                    write_sized_value(writer, size, index)?;
+                }
+                write_sized_value(writer, self.offset_size, extent.extent_offset)?;
+                write_sized_value(writer, self.length_size, extent.extent_length)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,382 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+    utils::decode_text,
+};
+
+/// Value of a `data` atom, decoded according to its well-known type.
+pub enum MetadataValue {
+    Text(String),
+    Integer(i64),
+    Unsigned(u64),
+
+    /// ID3v1 genre (`gnre`), as a zero-based index.
+    Genre(u16),
+
+    /// Track (`trkn`) or disc (`disk`) number, out of a total.
+    IndexOfTotal {
+        index: u16,
+        total: u16,
+    },
+
+    /// Cover art. Only its format and size are kept.
+    Image {
+        format: &'static str,
+        size: u64,
+    },
+
+    /// Any other type, whose content is kept as is.
+    Binary(Vec<u8>),
+}
+
+impl MetadataValue {
+    fn decode(item_type: &str, data_type: u32, data: Vec<u8>) -> Self {
+        match data_type {
+            1 => MetadataValue::Text(decode_text(&data)),
+            2 => {
+                let units: Vec<u16> = data
+                    .chunks_exact(2)
+                    .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                    .collect();
+                MetadataValue::Text(String::from_utf16_lossy(&units))
+            },
+            13 => MetadataValue::Image { format: "JPEG", size: data.len() as u64 },
+            14 => MetadataValue::Image { format: "PNG", size: data.len() as u64 },
+            27 => MetadataValue::Image { format: "BMP", size: data.len() as u64 },
+            21 | 65 | 66 | 67 | 74 => match read_integer(&data) {
+                Some(val) => {
+                    // sign-extend from the actual integer size
+                    let shift = 64 - 8 * data.len() as u32;
+                    MetadataValue::Integer(((val << shift) as i64) >> shift)
+                },
+                None => MetadataValue::Binary(data),
+            },
+            22 | 75 | 76 | 77 | 78 => match read_integer(&data) {
+                Some(val) => MetadataValue::Unsigned(val),
+                None => MetadataValue::Binary(data),
+            },
+            23 if data.len() == 4 => MetadataValue::Text(
+                f32::from_be_bytes([data[0], data[1], data[2], data[3]]).to_string()),
+            24 if data.len() == 8 => {
+                let mut bytes = [0u8; 8];
+                bytes.copy_from_slice(&data);
+                MetadataValue::Text(f64::from_be_bytes(bytes).to_string())
+            },
+            0 if (item_type == "trkn" || item_type == "disk") && data.len() >= 6 =>
+                MetadataValue::IndexOfTotal {
+                    index: u16::from_be_bytes([data[2], data[3]]),
+                    total: u16::from_be_bytes([data[4], data[5]]),
+                },
+            // ID3v1 genre, stored off by one
+            0 if item_type == "gnre" && data.len() == 2 =>
+                match u16::from_be_bytes([data[0], data[1]]).checked_sub(1) {
+                    Some(genre) => MetadataValue::Genre(genre),
+                    None => MetadataValue::Binary(data),
+                },
+            _ => MetadataValue::Binary(data),
+        }
+    }
+
+    pub fn get_values(&self) -> Vec<(&'static str, BoxValue)> {
+        match self {
+            MetadataValue::Text(text) => vec![("value", BoxValue::from(text.as_str()))],
+            MetadataValue::Integer(val) => vec![("value", BoxValue::from(*val))],
+            MetadataValue::Unsigned(val) => vec![("value", BoxValue::from(*val))],
+            MetadataValue::Genre(genre) => {
+                let mut values = vec![("genre_id", BoxValue::from(*genre))];
+                if let Some(name) = get_id3_genre_name(*genre) {
+                    values.push(("genre", BoxValue::from(name)));
+                }
+                values
+            },
+            MetadataValue::IndexOfTotal { index, total } => vec![
+                ("number", BoxValue::from(*index)),
+                ("total", BoxValue::from(*total)),
+            ],
+            MetadataValue::Image { format, size } => vec![
+                ("image_format", BoxValue::from(*format)),
+                ("image_size", BoxValue::from(*size)),
+            ],
+            MetadataValue::Binary(data) => vec![("value", BoxValue::Hex(data))],
+        }
+    }
+}
+
+/// Read a big-endian integer of 1, 2, 3, 4 or 8 bytes.
+fn read_integer(data: &[u8]) -> Option<u64> {
+    match data.len() {
+        1 | 2 | 3 | 4 | 8 => Some(data.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64)),
+        _ => None,
+    }
+}
+
+/// Returns the name of one of the original ID3v1 genres.
+fn get_id3_genre_name(genre: u16) -> Option<&'static str> {
+    const GENRES: [&str; 80] = [
+        "Blues", "Classic Rock", "Country", "Dance", "Disco", "Funk", "Grunge", "Hip-Hop",
+        "Jazz", "Metal", "New Age", "Oldies", "Other", "Pop", "R&B", "Rap", "Reggae", "Rock",
+        "Techno", "Industrial", "Alternative", "Ska", "Death Metal", "Pranks", "Soundtrack",
+        "Euro-Techno", "Ambient", "Trip-Hop", "Vocal", "Jazz+Funk", "Fusion", "Trance",
+        "Classical", "Instrumental", "Acid", "House", "Game", "Sound Clip", "Gospel", "Noise",
+        "AlternRock", "Bass", "Soul", "Punk", "Space", "Meditative", "Instrumental Pop",
+        "Instrumental Rock", "Ethnic", "Gothic", "Darkwave", "Techno-Industrial", "Electronic",
+        "Pop-Folk", "Eurodance", "Dream", "Southern Rock", "Comedy", "Cult", "Gangsta", "Top 40",
+        "Christian Rap", "Pop/Funk", "Jungle", "Native American", "Cabaret", "New Wave",
+        "Psychadelic", "Rave", "Showtunes", "Trailer", "Lo-Fi", "Tribal", "Acid Punk",
+        "Acid Jazz", "Polka", "Retro", "Musical", "Rock & Roll", "Hard Rock",
+    ];
+    GENRES.get(genre as usize).copied()
+}
+
+/// Returns the name of a well-known type of a `data` atom.
+fn get_data_type_name(data_type: u32) -> Option<&'static str> {
+    match data_type {
+        0 => Some("implicit"),
+        1 => Some("UTF-8"),
+        2 => Some("UTF-16"),
+        13 => Some("JPEG"),
+        14 => Some("PNG"),
+        21 => Some("signed integer"),
+        22 => Some("unsigned integer"),
+        23 => Some("32-bit float"),
+        24 => Some("64-bit float"),
+        27 => Some("BMP"),
+        28 => Some("QuickTime metadata atom"),
+        65 => Some("8-bit signed integer"),
+        66 => Some("16-bit signed integer"),
+        67 => Some("32-bit signed integer"),
+        74 => Some("64-bit signed integer"),
+        75 => Some("8-bit unsigned integer"),
+        76 => Some("16-bit unsigned integer"),
+        77 => Some("32-bit unsigned integer"),
+        78 => Some("64-bit unsigned integer"),
+        _ => None,
+    }
+}
+
+/// Returns what a well-known iTunes item type describes.
+fn get_item_description(item_type: &str) -> Option<&'static str> {
+    match item_type {
+        "\u{a9}nam" => Some("title"),
+        "\u{a9}ART" => Some("artist"),
+        "aART" => Some("album artist"),
+        "\u{a9}alb" => Some("album"),
+        "\u{a9}day" => Some("date"),
+        "\u{a9}too" => Some("encoder"),
+        "\u{a9}cmt" => Some("comment"),
+        "\u{a9}gen" | "gnre" => Some("genre"),
+        "\u{a9}wrt" => Some("composer"),
+        "\u{a9}grp" => Some("grouping"),
+        "\u{a9}lyr" => Some("lyrics"),
+        "covr" => Some("cover art"),
+        "trkn" => Some("track number"),
+        "disk" => Some("disc number"),
+        "tmpo" => Some("tempo"),
+        "cpil" => Some("compilation"),
+        "cprt" => Some("copyright"),
+        "desc" => Some("description"),
+        "ldes" => Some("long description"),
+        "----" => Some("custom"),
+        _ => None,
+    }
+}
+
+pub struct MetadataData {
+    type_set: u8,
+    data_type: u32,
+    locale: u32,
+    value: MetadataValue,
+}
+
+pub struct MetadataItem {
+    item_size: u32,
+
+    /// Type of the item, interpreted as four characters.
+    item_type: String,
+
+    /// When the `meta` box has a `keys` box, the 1-based index of the key
+    /// this item corresponds to, with that key if found.
+    key: Option<(u32, Option<String>)>,
+
+    /// Content of the `mean` and `name` atoms of custom (`----`) items.
+    mean: Option<String>,
+    name: Option<String>,
+
+    data: Vec<MetadataData>,
+}
+
+pub struct Ilst {
+    items: Vec<MetadataItem>,
+
+    /// The box's content, as encoded.
+    data: Vec<u8>,
+}
+
+/// Parse the items of an `ilst` box, whose content is read by `reader`.
+fn parse_items<T: BufRead>(
+    reader: &mut BoxReader<T>,
+    content_size: Option<u64>,
+    keys: &[String]
+) -> Result<Vec<MetadataItem>, std::io::Error> {
+    let mut items = vec![];
+    let mut remaining = content_size;
+    while remaining.map_or(!reader.is_empty()?, |r| r >= 8) {
+        let item_size = reader.read_u32()?;
+        let item_type_bytes = reader.read_bytes(4)?;
+        let item_type: String = item_type_bytes.iter().map(|b| *b as char).collect();
+        let item_content_size = match remaining {
+            Some(r) => (item_size as u64).clamp(8, r) - 8,
+            None => (item_size as u64).max(8) - 8,
+        };
+        remaining = remaining.map(|r| r - 8 - item_content_size);
+
+        let key = if keys.is_empty() {
+            None
+        } else {
+            let index = u32::from_be_bytes([
+                item_type_bytes[0], item_type_bytes[1],
+                item_type_bytes[2], item_type_bytes[3]]);
+            let key = index.checked_sub(1)
+                .and_then(|i| keys.get(i as usize))
+                .cloned();
+            Some((index, key))
+        };
+
+        let mut item = MetadataItem {
+            item_size,
+            item_type,
+            key,
+            mean: None,
+            name: None,
+            data: vec![],
+        };
+        let mut item_remaining = item_content_size;
+        while item_remaining >= 8 {
+            let atom_size = reader.read_u32()? as u64;
+            let atom_name = reader.read_4cc()?;
+            let atom_content_size = atom_size.clamp(8, item_remaining) - 8;
+            item_remaining -= 8 + atom_content_size;
+            match atom_name.as_str() {
+                "data" if atom_content_size >= 8 => {
+                    let type_indicator = reader.read_u32()?;
+                    let locale = reader.read_u32()?;
+                    let data = reader.read_bytes(atom_content_size as usize - 8)?;
+                    let data_type = type_indicator & 0x00FFFFFF;
+                    item.data.push(MetadataData {
+                        type_set: (type_indicator >> 24) as u8,
+                        data_type,
+                        locale,
+                        value: MetadataValue::decode(&item.item_type, data_type, data),
+                    });
+                },
+                "mean" | "name" if atom_content_size >= 4 => {
+                    // skip version and flags
+                    reader.read_u32()?;
+                    let text = decode_text(
+                        &reader.read_bytes(atom_content_size as usize - 4)?);
+                    if atom_name == "mean" {
+                        item.mean = Some(text);
+                    } else {
+                        item.name = Some(text);
+                    }
+                },
+                _ => {
+                    reader.read_bytes(atom_content_size as usize)?;
+                },
+            }
+        }
+        reader.read_bytes(item_remaining as usize)?;
+        items.push(item);
+    }
+    if let Some(r) = remaining {
+        reader.read_bytes(r as usize)?;
+    }
+    Ok(items)
+}
+
+impl IsoBoxParser for Ilst {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        // The content is kept as is, as only part of it is decoded
+        let data = match content_size {
+            Some(size) => reader.read_bytes(size as usize)?,
+            None => reader.read_to_end()?,
+        };
+        let items = parse_items(
+            &mut BoxReader::create(data.as_slice()),
+            Some(data.len() as u64),
+            reader.get_context().get_metadata_keys())?;
+        Ok(Self { items, data })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("items", BoxValue::Collection(
+                    self.items.iter().map(|item| {
+                        let mut values = vec![
+                            ("item_size", BoxValue::from(item.item_size)),
+                        ];
+                        match &item.key {
+                            Some((index, key)) => {
+                                values.push(("key_index", BoxValue::from(*index)));
+                                if let Some(key) = key {
+                                    values.push(("key", BoxValue::from(key.as_str())));
+                                }
+                            },
+                            None => {
+                                values.push(("item_type", BoxValue::from(item.item_type.as_str())));
+                                if let Some(description) = get_item_description(&item.item_type) {
+                                    values.push(("description", BoxValue::from(description)));
+                                }
+                            },
+                        }
+                        if let Some(mean) = &item.mean {
+                            values.push(("mean", BoxValue::from(mean.as_str())));
+                        }
+                        if let Some(name) = &item.name {
+                            values.push(("name", BoxValue::from(name.as_str())));
+                        }
+                        values.push(("data", BoxValue::Collection(
+                                    item.data.iter().map(|data| {
+                                        let mut data_values = vec![
+                                            ("type_set", BoxValue::from(data.type_set)),
+                                            ("type", BoxValue::from(data.data_type)),
+                                        ];
+                                        if let Some(name) = get_data_type_name(data.data_type) {
+                                            data_values.push(("type_name", BoxValue::from(name)));
+                                        }
+                                        data_values.push(("locale", BoxValue::from(data.locale)));
+                                        data_values.append(&mut data.value.get_values());
+                                        data_values
+                                    }).collect()
+                        )));
+                        values
+                    }).collect()
+            )),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "ilst"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Ilst {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_bytes(&self.data)
+    }
+}
@@ -0,0 +1,52 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub struct Imir {
+    axis: u8,
+}
+
+impl IsoBoxParser for Imir {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let axis = reader.read_u8()? & 0x01;
+        Ok(Self { axis })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        let mirroring = if self.axis == 0 {
+            "vertical axis (left-right flip)"
+        } else {
+            "horizontal axis (top-bottom flip)"
+        };
+        vec![
+            ("axis", BoxValue::from(self.axis)),
+            ("mirroring", BoxValue::from(mirroring)),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "imir"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Imir {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.axis)
+    }
+}
@@ -0,0 +1,169 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    FlagNames,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub struct Infe {
+    version: u8,
+    flags: Flags,
+    item_id: u32,
+    item_protection_index: u16,
+
+    /// Type of the item, only present from version 2.
+    item_type: Option<String>,
+    item_name: String,
+    content_type: Option<String>,
+    content_encoding: Option<String>,
+    item_uri_type: Option<String>,
+
+    /// The null-terminated strings following the item type, as encoded.
+    strings_data: Vec<u8>,
+}
+
+const FLAG_NAMES: FlagNames = &[
+    (0x000001, "hidden"),
+];
+
+impl Infe {
+    pub fn get_item_id(&self) -> u32 {
+        self.item_id
+    }
+
+    /// Returns the type of the item.
+    /// Items described by a version 0 or 1 `infe` have no type, they are
+    /// then considered as MIME items.
+    pub fn get_item_type(&self) -> &str {
+        self.item_type.as_deref().unwrap_or("mime")
+    }
+
+    pub fn get_item_name(&self) -> &str {
+        &self.item_name
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        u32::from(self.flags) & 0x01 != 0
+    }
+}
+
+impl IsoBoxParser for Infe {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let mut header_size = 4;
+        let item_id = if version == 3 {
+            header_size += 4;
+            reader.read_u32()?
+        } else {
+            header_size += 2;
+            reader.read_u16()? as u32
+        };
+        let item_protection_index = reader.read_u16()?;
+        header_size += 2;
+        let item_type = if version >= 2 {
+            header_size += 4;
+            Some(reader.read_4cc()?)
+        } else {
+            None
+        };
+
+        // All remaining fields are null-terminated strings
+        let remaining = match content_size {
+            Some(size) => reader.read_bytes(size.saturating_sub(header_size) as usize)?,
+            None => reader.read_to_end()?,
+        };
+        let mut strings = remaining
+            .split(|b| *b == 0)
+            .map(|s| String::from_utf8_lossy(s).into_owned());
+        let item_name = strings.next().unwrap_or_default();
+        let mut content_type = None;
+        let mut content_encoding = None;
+        let mut item_uri_type = None;
+        match item_type.as_deref() {
+            None | Some("mime") => {
+                content_type = strings.next();
+                content_encoding = strings.next().filter(|s| !s.is_empty());
+            },
+            Some("uri ") => {
+                item_uri_type = strings.next();
+            },
+            _ => {},
+        }
+        Ok(Self {
+            version,
+            flags,
+            item_id,
+            item_protection_index,
+            item_type,
+            item_name,
+            content_type,
+            content_encoding,
+            item_uri_type,
+            strings_data: remaining,
+        })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        let mut values = vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("item_id", BoxValue::from(self.item_id)),
+            ("item_protection_index", BoxValue::from(self.item_protection_index)),
+        ];
+        if let Some(val) = &self.item_type {
+            values.push(("item_type", BoxValue::from(val.as_str())));
+        }
+        values.push(("item_name", BoxValue::from(self.item_name.as_str())));
+        if let Some(val) = &self.content_type {
+            values.push(("content_type", BoxValue::from(val.as_str())));
+        }
+        if let Some(val) = &self.content_encoding {
+            values.push(("content_encoding", BoxValue::from(val.as_str())));
+        }
+        if let Some(val) = &self.item_uri_type {
+            values.push(("item_uri_type", BoxValue::from(val.as_str())));
+        }
+        values
+    }
+
+    fn get_short_name() -> &'static str {
+        "infe"
+    }
+
+    fn get_flag_names() -> FlagNames {
+        FLAG_NAMES
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Infe {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        if self.version == 3 {
+            writer.write_u32(self.item_id)?;
+        } else {
+            writer.write_u16(self.item_id as u16)?;
+        }
+        writer.write_u16(self.item_protection_index)?;
+        if let Some(item_type) = &self.item_type {
+            writer.write_4cc(item_type)?;
+        }
+        writer.write_bytes(&self.strings_data)
+    }
+}
@@ -0,0 +1,63 @@
+use std::io::{BufRead, Seek};
+use std::rc::Rc;
+use super::{
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxInfo,
+
+    IsoBoxParser,
+    IsoBoxSerializer,
+
+    utils::parse_children,
+};
+
+pub struct Ipco {
+    content: Vec<super::IsoBoxData>,
+}
+
+impl Ipco {
+    /// Returns the short name of the property at the given 1-based index.
+    pub fn get_property_name(&self, index: u16) -> Option<&str> {
+        if index == 0 {
+            return None;
+        }
+        self.content.get(index as usize - 1).map(|c| c.0.short_name.as_str())
+    }
+}
+
+impl IsoBoxParser for Ipco {
+    fn parse<T: BufRead + Seek>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        box_info: &Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let content = parse_children(reader, content_size, Some(box_info))?;
+        Ok(Self { content })
+    }
+
+    fn get_inner_boxes_mut(&mut self) -> Option<&mut Vec<super::IsoBoxData>> {
+        Some(&mut self.content)
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<super::ContainedBoxInfo<'_>>> {
+        Some(self.content.iter().map(|c|
+                (c.0.as_ref(), c.1.as_ref().map(|boxed| { std::boxed::Box::as_ref(boxed) }))
+        ).collect())
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![]
+    }
+
+    fn get_short_name() -> &'static str {
+        "ipco"
+    }
+}
+
+impl IsoBoxSerializer for Ipco {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_boxes(&self.content)
+    }
+}
@@ -0,0 +1,144 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+#[derive(Clone, Copy)]
+pub struct PropertyAssociation {
+    pub essential: bool,
+
+    /// 1-based index of the property in the `ipco` box, `0` meaning that no
+    /// property is associated.
+    pub property_index: u16,
+}
+
+pub struct IpmaEntry {
+    item_id: u32,
+    association_count: u8,
+    associations: Vec<PropertyAssociation>,
+}
+
+pub struct Ipma {
+    version: u8,
+    flags: Flags,
+    entry_count: u32,
+    entries: Vec<IpmaEntry>,
+}
+
+impl Ipma {
+    /// Returns the properties associated to the given item.
+    pub fn get_associations(&self, item_id: u32) -> Vec<PropertyAssociation> {
+        self.entries
+            .iter()
+            .filter(|e| e.item_id == item_id)
+            .flat_map(|e| e.associations.iter().copied())
+            .collect()
+    }
+}
+
+impl IsoBoxParser for Ipma {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let has_large_indexes = u32::from(flags) & 0x01 != 0;
+        let entry_count = reader.read_u32()?;
+        let mut entries = Vec::with_capacity(entry_count as usize);
+        for _ in 0..entry_count {
+            let item_id = if version < 1 {
+                reader.read_u16()? as u32
+            } else {
+                reader.read_u32()?
+            };
+            let association_count = reader.read_u8()?;
+            let mut associations = Vec::with_capacity(association_count as usize);
+            for _ in 0..association_count {
+                let association = if has_large_indexes {
+                    let val = reader.read_u16()?;
+                    PropertyAssociation {
+                        essential: val & 0x8000 != 0,
+                        property_index: val & 0x7FFF,
+                    }
+                } else {
+                    let val = reader.read_u8()?;
+                    PropertyAssociation {
+                        essential: val & 0x80 != 0,
+                        property_index: (val & 0x7F) as u16,
+                    }
+                };
+                associations.push(association);
+            }
+            entries.push(IpmaEntry { item_id, association_count, associations });
+        }
+        Ok(Self { version, flags, entry_count, entries })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("entry_count", BoxValue::from(self.entry_count)),
+            ("entries", BoxValue::Collection(
+                    self.entries.iter().map(|entry| {
+                        vec![
+                            ("item_id", BoxValue::from(entry.item_id)),
+                            ("association_count", BoxValue::from(entry.association_count)),
+                            ("associations", BoxValue::Collection(
+                                    entry.associations.iter().map(|association| {
+                                        vec![
+                                            ("essential", BoxValue::from(association.essential)),
+                                            ("property_index",
+                                             BoxValue::from(association.property_index)),
+                                        ]
+                                    }).collect()
+                            )),
+                        ]
+                    }).collect()
+            )),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "ipma"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Ipma {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        let has_large_indexes = u32::from(self.flags) & 0x01 != 0;
+        writer.write_u32(self.entry_count)?;
+        for entry in self.entries.iter() {
+            if self.version < 1 {
+                writer.write_u16(entry.item_id as u16)?;
+            } else {
+                writer.write_u32(entry.item_id)?;
+            }
+            writer.write_u8(entry.association_count)?;
+            for association in entry.associations.iter() {
+                if has_large_indexes {
+                    writer.write_u16((association.essential as u16) << 15 | association.property_index)?;
+                } else {
+                    writer.write_u8((association.essential as u8) << 7 | association.property_index as u8)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
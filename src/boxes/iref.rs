@@ -0,0 +1,139 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+/// A `SingleItemTypeReferenceBox`: references of a given type from one item
+/// to others.
+pub struct ItemReference {
+    box_size: u32,
+    reference_type: String,
+    from_item_id: u32,
+    reference_count: u16,
+    to_item_ids: Vec<u32>,
+}
+
+impl ItemReference {
+    pub fn get_reference_type(&self) -> &str {
+        &self.reference_type
+    }
+
+    pub fn get_from_item_id(&self) -> u32 {
+        self.from_item_id
+    }
+
+    pub fn get_to_item_ids(&self) -> &[u32] {
+        &self.to_item_ids
+    }
+}
+
+pub struct Iref {
+    version: u8,
+    flags: Flags,
+    references: Vec<ItemReference>,
+}
+
+impl Iref {
+    pub fn get_references(&self) -> &[ItemReference] {
+        &self.references
+    }
+}
+
+impl IsoBoxParser for Iref {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let mut remaining = content_size.map(|size| size.saturating_sub(4));
+        let mut references = vec![];
+        while remaining.map_or(!reader.is_empty()?, |r| r >= 8) {
+            let box_size = reader.read_u32()?;
+            let reference_type = reader.read_4cc()?;
+            let read_id = |reader: &mut BoxReader<T>| -> Result<u32, std::io::Error> {
+                if version == 0 {
+                    Ok(reader.read_u16()? as u32)
+                } else {
+                    reader.read_u32()
+                }
+            };
+            let from_item_id = read_id(reader)?;
+            let reference_count = reader.read_u16()?;
+            let mut to_item_ids = Vec::with_capacity(reference_count as usize);
+            for _ in 0..reference_count {
+                to_item_ids.push(read_id(reader)?);
+            }
+            remaining = remaining.map(|r| r.saturating_sub(box_size as u64));
+            references.push(ItemReference {
+                box_size,
+                reference_type,
+                from_item_id,
+                reference_count,
+                to_item_ids,
+            });
+        }
+        Ok(Self { version, flags, references })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("references", BoxValue::Collection(
+                    self.references.iter().map(|reference| {
+                        vec![
+                            ("box_size", BoxValue::from(reference.box_size)),
+                            ("reference_type",
+                             BoxValue::from(reference.reference_type.as_str())),
+                            ("from_item_id", BoxValue::from(reference.from_item_id)),
+                            ("reference_count", BoxValue::from(reference.reference_count)),
+                            ("to_item_ids", BoxValue::from(reference.to_item_ids.as_slice())),
+                        ]
+                    }).collect()
+            )),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "iref"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Iref {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        let id_size = if self.version == 0 { 2 } else { 4 };
+        let write_id = |writer: &mut BoxWriter, id: u32| -> Result<(), std::io::Error> {
+            if self.version == 0 {
+                writer.write_u16(id as u16)
+            } else {
+                writer.write_u32(id)
+            }
+        };
+        for reference in self.references.iter() {
+            writer.write_u32(10 + id_size * (1 + reference.to_item_ids.len() as u32))?;
+            writer.write_4cc(&reference.reference_type)?;
+            write_id(writer, reference.from_item_id)?;
+            writer.write_u16(reference.reference_count)?;
+            for item_id in reference.to_item_ids.iter() {
+                write_id(writer, *item_id)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub struct Irot {
+    /// Rotation, in anti-clockwise units of 90 degrees.
+    angle: u8,
+}
+
+impl IsoBoxParser for Irot {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let angle = reader.read_u8()? & 0x03;
+        Ok(Self { angle })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("angle", BoxValue::from(self.angle)),
+            ("rotation_degrees", BoxValue::from(self.angle as u16 * 90)),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "irot"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Irot {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.angle)
+    }
+}
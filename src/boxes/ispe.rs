@@ -0,0 +1,59 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub struct Ispe {
+    version: u8,
+    flags: Flags,
+    image_width: u32,
+    image_height: u32,
+}
+
+impl IsoBoxParser for Ispe {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let image_width = reader.read_u32()?;
+        let image_height = reader.read_u32()?;
+        Ok(Self { version, flags, image_width, image_height })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("image_width", BoxValue::from(self.image_width)),
+            ("image_height", BoxValue::from(self.image_height)),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "ispe"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Ispe {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_u32(self.image_width)?;
+        writer.write_u32(self.image_height)
+    }
+}
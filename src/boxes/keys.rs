@@ -0,0 +1,89 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub struct KeysEntry {
+    key_size: u32,
+    key_namespace: String,
+    key_value: String,
+}
+
+pub struct Keys {
+    version: u8,
+    flags: Flags,
+    entry_count: u32,
+    entries: Vec<KeysEntry>,
+}
+
+impl IsoBoxParser for Keys {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let entry_count = reader.read_u32()?;
+        let mut entries = Vec::with_capacity(entry_count as usize);
+        for _ in 0..entry_count {
+            let key_size = reader.read_u32()?;
+            let key_namespace = reader.read_4cc()?;
+            let key_value = reader.read_str(key_size.saturating_sub(8) as usize)?;
+            entries.push(KeysEntry { key_size, key_namespace, key_value });
+        }
+
+        // Items of the following `ilst` are identified by their index in
+        // this table
+        let keys = entries.iter().map(|e| e.key_value.clone()).collect();
+        reader.get_context_mut().set_metadata_keys(keys);
+        Ok(Self { version, flags, entry_count, entries })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("entry_count", BoxValue::from(self.entry_count)),
+            ("entries", BoxValue::Collection(
+                    self.entries.iter().map(|entry| {
+                        vec![
+                            ("key_size", BoxValue::from(entry.key_size)),
+                            ("key_namespace", BoxValue::from(entry.key_namespace.as_str())),
+                            ("key_value", BoxValue::from(entry.key_value.as_str())),
+                        ]
+                    }).collect()
+            )),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "keys"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Keys {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_u32(self.entry_count)?;
+        for entry in self.entries.iter() {
+            writer.write_u32(8 + entry.key_value.len() as u32)?;
+            writer.write_4cc(&entry.key_namespace)?;
+            writer.write_bytes(entry.key_value.as_bytes())?;
+        }
+        Ok(())
+    }
+}
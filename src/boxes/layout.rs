@@ -0,0 +1,213 @@
+use std::convert::TryFrom;
+use super::{
+    IsoBoxData,
+    IsoBoxEntry,
+    IsoBoxInfo,
+    co64::Co64,
+    iloc::Iloc,
+    moof::Moof,
+    saio::Saio,
+    sidx::{ResolvedSidxReference, Sidx},
+    ssix::{Ssix, SubsegmentByteRanges},
+    stco::Stco,
+    tfdt::Tfdt,
+    tfhd::Tfhd,
+    traf::Traf,
+    trun::Trun,
+    utils::collect_boxes,
+};
+
+/// A field of a box which holds an offset in the file, and thus has to be
+/// updated when the data it points to moves.
+pub enum OffsetField {
+    /// Absolute offset in the file: a `stco` or `co64` chunk offset, a
+    /// `tfhd` base data offset, a `saio` offset outside of a movie fragment,
+    /// or an `iloc` base offset or extent offset of an item without a base
+    /// offset.
+    Absolute {
+        /// Offset of the field itself in the file.
+        position: u64,
+        /// Size of the field in bytes, either 4 or 8.
+        size: u8,
+        value: u64,
+    },
+
+    /// `trun` data offset or `saio` offset in a track fragment, relative to
+    /// the base offset of that track fragment, or `iloc` extent offset,
+    /// relative to the base offset of its item.
+    Relative {
+        /// Offset of the field itself in the file.
+        position: u64,
+        /// Size of the field in bytes, either 4 or 8.
+        size: u8,
+        base_offset: u64,
+        value: i64,
+    },
+}
+
+impl OffsetField {
+    /// Returns the offset of the field itself in the file.
+    pub fn get_position(&self) -> u64 {
+        match self {
+            OffsetField::Absolute { position, .. } | OffsetField::Relative { position, .. } =>
+                *position,
+        }
+    }
+}
+
+/// Find every offset field of the given boxes.
+///
+/// The offsets of a track fragment are only reported if its base offset is
+/// explicit, or is the start of the `moof` box. Otherwise, it follows the
+/// data of the previous track fragment and stays valid as long as both move
+/// together.
+pub fn find_offset_fields(results: &[IsoBoxData]) -> Vec<OffsetField> {
+    let mut fields = vec![];
+    let mut stcos: Vec<(&IsoBoxInfo, &Stco)> = vec![];
+    let mut co64s: Vec<(&IsoBoxInfo, &Co64)> = vec![];
+    let mut saios: Vec<(&IsoBoxInfo, &Saio)> = vec![];
+    let mut ilocs: Vec<(&IsoBoxInfo, &Iloc)> = vec![];
+    for (info, entry) in results.iter() {
+        collect_boxes(info, entry.as_deref(), &mut stcos);
+        collect_boxes(info, entry.as_deref(), &mut co64s);
+        collect_boxes(info, entry.as_deref(), &mut ilocs);
+        // Offsets of movie fragments' `saio` boxes are relative
+        if info.short_name != "moof" {
+            collect_boxes(info, entry.as_deref(), &mut saios);
+        }
+    }
+
+    // Chunk offsets are stored at the end of those boxes
+    for (info, stco) in stcos {
+        let offsets = stco.get_chunk_offsets();
+        let start = info.offset + info.size - offsets.len() as u64 * 4;
+        fields.extend(offsets.into_iter().enumerate().map(|(i, value)| OffsetField::Absolute {
+            position: start + i as u64 * 4,
+            size: 4,
+            value,
+        }));
+    }
+    for (info, co64) in co64s {
+        let offsets = co64.get_chunk_offsets();
+        let start = info.offset + info.size - offsets.len() as u64 * 8;
+        fields.extend(offsets.into_iter().enumerate().map(|(i, value)| OffsetField::Absolute {
+            position: start + i as u64 * 8,
+            size: 8,
+            value,
+        }));
+    }
+    for (info, saio) in saios {
+        fields.extend(get_saio_fields(info, saio).map(|(position, size, value)| {
+            OffsetField::Absolute { position, size, value }
+        }));
+    }
+    for (info, iloc) in ilocs {
+        let content_start = info.offset + info.header_size;
+        for item in iloc.get_file_offsets() {
+            let base_offset = match item.base_offset {
+                Some((position, size, value)) => {
+                    fields.push(OffsetField::Absolute {
+                        position: content_start + position,
+                        size,
+                        value,
+                    });
+                    value
+                },
+                None => 0,
+            };
+            for (position, size, value) in item.extent_offsets {
+                let position = content_start + position;
+                if item.base_offset.is_none() {
+                    fields.push(OffsetField::Absolute { position, size, value });
+                } else if let Ok(value) = i64::try_from(value) {
+                    fields.push(OffsetField::Relative { position, size, base_offset, value });
+                }
+            }
+        }
+    }
+
+    for (moof_info, moof) in results.iter() {
+        let moof = match moof.as_ref().and_then(|m| m.as_any().downcast_ref::<Moof>()) {
+            Some(moof) => moof,
+            None => continue,
+        };
+        let mut trafs: Vec<(&IsoBoxInfo, &Traf)> = vec![];
+        collect_boxes(moof_info, Some(moof), &mut trafs);
+        for (i, (traf_info, traf)) in trafs.into_iter().enumerate() {
+            let mut tfhds: Vec<(&IsoBoxInfo, &Tfhd)> = vec![];
+            let mut truns: Vec<(&IsoBoxInfo, &Trun)> = vec![];
+            let mut saios: Vec<(&IsoBoxInfo, &Saio)> = vec![];
+            collect_boxes(traf_info, Some(traf), &mut tfhds);
+            collect_boxes(traf_info, Some(traf), &mut truns);
+            collect_boxes(traf_info, Some(traf), &mut saios);
+            let (tfhd_info, tfhd) = match tfhds.first() {
+                Some(tfhd) => *tfhd,
+                None => continue,
+            };
+            let base_offset = match tfhd.get_base_data_offset() {
+                Some(value) => {
+                    // It follows the version, flags and track_ID
+                    fields.push(OffsetField::Absolute {
+                        position: tfhd_info.offset + tfhd_info.header_size + 8,
+                        size: 8,
+                        value,
+                    });
+                    value
+                },
+                None if tfhd.is_default_base_moof() || i == 0 => moof_info.offset,
+                None => continue,
+            };
+            for (trun_info, trun) in truns {
+                if let Some(value) = trun.get_data_offset() {
+                    // It follows the version, flags and sample_count
+                    fields.push(OffsetField::Relative {
+                        position: trun_info.offset + trun_info.header_size + 8,
+                        size: 4,
+                        base_offset,
+                        value: value as i64,
+                    });
+                }
+            }
+            for (saio_info, saio) in saios {
+                fields.extend(get_saio_fields(saio_info, saio).map(|(position, size, value)| {
+                    OffsetField::Relative { position, size, base_offset, value: value as i64 }
+                }));
+            }
+        }
+    }
+    fields
+}
+
+/// Returns the position, size and value of each offset of a `saio` box,
+/// which are stored at its end.
+fn get_saio_fields<'a>(
+    info: &IsoBoxInfo,
+    saio: &'a Saio
+) -> impl Iterator<Item = (u64, u8, u64)> + 'a {
+    let size = saio.get_offset_size();
+    let offsets = saio.get_offsets();
+    let start = info.offset + info.size - offsets.len() as u64 * size as u64;
+    offsets.iter().enumerate().map(move |(i, value)| (start + i as u64 * size as u64, size, *value))
+}
+
+/// Returns the resolved references of a `sidx` box.
+/// `None` if the given box is not a `sidx`.
+pub fn get_sidx_references(entry: &dyn IsoBoxEntry) -> Option<Vec<ResolvedSidxReference>> {
+    entry.as_any().downcast_ref::<Sidx>().map(|sidx| sidx.resolve_references())
+}
+
+/// Returns the byte ranges of each subsegment of a `ssix` box.
+/// `None` if the given box is not a `ssix`.
+pub fn get_ssix_ranges(entry: &dyn IsoBoxEntry) -> Option<SubsegmentByteRanges> {
+    entry.as_any().downcast_ref::<Ssix>().map(|ssix| ssix.get_byte_ranges())
+}
+
+/// Returns the decode time, in seconds, at which a `moof` box starts, based
+/// on the `tfdt` box of its first track fragment.
+/// `None` if it is unknown or if the given box is not a `moof`.
+pub fn get_fragment_start_time(info: &IsoBoxInfo, entry: &dyn IsoBoxEntry) -> Option<f64> {
+    let moof = entry.as_any().downcast_ref::<Moof>()?;
+    let mut tfdts: Vec<(&IsoBoxInfo, &Tfdt)> = vec![];
+    collect_boxes(info, Some(moof), &mut tfdts);
+    tfdts.first().and_then(|(_, tfdt)| tfdt.get_start_time())
+}
@@ -0,0 +1,182 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+/// How samples are assigned to a given level.
+#[derive(Clone)]
+pub enum LevelAssignmentType {
+    /// Samples are assigned through a sample group of the given type.
+    SampleGroup { grouping_type: String },
+
+    /// Samples are assigned through a sample group of the given type and
+    /// grouping type parameter.
+    SampleGroupWithParameter { grouping_type: String, grouping_type_parameter: u32 },
+
+    /// The level contains the whole track.
+    Track,
+
+    /// The level contains the whole track, with its samples stored in the
+    /// subsegment in the order of its levels.
+    MediaInterleaved,
+
+    /// The level contains the given sub-track.
+    SubTrack { sub_track_id: u32 },
+
+    /// Reserved assignment type.
+    Reserved(u8),
+}
+
+/// Assignment of a single level, as described by a `leva` box.
+#[derive(Clone)]
+pub struct LevelAssignment {
+    pub track_id: u32,
+    pub padding_flag: bool,
+    pub assignment_type: LevelAssignmentType,
+}
+
+impl LevelAssignment {
+    pub fn get_values(&self) -> Vec<(&'static str, BoxValue)> {
+        let mut values = vec![
+            ("track_id", BoxValue::from(self.track_id)),
+            ("padding_flag", BoxValue::from(self.padding_flag)),
+        ];
+        match &self.assignment_type {
+            LevelAssignmentType::SampleGroup { grouping_type } => {
+                values.push(("assignment_type", BoxValue::from(0u8)));
+                values.push(("grouping_type", BoxValue::from(grouping_type.as_str())));
+            },
+            LevelAssignmentType::SampleGroupWithParameter {
+                grouping_type,
+                grouping_type_parameter,
+            } => {
+                values.push(("assignment_type", BoxValue::from(1u8)));
+                values.push(("grouping_type", BoxValue::from(grouping_type.as_str())));
+                values.push(
+                    ("grouping_type_parameter", BoxValue::from(*grouping_type_parameter)));
+            },
+            LevelAssignmentType::Track =>
+                values.push(("assignment_type", BoxValue::from(2u8))),
+            LevelAssignmentType::MediaInterleaved =>
+                values.push(("assignment_type", BoxValue::from(3u8))),
+            LevelAssignmentType::SubTrack { sub_track_id } => {
+                values.push(("assignment_type", BoxValue::from(4u8)));
+                values.push(("sub_track_id", BoxValue::from(*sub_track_id)));
+            },
+            LevelAssignmentType::Reserved(assignment_type) =>
+                values.push(("assignment_type", BoxValue::from(*assignment_type))),
+        }
+        values
+    }
+}
+
+pub struct Leva {
+    version: u8,
+    flags: Flags,
+    level_count: u8,
+    levels: Vec<LevelAssignment>,
+}
+
+impl IsoBoxParser for Leva {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let level_count = reader.read_u8()?;
+        let mut levels = Vec::with_capacity(level_count as usize);
+        for _ in 0..level_count {
+            let track_id = reader.read_u32()?;
+            let byte = reader.read_u8()?;
+            let assignment_type = match byte & 0x7F {
+                0 => LevelAssignmentType::SampleGroup {
+                    grouping_type: reader.read_str(4)?,
+                },
+                1 => LevelAssignmentType::SampleGroupWithParameter {
+                    grouping_type: reader.read_str(4)?,
+                    grouping_type_parameter: reader.read_u32()?,
+                },
+                2 => LevelAssignmentType::Track,
+                3 => LevelAssignmentType::MediaInterleaved,
+                4 => LevelAssignmentType::SubTrack {
+                    sub_track_id: reader.read_u32()?,
+                },
+                val => LevelAssignmentType::Reserved(val),
+            };
+            levels.push(LevelAssignment {
+                track_id,
+                padding_flag: byte & 0x80 != 0,
+                assignment_type,
+            });
+        }
+        reader.get_context_mut().set_level_assignments(levels.clone());
+        Ok(Self { version, flags, level_count, levels })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("level_count", BoxValue::from(self.level_count)),
+            ("levels", BoxValue::Collection(
+                    self.levels.iter().enumerate().map(|(i, level)| {
+                        let mut values = vec![("level", BoxValue::from(i as u32 + 1))];
+                        values.append(&mut level.get_values());
+                        values
+                    }).collect()
+            )),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "leva"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Leva {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_u8(self.level_count)?;
+        for level in self.levels.iter() {
+            writer.write_u32(level.track_id)?;
+            let padding_flag = (level.padding_flag as u8) << 7;
+            match &level.assignment_type {
+                LevelAssignmentType::SampleGroup { grouping_type } => {
+                    writer.write_u8(padding_flag)?;
+                    writer.write_bytes(grouping_type.as_bytes())?;
+                },
+                LevelAssignmentType::SampleGroupWithParameter {
+                    grouping_type,
+                    grouping_type_parameter,
+                } => {
+                    writer.write_u8(padding_flag | 1)?;
+                    writer.write_bytes(grouping_type.as_bytes())?;
+                    writer.write_u32(*grouping_type_parameter)?;
+                },
+                LevelAssignmentType::Track => writer.write_u8(padding_flag | 2)?,
+                LevelAssignmentType::MediaInterleaved => writer.write_u8(padding_flag | 3)?,
+                LevelAssignmentType::SubTrack { sub_track_id } => {
+                    writer.write_u8(padding_flag | 4)?;
+                    writer.write_u32(*sub_track_id)?;
+                },
+                LevelAssignmentType::Reserved(val) => writer.write_u8(padding_flag | val)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -3,24 +3,34 @@ use super::{
     IsoBoxInfo,
     BoxParsingError,
     BoxReader,
+    BoxWriter,
     BoxValue,
     IsoBoxEntry,
     IsoBoxParser,
+    IsoBoxSerializer,
 };
 
-pub struct Mdat {}
+pub struct Mdat {
+    /// Offset of the box's content in the parsed file, as it is not kept in
+    /// memory.
+    content_offset: u64,
+    /// Size of the box's content.
+    /// `None` if the box extends to the end of the file.
+    content_size: Option<u64>,
+}
 impl IsoBoxParser for Mdat {
     fn parse<T: BufRead + Seek>(
         reader: &mut BoxReader<T>,
         content_size: Option<u64>,
         _box_info: &std::rc::Rc<IsoBoxInfo>
     ) -> Result<Self, BoxParsingError> {
+        let content_offset = reader.get_pos()?;
         if let Some(size_to_skip) = content_size {
             reader.skip_bytes(size_to_skip)?;
         } else {
             reader.skip_to_end()?;
         }
-        Ok(Self {})
+        Ok(Self { content_offset, content_size })
     }
 
     fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
@@ -31,15 +41,13 @@ impl IsoBoxParser for Mdat {
         "mdat"
     }
 
-    fn get_long_name() -> &'static str {
-        "Media Data Box"
-    }
-
-    fn get_inner_boxes(self) -> Option<Vec<super::IsoBoxData>> {
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
         None
     }
+}
 
-    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
-        None
+impl IsoBoxSerializer for Mdat {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.copy_from_source(self.content_offset, self.content_size)
     }
 }
@@ -0,0 +1,86 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+/// Chromaticity coordinates are expressed in units of 0.00002.
+const CHROMATICITY_DIVIDER: u32 = 50_000;
+
+/// Luminance values are expressed in units of 0.0001 candela per square meter.
+const LUMINANCE_DIVIDER: u32 = 10_000;
+
+pub struct Mdcv {
+    /// x and y coordinates of the green, blue and red primaries, in that
+    /// order.
+    display_primaries: [(u16, u16); 3],
+    white_point: (u16, u16),
+    max_display_mastering_luminance: u32,
+    min_display_mastering_luminance: u32,
+}
+
+impl IsoBoxParser for Mdcv {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let mut display_primaries = [(0, 0); 3];
+        for primary in display_primaries.iter_mut() {
+            *primary = (reader.read_u16()?, reader.read_u16()?);
+        }
+        let white_point = (reader.read_u16()?, reader.read_u16()?);
+        let max_display_mastering_luminance = reader.read_u32()?;
+        let min_display_mastering_luminance = reader.read_u32()?;
+        Ok(Self {
+            display_primaries,
+            white_point,
+            max_display_mastering_luminance,
+            min_display_mastering_luminance,
+        })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        let chromaticity = |val: u16| BoxValue::Scaled(val as u32, CHROMATICITY_DIVIDER);
+        let [green, blue, red] = self.display_primaries;
+        vec![
+            ("display_primaries_green_x", chromaticity(green.0)),
+            ("display_primaries_green_y", chromaticity(green.1)),
+            ("display_primaries_blue_x", chromaticity(blue.0)),
+            ("display_primaries_blue_y", chromaticity(blue.1)),
+            ("display_primaries_red_x", chromaticity(red.0)),
+            ("display_primaries_red_y", chromaticity(red.1)),
+            ("white_point_x", chromaticity(self.white_point.0)),
+            ("white_point_y", chromaticity(self.white_point.1)),
+            ("max_display_mastering_luminance",
+             BoxValue::Scaled(self.max_display_mastering_luminance, LUMINANCE_DIVIDER)),
+            ("min_display_mastering_luminance",
+             BoxValue::Scaled(self.min_display_mastering_luminance, LUMINANCE_DIVIDER)),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "mdcv"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Mdcv {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        for (x, y) in self.display_primaries.iter().chain(std::iter::once(&self.white_point)) {
+            writer.write_u16(*x)?;
+            writer.write_u16(*y)?;
+        }
+        writer.write_u32(self.max_display_mastering_luminance)?;
+        writer.write_u32(self.min_display_mastering_luminance)
+    }
+}
@@ -0,0 +1,125 @@
+use std::io::BufRead;
+use std::rc::Rc;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub struct Mdhd {
+    version: u8,
+    flags: Flags,
+    creation_time: u64,
+    modification_time: u64,
+    timescale: u32,
+    duration: u64,
+    language: u16,
+    pre_defined: u16,
+}
+
+impl Mdhd {
+    pub fn get_timescale(&self) -> u32 {
+        self.timescale
+    }
+
+    /// Set the duration, switching to the 64-bit version of the box if it
+    /// does not fit in 32 bits.
+    pub fn set_duration(&mut self, duration: u64) {
+        if duration > u32::MAX as u64 {
+            self.version = 1;
+        }
+        self.duration = duration;
+    }
+}
+
+impl IsoBoxParser for Mdhd {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        box_info: &Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let (creation_time, modification_time, timescale, duration) = match version {
+            0 => (
+                reader.read_u32()? as u64,
+                reader.read_u32()? as u64,
+                reader.read_u32()?,
+                reader.read_u32()? as u64),
+            1 => (
+                reader.read_u64()?,
+                reader.read_u64()?,
+                reader.read_u32()?,
+                reader.read_u64()?),
+            v => {
+                return Err(BoxParsingError::InvalidVersion {
+                    box_info: Rc::clone(box_info),
+                    expected: vec![0, 1],
+                    actual: v });
+            }
+        };
+        let language = reader.read_u16()? & 0x7FFF;
+        let pre_defined = reader.read_u16()?;
+
+        if let Some(track) = reader.get_context_mut().get_current_track_mut() {
+            track.timescale = Some(timescale);
+        }
+        Ok(Self {
+            version,
+            flags,
+            creation_time,
+            modification_time,
+            timescale,
+            duration,
+            language,
+            pre_defined,
+        })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("creation_time", BoxValue::Date(self.creation_time)),
+            ("modification_time", BoxValue::Date(self.modification_time)),
+            ("timescale", BoxValue::from(self.timescale)),
+            ("duration", BoxValue::from_ticks(self.duration, Some(self.timescale))),
+            ("language", BoxValue::Language(self.language)),
+            ("pre_defined", BoxValue::from(self.pre_defined)),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "mdhd"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Mdhd {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        if self.version == 1 {
+            writer.write_u64(self.creation_time)?;
+            writer.write_u64(self.modification_time)?;
+            writer.write_u32(self.timescale)?;
+            writer.write_u64(self.duration)?;
+        } else {
+            writer.write_u32(self.creation_time as u32)?;
+            writer.write_u32(self.modification_time as u32)?;
+            writer.write_u32(self.timescale)?;
+            writer.write_u32(self.duration as u32)?;
+        }
+        writer.write_u16(self.language)?;
+        writer.write_u16(self.pre_defined)
+    }
+}
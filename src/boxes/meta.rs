@@ -0,0 +1,287 @@
+use std::io::{BufRead, Seek};
+use std::rc::Rc;
+use super::{
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxInfo,
+
+    IsoBoxData,
+    IsoBoxParser,
+    IsoBoxSerializer,
+
+    idat::Idat,
+    iinf::Iinf,
+    iloc::Iloc,
+    infe::Infe,
+    ipco::Ipco,
+    ipma::{Ipma, PropertyAssociation},
+    iprp::Iprp,
+    iref::Iref,
+    pitm::Pitm,
+    utils::parse_children,
+};
+
+/// Information on an item, gathered from all the boxes of a `meta` box
+/// describing it.
+pub struct DerivedItem {
+    item_id: u32,
+    item_type: String,
+    item_name: String,
+    is_hidden: bool,
+    is_primary: bool,
+
+    /// Associated properties, with the short name of the corresponding box
+    /// in the `ipco`, if found.
+    properties: Vec<(PropertyAssociation, Option<String>)>,
+
+    /// Reference type and referenced items of each reference from this item.
+    references: Vec<(String, Vec<u32>)>,
+
+    construction_method: Option<u8>,
+
+    /// Offsets in the file of the first byte of each extent of this item's
+    /// data and of the first byte following it.
+    /// The end offset is `None` when the extent goes until the end of the
+    /// file.
+    /// Extents are not resolved for items constructed from other items, nor
+    /// for items whose offsets do not fit in 64 bits.
+    extents: Vec<(u64, Option<u64>)>,
+}
+
+impl DerivedItem {
+    pub fn get_values(&self) -> Vec<(&'static str, BoxValue)> {
+        let mut values = vec![
+            ("item_id", BoxValue::from(self.item_id)),
+            ("item_type", BoxValue::from(self.item_type.as_str())),
+        ];
+        if !self.item_name.is_empty() {
+            values.push(("item_name", BoxValue::from(self.item_name.as_str())));
+        }
+        values.push(("primary", BoxValue::from(self.is_primary)));
+        values.push(("hidden", BoxValue::from(self.is_hidden)));
+        values.push(("properties", BoxValue::Collection(
+                    self.properties.iter().map(|(association, name)| {
+                        let mut property_values = vec![
+                            ("property_index", BoxValue::from(association.property_index)),
+                            ("essential", BoxValue::from(association.essential)),
+                        ];
+                        if let Some(name) = name {
+                            property_values.push(("property", BoxValue::from(name.as_str())));
+                        }
+                        property_values
+                    }).collect()
+        )));
+        if !self.references.is_empty() {
+            values.push(("references", BoxValue::Collection(
+                        self.references.iter().map(|(reference_type, to_item_ids)| {
+                            vec![
+                                ("reference_type", BoxValue::from(reference_type.as_str())),
+                                ("to_item_ids", BoxValue::from(to_item_ids.as_slice())),
+                            ]
+                        }).collect()
+            )));
+        }
+        if let Some(val) = self.construction_method {
+            values.push(("construction_method", BoxValue::from(val)));
+        }
+        values.push(("extents", BoxValue::Collection(
+                    self.extents.iter().map(|(start, end)| {
+                        let mut extent_values = vec![
+                            ("start_offset", BoxValue::from(*start)),
+                        ];
+                        if let Some(end) = end {
+                            extent_values.push(("end_offset", BoxValue::from(*end)));
+                        }
+                        extent_values
+                    }).collect()
+        )));
+        values
+    }
+}
+
+/// Combine the item-related boxes found in `children` (the content of a
+/// `meta` box) into a list of items with their type, properties and
+/// location in the file.
+fn derive_items(children: &[IsoBoxData]) -> Vec<DerivedItem> {
+    let boxes = children
+        .iter()
+        .filter_map(|c| c.1.as_ref().map(|b| b.as_any()));
+    let iinf = match boxes.clone().find_map(|b| b.downcast_ref::<Iinf>()) {
+        Some(iinf) => iinf,
+        None => return vec![],
+    };
+    let primary_item_id = boxes.clone()
+        .find_map(|b| b.downcast_ref::<Pitm>())
+        .map(|pitm| pitm.get_item_id());
+    let iloc = boxes.clone().find_map(|b| b.downcast_ref::<Iloc>());
+    let idat = boxes.clone().find_map(|b| b.downcast_ref::<Idat>());
+    let iref = boxes.clone().find_map(|b| b.downcast_ref::<Iref>());
+    let iprp_boxes: Vec<&dyn std::any::Any> = boxes.clone()
+        .find_map(|b| b.downcast_ref::<Iprp>())
+        .map(|iprp| iprp.get_content()
+            .iter()
+            .filter_map(|c| c.1.as_ref().map(|b| b.as_any()))
+            .collect())
+        .unwrap_or_default();
+    let ipco = iprp_boxes.iter().find_map(|b| b.downcast_ref::<Ipco>());
+    let ipma = iprp_boxes.iter().find_map(|b| b.downcast_ref::<Ipma>());
+
+    iinf.get_content()
+        .iter()
+        .filter_map(|c| c.1.as_ref().and_then(|b| b.as_any().downcast_ref::<Infe>()))
+        .map(|infe| {
+            let item_id = infe.get_item_id();
+            let properties = ipma
+                .map(|ipma| ipma.get_associations(item_id))
+                .unwrap_or_default()
+                .into_iter()
+                .map(|association| {
+                    let name = ipco
+                        .and_then(|ipco| ipco.get_property_name(association.property_index))
+                        .map(|name| name.to_owned());
+                    (association, name)
+                })
+                .collect();
+            let references = iref
+                .map(|iref| iref.get_references())
+                .unwrap_or_default()
+                .iter()
+                .filter(|r| r.get_from_item_id() == item_id)
+                .map(|r| (r.get_reference_type().to_owned(), r.get_to_item_ids().to_vec()))
+                .collect();
+            let location = iloc.and_then(|iloc| iloc.get_items()
+                .iter()
+                .find(|i| i.get_item_id() == item_id));
+            let construction_method = location.map(|l| l.get_construction_method());
+            let extents = match (location, construction_method) {
+                (Some(location), Some(0)) => location.get_extents()
+                    .and_then(|extents| extents
+                        .iter()
+                        .map(|(offset, length)| {
+                            if *length == 0 {
+                                Some((*offset, None))
+                            } else {
+                                Some((*offset, Some(offset.checked_add(*length)?)))
+                            }
+                        })
+                        .collect())
+                    .unwrap_or_default(),
+                (Some(location), Some(1)) => match idat {
+                    Some(idat) => {
+                        let base = idat.get_data_offset();
+                        location.get_extents()
+                            .and_then(|extents| extents
+                                .iter()
+                                .map(|(offset, length)| {
+                                    let start = base.checked_add(*offset)?;
+                                    let end = if *length == 0 {
+                                        base.checked_add(idat.get_data_size())?
+                                    } else {
+                                        start.checked_add(*length)?
+                                    };
+                                    Some((start, Some(end)))
+                                })
+                                .collect())
+                            .unwrap_or_default()
+                    },
+                    None => vec![],
+                },
+                _ => vec![],
+            };
+            DerivedItem {
+                item_id,
+                item_type: infe.get_item_type().to_owned(),
+                item_name: infe.get_item_name().to_owned(),
+                is_hidden: infe.is_hidden(),
+                is_primary: primary_item_id == Some(item_id),
+                properties,
+                references,
+                construction_method,
+                extents,
+            }
+        })
+        .collect()
+}
+
+pub struct Meta {
+    /// `version` and `flags` of the box.
+    /// `None` for the QuickTime variant of the `meta` box, which is a plain
+    /// container.
+    version_and_flags: Option<(u8, Flags)>,
+    content: Vec<super::IsoBoxData>,
+
+    /// Items described by this box, derived from its `iinf`, `iloc`, `iprp`
+    /// and `iref` boxes.
+    items: Vec<DerivedItem>,
+}
+
+impl IsoBoxParser for Meta {
+    fn parse<T: BufRead + Seek>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        box_info: &Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        // A QuickTime `meta` directly starts with its `hdlr` box where an
+        // ISOBMFF one would have its version and flags.
+        let is_quicktime = reader.peek_bytes(8)
+            .is_ok_and(|bytes| &bytes[4..] == b"hdlr");
+        let (version_and_flags, content_size) = if is_quicktime {
+            (None, content_size)
+        } else {
+            let version = reader.read_u8()?;
+            let flags = Flags::read(reader)?;
+            (Some((version, flags)), content_size.map(|size| size.saturating_sub(4)))
+        };
+        let content = parse_children(reader, content_size, Some(box_info))?;
+
+        // Keys only apply to the `ilst` of the same `meta` box
+        reader.get_context_mut().set_metadata_keys(vec![]);
+        let items = derive_items(&content);
+        Ok(Self { version_and_flags, content, items })
+    }
+
+    fn get_inner_boxes_mut(&mut self) -> Option<&mut Vec<super::IsoBoxData>> {
+        Some(&mut self.content)
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<super::ContainedBoxInfo<'_>>> {
+        Some(self.content.iter().map(|c|
+                (c.0.as_ref(), c.1.as_ref().map(|boxed| { std::boxed::Box::as_ref(boxed) }))
+        ).collect())
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        let mut values = match self.version_and_flags {
+            Some((version, flags)) => vec![
+                ("version", BoxValue::from(version)),
+                ("flags", BoxValue::from(flags)),
+            ],
+            None => vec![
+                ("variant", BoxValue::from("QuickTime")),
+            ],
+        };
+        if !self.items.is_empty() {
+            values.push(("items", BoxValue::Collection(
+                        self.items.iter().map(|item| item.get_values()).collect()
+            )));
+        }
+        values
+    }
+
+    fn get_short_name() -> &'static str {
+        "meta"
+    }
+}
+
+impl IsoBoxSerializer for Meta {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        if let Some((version, flags)) = self.version_and_flags {
+            writer.write_u8(version)?;
+            writer.write_flags(flags)?;
+        }
+        writer.write_boxes(&self.content)
+    }
+}
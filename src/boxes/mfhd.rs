@@ -3,10 +3,12 @@ use super::{
     IsoBoxInfo,
     BoxParsingError,
     BoxReader,
+    BoxWriter,
     BoxValue,
     Flags,
     IsoBoxEntry,
     IsoBoxParser,
+    IsoBoxSerializer,
 };
 
 pub struct Mfhd {
@@ -15,6 +17,12 @@ pub struct Mfhd {
     sequence_number: u32,
 }
 
+impl Mfhd {
+    pub fn new(sequence_number: u32) -> Self {
+        Self { version: 0, flags: Flags::from(0), sequence_number }
+    }
+}
+
 impl IsoBoxParser for Mfhd {
     fn parse<T: BufRead>(
         reader: &mut BoxReader<T>,
@@ -39,15 +47,15 @@ impl IsoBoxParser for Mfhd {
         "mfhd"
     }
 
-    fn get_long_name() -> &'static str {
-        "Movie Fragment Header Box"
-    }
-
-    fn get_inner_boxes(self) -> Option<Vec<super::IsoBoxData>> {
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
         None
     }
+}
 
-    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
-        None
+impl IsoBoxSerializer for Mfhd {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_u32(self.sequence_number)
     }
 }
@@ -1,39 +1,151 @@
 mod box_types;
 mod box_reader;
+mod box_writer;
+mod checks;
+mod context;
 mod error;
+mod layout;
+mod samples;
 mod utils;
 
 // individual boxes
+mod audio_sample_entry;
+mod av1c;
+mod avcc;
+mod btrt;
+mod clap;
+mod clli;
+mod co64;
+mod colr;
+mod cprt;
+mod ctts;
+mod dac3;
+mod dec3;
+mod dops;
+mod edts;
+mod elng;
+mod elst;
+mod esds;
+mod fiel;
 mod free;
+mod frma;
+mod ftab;
 mod ftyp;
 mod hdlr;
+mod hvcc;
+mod idat;
+mod iinf;
+mod iloc;
+mod ilst;
+mod imir;
+mod infe;
+mod ipco;
+mod ipma;
+mod iprp;
+mod iref;
+mod irot;
+mod ispe;
+mod keys;
+mod leva;
 mod mdat;
+mod mdcv;
+mod mdhd;
+mod mdia;
+mod meta;
 mod mfhd;
+mod minf;
 mod moof;
 mod moov;
+mod mvex;
 mod mvhd;
+mod name;
+mod pasp;
 mod pdin;
+mod pitm;
+mod pixi;
+mod prft;
 mod saio;
 mod saiz;
+mod sbgp;
+mod schi;
+mod schm;
+mod sdtp;
+mod senc;
+mod sgpd;
 mod sidx;
+mod sinf;
+mod ssix;
+mod stbl;
+mod stco;
+mod stpp;
+mod stsc;
+mod stsd;
+mod stss;
+mod stsz;
+mod stts;
 mod styp;
 mod subs;
+mod tenc;
 mod tfdt;
 mod tfhd;
+mod tkhd;
 mod traf;
+mod trak;
+mod tref;
+mod trex;
+mod trgr;
 mod trun;
+mod tx3g;
+mod udta;
+mod visual_sample_entry;
+mod vlab;
+mod vttc;
+mod vvcc;
+mod wvtt;
 
 pub use box_types::{
     BoxValue,
     ContainedBoxInfo,
+    FlagNames,
     Flags,
     IsoBoxData,
     IsoBoxEntry,
     IsoBoxInfo,
-    IsoBoxParser
+    IsoBoxParser,
+    IsoBoxSerializer,
+    SampleFlags,
+    create_box_data,
 };
 pub use box_reader::BoxReader;
+pub use box_writer::BoxWriter;
+pub use checks::{check_auxiliary_information, check_isobmff, has_auxiliary_information};
 pub use error::BoxParsingError;
+pub use layout::{OffsetField, find_offset_fields, get_fragment_start_time, get_sidx_references, get_ssix_ranges};
+pub use utils::decode_text;
+pub use dac3::Ac3StreamInfo;
+pub use samples::{get_track_samples, SampleLocation, TrackSamples};
+pub use senc::SampleEncryption;
+pub use tenc::TrackEncryption;
+pub use sidx::{ResolvedSidxReference, Sidx, SidxReference};
+
+pub use co64::Co64;
+pub use ctts::Ctts;
+pub use mdhd::Mdhd;
+pub use mfhd::Mfhd;
+pub use moof::Moof;
+pub use mvex::Mvex;
+pub use mvhd::Mvhd;
+pub use stco::Stco;
+pub use stsc::Stsc;
+pub use stss::Stss;
+pub use stsz::Stsz;
+pub use stts::Stts;
+pub use tfdt::Tfdt;
+pub use tfhd::Tfhd;
+pub use tkhd::Tkhd;
+pub use traf::Traf;
+pub use trex::Trex;
+pub use trun::{Trun, TrunSample};
 
 use std::io::{BufRead, Seek};
 pub fn parse_isobmff(
@@ -4,17 +4,24 @@ use super::{
     IsoBoxInfo,
     BoxParsingError,
     BoxReader,
+    BoxWriter,
     BoxValue,
-    IsoBoxEntry,
     IsoBoxParser,
+    IsoBoxSerializer,
     utils::parse_children,
 };
 
 pub struct Moof {
-    content: Vec<(Rc<IsoBoxInfo>, Option<Box<dyn IsoBoxEntry>>)>,
+    content: Vec<super::IsoBoxData>,
 }
 
-impl<'a> IsoBoxParser for Moof {
+impl Moof {
+    pub fn new(content: Vec<super::IsoBoxData>) -> Self {
+        Self { content }
+    }
+}
+
+impl IsoBoxParser for Moof {
     fn parse<T: BufRead + Seek>(
         reader: &mut BoxReader<T>,
         content_size: Option<u64>,
@@ -24,13 +31,13 @@ impl<'a> IsoBoxParser for Moof {
         Ok(Self { content })
     }
 
-    fn get_inner_boxes(self) -> Option<Vec<super::IsoBoxData>> {
-        Some(self.content)
+    fn get_inner_boxes_mut(&mut self) -> Option<&mut Vec<super::IsoBoxData>> {
+        Some(&mut self.content)
     }
 
-    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+    fn get_inner_boxes_ref(&self) -> Option<Vec<super::ContainedBoxInfo<'_>>> {
         Some(self.content.iter().map(|c|
-                (c.0.as_ref(), c.1.as_ref().map(|boxed| { std::boxed::Box::as_ref(&boxed) }))
+                (c.0.as_ref(), c.1.as_ref().map(|boxed| { std::boxed::Box::as_ref(boxed) }))
         ).collect())
     }
 
@@ -41,8 +48,10 @@ impl<'a> IsoBoxParser for Moof {
     fn get_short_name() -> &'static str {
         "moof"
     }
+}
 
-    fn get_long_name() -> &'static str {
-        "Movie Fragment Box"
+impl IsoBoxSerializer for Moof {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_boxes(&self.content)
     }
 }
@@ -3,20 +3,21 @@ use std::rc::Rc;
 use super::{
     BoxParsingError,
     BoxReader,
+    BoxWriter,
     BoxValue,
     IsoBoxInfo,
 
     IsoBoxParser,
-    IsoBoxEntry,
+    IsoBoxSerializer,
 
     utils::parse_children,
 };
 
 pub struct Moov {
-    content: Vec<(Rc<IsoBoxInfo>, Option<Box<dyn IsoBoxEntry>>)>,
+    content: Vec<super::IsoBoxData>,
 }
 
-impl<'a> IsoBoxParser for Moov {
+impl IsoBoxParser for Moov {
     fn parse<T: BufRead + Seek>(
         reader: &mut BoxReader<T>,
         content_size: Option<u64>,
@@ -26,13 +27,13 @@ impl<'a> IsoBoxParser for Moov {
         Ok(Self { content })
     }
 
-    fn get_inner_boxes(self) -> Option<Vec<super::IsoBoxData>> {
-        Some(self.content)
+    fn get_inner_boxes_mut(&mut self) -> Option<&mut Vec<super::IsoBoxData>> {
+        Some(&mut self.content)
     }
 
-    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+    fn get_inner_boxes_ref(&self) -> Option<Vec<super::ContainedBoxInfo<'_>>> {
         Some(self.content.iter().map(|c|
-                (c.0.as_ref(), c.1.as_ref().map(|boxed| { std::boxed::Box::as_ref(&boxed) }))
+                (c.0.as_ref(), c.1.as_ref().map(|boxed| { std::boxed::Box::as_ref(boxed) }))
         ).collect())
     }
 
@@ -43,8 +44,10 @@ impl<'a> IsoBoxParser for Moov {
     fn get_short_name() -> &'static str {
         "moov"
     }
+}
 
-    fn get_long_name() -> &'static str {
-        "Movie Box"
+impl IsoBoxSerializer for Moov {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_boxes(&self.content)
     }
 }
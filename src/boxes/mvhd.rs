@@ -4,10 +4,12 @@ use super::{
     IsoBoxInfo,
     BoxParsingError,
     BoxReader,
+    BoxWriter,
     BoxValue,
     Flags,
     IsoBoxEntry,
     IsoBoxParser,
+    IsoBoxSerializer,
 };
 
 pub struct Mvhd {
@@ -17,8 +19,8 @@ pub struct Mvhd {
     modification_time: u64,
     timescale: u32,
     duration: u64,
-    rate: [u16; 2],
-    volume: [u8; 2],
+    rate: i32,
+    volume: i16,
     reserved_1: u16,
     reserved_2: [u32; 2],
     matrix: [u32; 9],
@@ -26,6 +28,21 @@ pub struct Mvhd {
     next_track_id: u32,
 }
 
+impl Mvhd {
+    pub fn get_timescale(&self) -> u32 {
+        self.timescale
+    }
+
+    /// Set the duration, switching to the 64-bit version of the box if it
+    /// does not fit in 32 bits.
+    pub fn set_duration(&mut self, duration: u64) {
+        if duration > u32::MAX as u64 {
+            self.version = 1;
+        }
+        self.duration = duration;
+    }
+}
+
 impl IsoBoxParser for Mvhd {
     fn parse<T: BufRead>(
         reader: &mut BoxReader<T>,
@@ -53,8 +70,8 @@ impl IsoBoxParser for Mvhd {
             }
         };
 
-        let rate = [reader.read_u16()?, reader.read_u16()?];
-        let volume = [reader.read_u8()?, reader.read_u8()?];
+        let rate = reader.read_i32()?;
+        let volume = reader.read_u16()? as i16;
         let reserved_1 = reader.read_u16()?;
         let reserved_2 = [reader.read_u32()?, reader.read_u32()?];
         let matrix = [
@@ -65,6 +82,7 @@ impl IsoBoxParser for Mvhd {
             reader.read_u32()?, reader.read_u32()?, reader.read_u32()?,
             reader.read_u32()?, reader.read_u32()?, reader.read_u32()?];
         let next_track_id = reader.read_u32()?;
+        reader.get_context_mut().set_movie_timescale(timescale);
         Ok(Self {
             version,
             flags,
@@ -86,12 +104,12 @@ impl IsoBoxParser for Mvhd {
         vec![
             ("version", BoxValue::from(self.version)),
             ("flags", BoxValue::from(self.flags)),
-            ("creation_time", BoxValue::from(self.creation_time)),
-            ("modification_time", BoxValue::from(self.modification_time)),
+            ("creation_time", BoxValue::Date(self.creation_time)),
+            ("modification_time", BoxValue::Date(self.modification_time)),
             ("timescale", BoxValue::from(self.timescale)),
-            ("duration", BoxValue::from(self.duration)),
-            ("rate", BoxValue::FixedPoint16(self.rate)),
-            ("volume", BoxValue::FixedPoint8(self.volume)),
+            ("duration", BoxValue::from_ticks(self.duration, Some(self.timescale))),
+            ("rate", BoxValue::FixedPoint16_16(self.rate)),
+            ("volume", BoxValue::FixedPoint8_8(self.volume)),
             ("reserved_1", BoxValue::from(self.reserved_1)),
             ("reserved_2", BoxValue::from(self.reserved_2.as_ref())),
             ("matrix", BoxValue::Matrix3_3(&self.matrix)),
@@ -104,15 +122,35 @@ impl IsoBoxParser for Mvhd {
         "mvhd"
     }
 
-    fn get_long_name() -> &'static str {
-        "Movie Header Box"
-    }
-
-    fn get_inner_boxes(self) -> Option<Vec<super::IsoBoxData>> {
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
         None
     }
+}
 
-    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
-        None
+impl IsoBoxSerializer for Mvhd {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        if self.version == 1 {
+            writer.write_u64(self.creation_time)?;
+            writer.write_u64(self.modification_time)?;
+            writer.write_u32(self.timescale)?;
+            writer.write_u64(self.duration)?;
+        } else {
+            writer.write_u32(self.creation_time as u32)?;
+            writer.write_u32(self.modification_time as u32)?;
+            writer.write_u32(self.timescale)?;
+            writer.write_u32(self.duration as u32)?;
+        }
+        writer.write_i32(self.rate)?;
+        writer.write_u16(self.volume as u16)?;
+        writer.write_u16(self.reserved_1)?;
+        for reserved in self.reserved_2.iter() {
+            writer.write_u32(*reserved)?;
+        }
+        for value in self.matrix.iter().chain(self.pre_defined.iter()) {
+            writer.write_u32(*value)?;
+        }
+        writer.write_u32(self.next_track_id)
     }
 }
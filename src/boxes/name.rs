@@ -0,0 +1,54 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+    utils::decode_text,
+};
+
+/// QuickTime track name, found in a track's `udta`.
+/// It only contains the name, without version nor flags.
+pub struct Name {
+    name: String,
+    /// The name as encoded in the box.
+    data: Vec<u8>,
+}
+
+impl IsoBoxParser for Name {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let data = match content_size {
+            Some(size) => reader.read_bytes(size as usize)?,
+            None => reader.read_to_end()?,
+        };
+        Ok(Self { name: decode_text(&data), data })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("name", BoxValue::from(self.name.as_str())),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "name"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Name {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_bytes(&self.data)
+    }
+}
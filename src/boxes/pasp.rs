@@ -0,0 +1,50 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub struct Pasp {
+    h_spacing: u32,
+    v_spacing: u32,
+}
+
+impl IsoBoxParser for Pasp {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let h_spacing = reader.read_u32()?;
+        let v_spacing = reader.read_u32()?;
+        Ok(Self { h_spacing, v_spacing })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("h_spacing", BoxValue::from(self.h_spacing)),
+            ("v_spacing", BoxValue::from(self.v_spacing)),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "pasp"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Pasp {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u32(self.h_spacing)?;
+        writer.write_u32(self.v_spacing)
+    }
+}
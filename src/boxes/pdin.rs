@@ -4,10 +4,12 @@ use super::{
     IsoBoxInfo,
     BoxParsingError,
     BoxReader,
+    BoxWriter,
     BoxValue,
     Flags,
     IsoBoxEntry,
     IsoBoxParser,
+    IsoBoxSerializer,
 };
 
 pub struct Pdin {
@@ -50,15 +52,16 @@ impl IsoBoxParser for Pdin {
         "pdin"
     }
 
-    fn get_long_name() -> &'static str {
-        "Progressive Download Information Box"
-    }
-
-    fn get_inner_boxes(self) -> Option<Vec<super::IsoBoxData>> {
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
         None
     }
+}
 
-    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
-        None
+impl IsoBoxSerializer for Pdin {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_u32(self.rate)?;
+        writer.write_u32(self.delay)
     }
 }
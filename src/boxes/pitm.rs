@@ -0,0 +1,69 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub struct Pitm {
+    version: u8,
+    flags: Flags,
+    item_id: u32,
+}
+
+impl Pitm {
+    pub fn get_item_id(&self) -> u32 {
+        self.item_id
+    }
+}
+
+impl IsoBoxParser for Pitm {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let item_id = if version == 0 {
+            reader.read_u16()? as u32
+        } else {
+            reader.read_u32()?
+        };
+        Ok(Self { version, flags, item_id })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("item_id", BoxValue::from(self.item_id)),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "pitm"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Pitm {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        if self.version == 0 {
+            writer.write_u16(self.item_id as u16)
+        } else {
+            writer.write_u32(self.item_id)
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub struct Pixi {
+    version: u8,
+    flags: Flags,
+    num_channels: u8,
+    bits_per_channel: Vec<u8>,
+}
+
+impl IsoBoxParser for Pixi {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let num_channels = reader.read_u8()?;
+        let bits_per_channel = reader.read_bytes(num_channels as usize)?;
+        Ok(Self { version, flags, num_channels, bits_per_channel })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("num_channels", BoxValue::from(self.num_channels)),
+            ("bits_per_channel", BoxValue::from(self.bits_per_channel.as_slice())),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "pixi"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Pixi {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_u8(self.num_channels)?;
+        writer.write_bytes(&self.bits_per_channel)
+    }
+}
@@ -0,0 +1,135 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub struct Prft {
+    version: u8,
+    flags: Flags,
+    reference_track_id: u32,
+    ntp_timestamp: u64,
+    media_time: u64,
+
+    /// Timescale of the reference track, if known.
+    timescale: Option<u32>,
+}
+
+impl IsoBoxParser for Prft {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let reference_track_id = reader.read_u32()?;
+        let ntp_timestamp = reader.read_u64()?;
+        let media_time = if version == 0 {
+            reader.read_u32()? as u64
+        } else {
+            reader.read_u64()?
+        };
+
+        let timescale = reader.get_context().get_track(reference_track_id)
+            .and_then(|t| t.timescale);
+        Ok(Self {
+            version,
+            flags,
+            reference_track_id,
+            ntp_timestamp,
+            media_time,
+            timescale,
+        })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("time_reference", BoxValue::from(get_time_reference_name(self.flags))),
+            ("reference_track_id", BoxValue::from(self.reference_track_id)),
+            ("ntp_timestamp", BoxValue::NtpTimestamp(self.ntp_timestamp)),
+            ("media_time", BoxValue::from_ticks(self.media_time, self.timescale)),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "prft"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+/// Returns the point in the production chain at which the `ntp_timestamp` was
+/// taken, as indicated by the `flags` of the `prft` box.
+///
+/// Unlike in most boxes, those flags are an enumeration of values and not a
+/// combination of bits.
+fn get_time_reference_name(flags: Flags) -> &'static str {
+    match u32::from(flags) {
+        0 => "encoder input",
+        1 => "encoder output",
+        2 => "moof finalized",
+        4 => "moof written",
+        8 => "arbitrary consistent",
+        24 => "captured",
+        _ => "reserved",
+    }
+}
+
+impl IsoBoxSerializer for Prft {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_u32(self.reference_track_id)?;
+        writer.write_u64(self.ntp_timestamp)?;
+        if self.version == 0 {
+            writer.write_u32(self.media_time as u32)
+        } else {
+            writer.write_u64(self.media_time)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use super::Prft;
+    use super::super::{parse_isobmff, utils::collect_boxes};
+
+    #[test]
+    fn producer_reference_times() {
+        let path = format!("{}/tests/fixtures/prft.mp4", env!("CARGO_MANIFEST_DIR"));
+        let input = std::fs::read(&path).unwrap();
+        let data = parse_isobmff(Cursor::new(&input)).unwrap();
+        let mut found: Vec<(_, &Prft)> = vec![];
+        for (info, entry) in data.iter() {
+            collect_boxes(info, entry.as_deref(), &mut found);
+        }
+        let prfts: Vec<_> = found.into_iter()
+            .map(|(_, prft)| (
+                prft.version,
+                super::get_time_reference_name(prft.flags),
+                prft.reference_track_id,
+                prft.ntp_timestamp,
+                prft.media_time,
+            ))
+            .collect();
+        // 2024-01-01T00:00:00.500Z
+        let ntp_timestamp = (3_913_056_000 << 32) | 0x8000_0000;
+        assert_eq!(prfts, vec![
+            (0, "encoder output", 1, ntp_timestamp, 90000),
+            (1, "captured", 1, ntp_timestamp, 90000),
+        ]);
+    }
+}
@@ -3,10 +3,13 @@ use super::{
     IsoBoxInfo,
     BoxParsingError,
     BoxReader,
+    BoxWriter,
     BoxValue,
+    FlagNames,
     Flags,
     IsoBoxEntry,
     IsoBoxParser,
+    IsoBoxSerializer,
 };
 
 pub struct Saio {
@@ -18,6 +21,28 @@ pub struct Saio {
     offset: Vec<u64>,
 }
 
+const FLAG_NAMES: FlagNames = &[
+    (0x000001, "aux-info-type-present"),
+];
+
+impl Saio {
+    pub fn get_aux_info_type(&self) -> Option<u32> {
+        self.aux_info_type
+    }
+
+    /// Returns the offset of each chunk's or track run's auxiliary
+    /// information, or of the whole track's or track fragment's one if there
+    /// is a single offset.
+    pub fn get_offsets(&self) -> &[u64] {
+        &self.offset
+    }
+
+    /// Returns the size in bytes of each offset, which depends on the version.
+    pub fn get_offset_size(&self) -> u8 {
+        if self.version == 0 { 4 } else { 8 }
+    }
+}
+
 impl IsoBoxParser for Saio {
     fn parse<T: BufRead>(
         reader: &mut BoxReader<T>,
@@ -77,15 +102,33 @@ impl IsoBoxParser for Saio {
         "saio"
     }
 
-    fn get_long_name() -> &'static str {
-        "Sample Auxiliary Information Offsets Box"
+    fn get_flag_names() -> FlagNames {
+        FLAG_NAMES
     }
 
-    fn get_inner_boxes(self) -> Option<Vec<super::IsoBoxData>> {
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
         None
     }
+}
 
-    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
-        None
+impl IsoBoxSerializer for Saio {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        if let (Some(aux_info_type), Some(parameter)) =
+            (self.aux_info_type, self.aux_info_type_parameter)
+        {
+            writer.write_u32(aux_info_type)?;
+            writer.write_u32(parameter)?;
+        }
+        writer.write_u32(self.entry_count)?;
+        for offset in self.offset.iter() {
+            if self.version == 0 {
+                writer.write_u32(*offset as u32)?;
+            } else {
+                writer.write_u64(*offset)?;
+            }
+        }
+        Ok(())
     }
 }
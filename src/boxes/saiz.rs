@@ -3,10 +3,13 @@ use super::{
     IsoBoxInfo,
     BoxParsingError,
     BoxReader,
+    BoxWriter,
     BoxValue,
+    FlagNames,
     Flags,
     IsoBoxEntry,
     IsoBoxParser,
+    IsoBoxSerializer,
 };
 
 pub struct Saiz {
@@ -19,6 +22,32 @@ pub struct Saiz {
     sample_info_size: Vec<u8>,
 }
 
+const FLAG_NAMES: FlagNames = &[
+    (0x000001, "aux-info-type-present"),
+];
+
+impl Saiz {
+    pub fn get_aux_info_type(&self) -> Option<u32> {
+        self.aux_info_type
+    }
+
+    pub fn get_sample_count(&self) -> u32 {
+        self.entry_count
+    }
+
+    /// Returns the size of the auxiliary information of the sample at the
+    /// given 0-based index. `None` if it is out of bounds.
+    pub fn get_sample_info_size(&self, index: u32) -> Option<u8> {
+        if index >= self.entry_count {
+            None
+        } else if self.default_sample_info_size != 0 {
+            Some(self.default_sample_info_size)
+        } else {
+            self.sample_info_size.get(index as usize).copied()
+        }
+    }
+}
+
 impl IsoBoxParser for Saiz {
     fn parse<T: BufRead>(
         reader: &mut BoxReader<T>,
@@ -87,15 +116,27 @@ impl IsoBoxParser for Saiz {
         "saiz"
     }
 
-    fn get_long_name() -> &'static str {
-        "Sample Auxiliary Information Sizes Box"
+    fn get_flag_names() -> FlagNames {
+        FLAG_NAMES
     }
 
-    fn get_inner_boxes(self) -> Option<Vec<super::IsoBoxData>> {
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
         None
     }
+}
 
-    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
-        None
+impl IsoBoxSerializer for Saiz {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        if let (Some(aux_info_type), Some(parameter)) =
+            (self.aux_info_type, self.aux_info_type_parameter)
+        {
+            writer.write_u32(aux_info_type)?;
+            writer.write_u32(parameter)?;
+        }
+        writer.write_u8(self.default_sample_info_size)?;
+        writer.write_u32(self.entry_count)?;
+        writer.write_bytes(&self.sample_info_size)
     }
 }
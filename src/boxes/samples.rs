@@ -0,0 +1,538 @@
+use super::{
+    IsoBoxData,
+    IsoBoxEntry,
+    IsoBoxInfo,
+    av1c::Av1c,
+    avcc::Avcc,
+    co64::Co64,
+    ctts::Ctts,
+    dac3::{Ac3StreamInfo, Dac3},
+    dec3::Dec3,
+    dops::Dops,
+    esds::Esds,
+    hvcc::Hvcc,
+    frma::Frma,
+    mdhd::Mdhd,
+    mdia::Mdia,
+    moof::Moof,
+    saio::Saio,
+    saiz::Saiz,
+    schm::Schm,
+    senc::{SampleEncryption, Senc},
+    sinf::Sinf,
+    stco::Stco,
+    stsc::Stsc,
+    stsd::Stsd,
+    stss::Stss,
+    stsz::Stsz,
+    stts::Stts,
+    tenc::{Tenc, TrackEncryption},
+    tfdt::Tfdt,
+    tfhd::Tfhd,
+    tkhd::Tkhd,
+    traf::Traf,
+    trak::Trak,
+    trex::Trex,
+    trun::Trun,
+    utils::{collect_boxes, find_in},
+    vvcc::Vvcc,
+};
+
+/// Location in the file of a single sample.
+pub struct SampleLocation {
+    /// 1-based number of the sample in its track, fragments included.
+    pub sample_number: u32,
+    pub offset: u64,
+    pub size: u32,
+
+    /// Decoding time of the sample, in the track's timescale.
+    pub decode_time: u64,
+    pub duration: u32,
+
+    /// Difference between the composition and decoding times of the sample.
+    pub composition_offset: i64,
+
+    /// `true` for sync samples, from which decoding can start.
+    pub is_sync: bool,
+
+    /// 1-based index of the sample entry describing the sample.
+    pub sample_description_index: u32,
+
+    /// Encryption parameters of the sample, as found in the `senc` box
+    /// describing it. `None` for clear samples or if there is no such box.
+    pub encryption: Option<SampleEncryption>,
+
+    /// Offset and size in the file of the sample's encryption parameters
+    /// stored as auxiliary information, as described by the `saiz` and `saio`
+    /// boxes.
+    pub auxiliary_information: Option<(u64, u8)>,
+}
+
+/// Protection applied to the samples of a track, as described by the
+/// `sinf` box of its sample entry.
+pub struct TrackProtection<'a> {
+    /// Protection scheme, such as "cenc" or "cbcs".
+    pub scheme_type: &'a str,
+
+    /// Coding name of the sample entry before it was protected.
+    pub original_format: Option<&'a str>,
+
+    pub encryption: Option<&'a TrackEncryption>,
+}
+
+/// Samples of a track, with the information needed to interpret them.
+pub struct TrackSamples<'a> {
+    pub track_id: u32,
+
+    /// Coding name of the track's first sample entry.
+    pub codec: Option<String>,
+
+    /// The track's first sample entry, whose contained boxes describe the
+    /// coding configuration.
+    pub sample_entry: Option<&'a dyn IsoBoxEntry>,
+
+    /// Timescale of the track, as indicated by its `mdhd` box.
+    pub timescale: Option<u32>,
+
+    pub samples: Vec<SampleLocation>,
+
+    /// Issues found while locating the samples.
+    pub warnings: Vec<String>,
+}
+
+impl<'a> TrackSamples<'a> {
+    /// Returns the protection applied to the track's samples.
+    /// `None` if its sample entry is not protected.
+    pub fn get_protection(&self) -> Option<TrackProtection<'a>> {
+        let children = self.sample_entry?.get_inner_boxes_ref()?;
+        let sinf = find_all::<Sinf>(children.into_iter()).into_iter().next()?;
+        let sinf_children = || sinf.get_inner_boxes_ref().unwrap_or_default().into_iter();
+        let scheme_type = find_all::<Schm>(sinf_children()).first()?.get_scheme_type();
+        Some(TrackProtection {
+            scheme_type,
+            original_format: find_all::<Frma>(sinf_children())
+                .first()
+                .map(|frma| frma.get_data_format()),
+            encryption: find_all::<Tenc>(sinf_children())
+                .first()
+                .map(|tenc| tenc.get_track_encryption()),
+        })
+    }
+
+    /// Returns the coding name of the track's samples, which for protected
+    /// tracks is the one of the sample entry before it was protected.
+    pub fn get_coding_name(&self) -> Option<&str> {
+        self.get_protection()
+            .and_then(|protection| protection.original_format)
+            .or(self.codec.as_deref())
+    }
+
+    /// Returns the size in bytes of the length field preceding each NAL unit
+    /// of the samples, as indicated by the `avcC`, `hvcC` or `vvcC` box of the
+    /// sample entry.
+    /// `None` if the track is not made of NAL units or if the size is unknown.
+    pub fn get_nal_length_size(&self) -> Option<u8> {
+        let children = self.sample_entry?.get_inner_boxes_ref()?;
+        children.into_iter().find_map(|(_, child)| {
+            let child = child?.as_any();
+            child.downcast_ref::<Avcc>().map(|avcc| avcc.get_nal_length_size())
+                .or_else(|| child.downcast_ref::<Hvcc>().map(|hvcc| hvcc.get_nal_length_size()))
+                .or_else(|| child.downcast_ref::<Vvcc>().map(|vvcc| vvcc.get_nal_length_size()))
+        })
+    }
+
+    /// Returns the OBUs stored in the `av1C` box of the sample entry.
+    pub fn get_av1_config_obus(&self) -> Option<&[u8]> {
+        let children = self.sample_entry?.get_inner_boxes_ref()?;
+        children.into_iter().find_map(|(_, child)| {
+            child?.as_any().downcast_ref::<Av1c>().map(|av1c| av1c.get_config_obus())
+        })
+    }
+
+    /// Returns the `channelConfiguration` announced by the `esds` box of the
+    /// sample entry, for MPEG-4 audio tracks.
+    pub fn get_aac_channel_configuration(&self) -> Option<u8> {
+        let children = self.sample_entry?.get_inner_boxes_ref()?;
+        children.into_iter().find_map(|(_, child)| {
+            child?.as_any().downcast_ref::<Esds>()?.get_channel_configuration()
+        })
+    }
+
+    /// Returns the stream parameters announced by the `dac3` box, or for the
+    /// first independent substream by the `dec3` box, of the sample entry.
+    pub fn get_ac3_stream_info(&self) -> Option<Ac3StreamInfo> {
+        let children = self.sample_entry?.get_inner_boxes_ref()?;
+        children.into_iter().find_map(|(_, child)| {
+            let child = child?.as_any();
+            child.downcast_ref::<Dac3>().map(|dac3| dac3.get_stream_info())
+                .or_else(|| child.downcast_ref::<Dec3>().and_then(|dec3| dec3.get_stream_info()))
+        })
+    }
+
+    /// Returns the output channel count announced by the `dOps` box of the
+    /// sample entry, for Opus tracks.
+    pub fn get_opus_channel_count(&self) -> Option<u8> {
+        let children = self.sample_entry?.get_inner_boxes_ref()?;
+        children.into_iter().find_map(|(_, child)| {
+            child?.as_any().downcast_ref::<Dops>().map(|dops| dops.get_output_channel_count())
+        })
+    }
+
+    /// Returns the NAL units, such as parameter sets, stored in the `avcC` or
+    /// `hvcC` box of the sample entry.
+    pub fn get_configuration_nal_units(&self) -> Vec<&[u8]> {
+        let children = match self.sample_entry.and_then(|entry| entry.get_inner_boxes_ref()) {
+            Some(children) => children,
+            None => return vec![],
+        };
+        children.into_iter().find_map(|(_, child)| {
+            let child = child?.as_any();
+            child.downcast_ref::<Avcc>().map(|avcc| avcc.get_parameter_sets())
+                .or_else(|| child.downcast_ref::<Hvcc>().map(|hvcc| hvcc.get_nal_units()))
+        }).unwrap_or_default()
+    }
+}
+
+/// Walks through the auxiliary information of consecutive samples, as
+/// described by a pair of `saiz` and `saio` boxes.
+struct AuxInfoCursor<'a> {
+    saiz: &'a Saiz,
+    saio: &'a Saio,
+
+    /// Offset to which the `saio` offsets are relative.
+    base_offset: u64,
+    sample_index: u32,
+    next_offset: Option<u64>,
+
+    /// `true` once an offset did not fit in 64 bits, after which the
+    /// following samples have no auxiliary information.
+    overflowed: bool,
+}
+
+impl<'a> AuxInfoCursor<'a> {
+    /// Find the `saiz` and `saio` boxes describing the encryption parameters
+    /// of samples in the given box.
+    fn find(info: &'a IsoBoxInfo, entry: &'a dyn IsoBoxEntry, base_offset: u64) -> Option<Self> {
+        let is_cenc_type = |aux_info_type: Option<u32>| match aux_info_type {
+            None => true,
+            Some(t) => [b"cenc", b"cens", b"cbc1", b"cbcs"]
+                .iter()
+                .any(|scheme| t == u32::from_be_bytes(**scheme)),
+        };
+        let (_, saiz) = find_in::<Saiz>(info, entry)
+            .into_iter()
+            .find(|(_, saiz)| is_cenc_type(saiz.get_aux_info_type()))?;
+        let (_, saio) = find_in::<Saio>(info, entry)
+            .into_iter()
+            .find(|(_, saio)| is_cenc_type(saio.get_aux_info_type()))?;
+        Some(Self {
+            saiz,
+            saio,
+            base_offset,
+            sample_index: 0,
+            next_offset: None,
+            overflowed: false,
+        })
+    }
+
+    /// Indicates that the next samples belong to the chunk or track run with
+    /// the given 0-based index.
+    fn start_group(&mut self, group_index: usize) {
+        let offsets = self.saio.get_offsets();
+        if offsets.len() > 1 || group_index == 0 {
+            self.next_offset = offsets.get(group_index)
+                .and_then(|o| self.check_overflow(self.base_offset.checked_add(*o)));
+        }
+    }
+
+    /// Returns the offset and size of the next sample's auxiliary information.
+    fn next_sample(&mut self) -> Option<(u64, u8)> {
+        let size = self.saiz.get_sample_info_size(self.sample_index)?;
+        self.sample_index += 1;
+        let offset = self.next_offset?;
+        self.next_offset = self.check_overflow(offset.checked_add(size as u64));
+        Some((offset, size))
+    }
+
+    fn check_overflow(&mut self, offset: Option<u64>) -> Option<u64> {
+        self.overflowed |= offset.is_none();
+        offset
+    }
+
+    /// Returns a warning if some samples were left without auxiliary
+    /// information because of an offset not fitting in 64 bits.
+    fn get_warning(&self, location: String) -> Option<String> {
+        if self.overflowed {
+            Some(format!("{}: the auxiliary information of some samples goes beyond the \
+                largest 64-bit offset and is ignored", location))
+        } else {
+            None
+        }
+    }
+}
+
+/// Find every box parsed as `B` in the given boxes and their children.
+fn find_all<'a, B: 'static>(
+    boxes: impl Iterator<Item = (&'a IsoBoxInfo, Option<&'a dyn IsoBoxEntry>)>
+) -> Vec<&'a B> {
+    let mut found = vec![];
+    for (info, entry) in boxes {
+        collect_boxes::<B>(info, entry, &mut found);
+    }
+    found.into_iter().map(|f| f.1).collect()
+}
+
+/// Locate the samples of each track, from the sample tables of the `moov`
+/// box and from the track runs of the top-level `moof` boxes.
+pub fn get_track_samples(results: &[IsoBoxData]) -> Vec<TrackSamples> {
+    let top_level = || results.iter().map(|r| (r.0.as_ref(), r.1.as_deref()));
+    let mut tracks: Vec<TrackSamples> = vec![];
+
+    // No sample can end after the last box, unless its size is unknown
+    let file_end = results.last()
+        .filter(|(info, _)| info.size != 0)
+        .map_or(u64::MAX, |(info, _)| info.offset.saturating_add(info.size));
+
+    let mut traks: Vec<(&IsoBoxInfo, &Trak)> = vec![];
+    for (info, entry) in top_level() {
+        collect_boxes(info, entry, &mut traks);
+    }
+    for (trak_info, trak) in traks {
+        let track_id = match find_in::<Tkhd>(trak_info, trak).first() {
+            Some((_, tkhd)) => tkhd.get_track_id(),
+            None => continue,
+        };
+        let mdia = match find_in::<Mdia>(trak_info, trak).first() {
+            Some((_, mdia)) => *mdia as &dyn IsoBoxEntry,
+            None => continue,
+        };
+        let timescale = find_in::<Mdhd>(trak_info, mdia)
+            .first()
+            .map(|(_, mdhd)| mdhd.get_timescale());
+        let stsd = find_in::<Stsd>(trak_info, mdia).first().map(|(_, stsd)| *stsd);
+        let stsz = find_in::<Stsz>(trak_info, mdia).first().map(|(_, stsz)| *stsz);
+        let stsc = find_in::<Stsc>(trak_info, mdia).first().map(|(_, stsc)| *stsc);
+        let chunk_offsets = find_in::<Stco>(trak_info, mdia)
+            .first()
+            .map(|(_, stco)| stco.get_chunk_offsets())
+            .or_else(|| find_in::<Co64>(trak_info, mdia)
+                .first()
+                .map(|(_, co64)| co64.get_chunk_offsets()));
+        let first_entry = stsd.and_then(|stsd| stsd.get_entries().first());
+        let mut samples = vec![];
+        let mut warnings = vec![];
+        if let (Some(stsz), Some(stsc), Some(chunk_offsets)) = (stsz, stsc, chunk_offsets) {
+            let samples_per_chunk = stsc.get_samples_per_chunk(chunk_offsets.len() as u32);
+            let description_indexes =
+                stsc.get_sample_description_indexes(chunk_offsets.len() as u32);
+            // Both are read as samples are found, so only as many entries as
+            // there are `stsz` samples are considered
+            let mut durations = find_in::<Stts>(trak_info, mdia)
+                .first()
+                .map(|(_, stts)| stts.get_sample_durations());
+            let mut composition_offsets = find_in::<Ctts>(trak_info, mdia)
+                .first()
+                .map(|(_, ctts)| ctts.get_composition_offsets());
+            // Without a `stss` box, every sample is a sync sample
+            let sync_samples = find_in::<Stss>(trak_info, mdia)
+                .first()
+                .map(|(_, stss)| stss.get_sync_samples());
+            let mut sizes = stsz.get_sample_sizes();
+            let mut decode_time = 0;
+            let mut aux_info = AuxInfoCursor::find(trak_info, trak, 0);
+            let chunks = chunk_offsets.iter()
+                .zip(samples_per_chunk)
+                .zip(description_indexes)
+                .enumerate();
+            'chunks: for (chunk_index, ((chunk_offset, count), description_index)) in chunks {
+                if let Some(aux_info) = aux_info.as_mut() {
+                    aux_info.start_group(chunk_index);
+                }
+                let mut offset = *chunk_offset;
+                for _ in 0..count {
+                    let size = match sizes.next() {
+                        Some(size) => size,
+                        None => break 'chunks,
+                    };
+                    // Stop at a sample whose end does not fit in 64 bits or is
+                    // past the end of the file, the following ones being
+                    // either past it too or at an unknown offset
+                    let next_offset = match offset.checked_add(size as u64) {
+                        Some(next_offset) if next_offset <= file_end => next_offset,
+                        _ => {
+                            warnings.push(format!(
+                                "track {}: sample {} and the following ones are beyond the \
+                                end of the file and are ignored",
+                                track_id, samples.len() + 1));
+                            break 'chunks;
+                        },
+                    };
+                    let index = samples.len();
+                    let sample_number = index as u32 + 1;
+                    let duration = durations.as_mut().and_then(|d| d.next()).unwrap_or(0);
+                    let composition_offset = composition_offsets.as_mut()
+                        .and_then(|c| c.next())
+                        .unwrap_or(0);
+                    samples.push(SampleLocation {
+                        sample_number,
+                        offset,
+                        size,
+                        decode_time,
+                        duration,
+                        composition_offset,
+                        // Sync sample numbers are stored in increasing order
+                        is_sync: sync_samples.is_none_or(|s| s.binary_search(&sample_number).is_ok()),
+                        sample_description_index: description_index,
+                        encryption: None,
+                        auxiliary_information: aux_info.as_mut().and_then(|a| a.next_sample()),
+                    });
+                    offset = next_offset;
+                    // Stop at a sample whose decode time does not fit in 64 bits
+                    decode_time = match decode_time.checked_add(duration as u64) {
+                        Some(decode_time) => decode_time,
+                        None => break 'chunks,
+                    };
+                }
+            }
+            let location = format!("track {}", track_id);
+            warnings.extend(aux_info.and_then(|aux_info| aux_info.get_warning(location)));
+        }
+        if let Some((_, senc)) = find_in::<Senc>(trak_info, trak).first() {
+            for (sample, encryption) in samples.iter_mut().zip(senc.get_samples()) {
+                sample.encryption = Some(encryption.clone());
+            }
+        }
+        tracks.push(TrackSamples {
+            track_id,
+            codec: first_entry.map(|e| e.0.short_name.clone()),
+            sample_entry: first_entry.and_then(|e| e.1.as_deref()),
+            timescale,
+            samples,
+            warnings,
+        });
+    }
+
+    let trexs: Vec<&Trex> = find_all(top_level());
+    for (moof_info, moof) in results.iter() {
+        let moof = match moof.as_ref().and_then(|m| m.as_any().downcast_ref::<Moof>()) {
+            Some(moof) => moof,
+            None => continue,
+        };
+        // Unless the `default-base-is-moof` flag is set, the data of a track
+        // fragment directly follows the one of the previous track fragment
+        let mut previous_traf_end = moof_info.offset;
+        for (traf_info, traf) in find_in::<Traf>(moof_info, moof) {
+            let tfhd = match find_in::<Tfhd>(moof_info, traf).first() {
+                Some((_, tfhd)) => *tfhd,
+                None => continue,
+            };
+            let track_id = tfhd.get_track_id();
+            let trex = trexs.iter().find(|t| t.get_track_id() == track_id);
+            let default_size = tfhd.get_default_sample_size()
+                .or_else(|| trex.map(|t| t.get_default_sample_size()));
+            let default_duration = tfhd.get_default_sample_duration()
+                .or_else(|| trex.map(|t| t.get_default_sample_duration()))
+                .unwrap_or(0);
+            let default_flags = tfhd.get_default_sample_flags()
+                .or_else(|| trex.map(|t| t.get_default_sample_flags()));
+            let description_index = tfhd.get_sample_description_index()
+                .or_else(|| trex.map(|t| t.get_default_sample_description_index()))
+                .unwrap_or(1);
+            let base_offset = match tfhd.get_base_data_offset() {
+                Some(offset) => offset,
+                None if tfhd.is_default_base_moof() => moof_info.offset,
+                None => previous_traf_end,
+            };
+            let track = match tracks.iter_mut().position(|t| t.track_id == track_id) {
+                Some(pos) => &mut tracks[pos],
+                None => {
+                    tracks.push(TrackSamples {
+                        track_id,
+                        codec: None,
+                        sample_entry: None,
+                        timescale: None,
+                        samples: vec![],
+                        warnings: vec![],
+                    });
+                    tracks.last_mut().unwrap()
+                },
+            };
+
+            let first_traf_sample = track.samples.len();
+            // Without a `tfdt` box, decoding continues from the previous
+            // fragment
+            let mut decode_time = match find_in::<Tfdt>(moof_info, traf).first() {
+                Some((_, tfdt)) => tfdt.get_base_media_decode_time(),
+                None => match track.samples.last() {
+                    Some(s) => match s.decode_time.checked_add(s.duration as u64) {
+                        Some(decode_time) => decode_time,
+                        None => continue,
+                    },
+                    None => 0,
+                },
+            };
+            let mut aux_info = AuxInfoCursor::find(moof_info, traf, base_offset);
+
+            // Without a data offset, a run's data directly follows the one of
+            // the previous run
+            let mut next_offset = base_offset;
+            for (trun_index, (_, trun)) in find_in::<Trun>(moof_info, traf).into_iter().enumerate() {
+                if let Some(aux_info) = aux_info.as_mut() {
+                    aux_info.start_group(trun_index);
+                }
+                let mut offset = match trun.get_data_offset() {
+                    Some(data_offset) => match (base_offset as i64).checked_add(data_offset as i64) {
+                        Some(offset) => offset as u64,
+                        None => break,
+                    },
+                    None => next_offset,
+                };
+                let trun_samples = trun.get_sample_sizes().into_iter()
+                    .zip(trun.get_sample_durations())
+                    .zip(trun.get_sample_flags())
+                    .zip(trun.get_composition_time_offsets());
+                for (((size, duration), flags), composition_offset) in trun_samples {
+                    let size = match size.or(default_size) {
+                        Some(size) => size,
+                        None => break,
+                    };
+                    let sample_end = match offset.checked_add(size as u64) {
+                        Some(sample_end) => sample_end,
+                        None => break,
+                    };
+                    let duration = duration.unwrap_or(default_duration);
+                    let is_sync = flags.or(default_flags)
+                        .is_none_or(|flags| !flags.sample_is_non_sync_sample());
+                    track.samples.push(SampleLocation {
+                        sample_number: track.samples.len() as u32 + 1,
+                        offset,
+                        size,
+                        decode_time,
+                        duration,
+                        composition_offset,
+                        is_sync,
+                        sample_description_index: description_index,
+                        encryption: None,
+                        auxiliary_information: aux_info.as_mut().and_then(|a| a.next_sample()),
+                    });
+                    offset = sample_end;
+                    decode_time = match decode_time.checked_add(duration as u64) {
+                        Some(decode_time) => decode_time,
+                        None => break,
+                    };
+                }
+                next_offset = offset;
+            }
+            previous_traf_end = next_offset;
+            let location = format!("track {} traf at offset {}", track_id, traf_info.offset);
+            track.warnings.extend(aux_info.and_then(|aux_info| aux_info.get_warning(location)));
+            if let Some((_, senc)) = find_in::<Senc>(moof_info, traf).first() {
+                let traf_samples = track.samples[first_traf_sample..].iter_mut();
+                for (sample, encryption) in traf_samples.zip(senc.get_samples()) {
+                    sample.encryption = Some(encryption.clone());
+                }
+            }
+        }
+    }
+    tracks
+}
@@ -0,0 +1,201 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxData,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+    context::TrackContext,
+    sgpd::{Sgpd, SampleGroupEntry},
+};
+
+/// In a track fragment, group description indexes above this value refer to
+/// the `sgpd` boxes of the same fragment instead of the ones in the `stbl`.
+const FRAGMENT_LOCAL_INDEX_OFFSET: u32 = 0x10000;
+
+pub struct SbgpEntry {
+    sample_count: u32,
+    group_description_index: u32,
+}
+
+pub struct Sbgp {
+    version: u8,
+    flags: Flags,
+    grouping_type: String,
+    grouping_type_parameter: Option<u32>,
+    entry_count: u32,
+    entries: Vec<SbgpEntry>,
+}
+
+impl IsoBoxParser for Sbgp {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let grouping_type = reader.read_4cc()?;
+        let grouping_type_parameter = if version == 1 {
+            Some(reader.read_u32()?)
+        } else {
+            None
+        };
+        let entry_count = reader.read_u32()?;
+        // Do not trust the entry count more than the size of the box
+        let header_size = if version == 1 { 16 } else { 12 };
+        let max_count = content_size.map_or(0, |size| size.saturating_sub(header_size) / 8);
+        let mut entries = Vec::with_capacity(max_count.min(entry_count as u64) as usize);
+        for _ in 0..entry_count {
+            let sample_count = reader.read_u32()?;
+            let group_description_index = reader.read_u32()?;
+            entries.push(SbgpEntry { sample_count, group_description_index });
+        }
+        Ok(Self {
+            version,
+            flags,
+            grouping_type,
+            grouping_type_parameter,
+            entry_count,
+            entries,
+        })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        let mut values = vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("grouping_type", BoxValue::from(self.grouping_type.as_str())),
+        ];
+        if let Some(val) = self.grouping_type_parameter {
+            values.push(("grouping_type_parameter", BoxValue::from(val)));
+        }
+        values.push(("entry_count", BoxValue::from(self.entry_count)));
+        values.push(
+            ("entries", BoxValue::Collection(
+                    self.entries.iter().map(|entry| {
+                        vec![
+                            ("sample_count", BoxValue::from(entry.sample_count)),
+                            ("group_description_index",
+                             BoxValue::from(entry.group_description_index)),
+                        ]
+                    }).collect()
+            )));
+        values
+    }
+
+    fn get_short_name() -> &'static str {
+        "sbgp"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+/// Association of consecutive samples to the group description they belong
+/// to.
+pub struct SampleToGroup {
+    /// 1-based number of the first sample in its track (or track fragment).
+    first_sample_number: u32,
+    sample_count: u32,
+    grouping_type: String,
+    group_description_index: u32,
+
+    /// The corresponding group description.
+    /// `None` if the sample is not part of a group of that type or if the
+    /// description could not be found.
+    description: Option<SampleGroupEntry>,
+}
+
+impl SampleToGroup {
+    pub fn get_values(&self) -> Vec<(&'static str, BoxValue)> {
+        let mut values = vec![
+            ("first_sample_number", BoxValue::from(self.first_sample_number)),
+            ("sample_count", BoxValue::from(self.sample_count)),
+            ("grouping_type", BoxValue::from(self.grouping_type.as_str())),
+            ("group_description_index", BoxValue::from(self.group_description_index)),
+        ];
+        if let Some(description) = &self.description {
+            values.append(&mut description.get_values());
+        }
+        values
+    }
+}
+
+/// Combine the `sbgp` and `sgpd` boxes found in `children` (the content of
+/// either a `stbl` or a `traf` box) to associate each run of samples to its
+/// group description.
+///
+/// `track` is the information known about the corresponding track. In a
+/// track fragment, it is used to retrieve the group descriptions defined in
+/// that track's sample table.
+pub fn map_samples_to_groups(
+    children: &[IsoBoxData],
+    is_fragment: bool,
+    track: Option<&TrackContext>
+) -> Vec<SampleToGroup> {
+    let boxes = children
+        .iter()
+        .filter_map(|c| c.1.as_ref().map(|b| b.as_any()));
+    let sgpds: Vec<&Sgpd> = boxes.clone().filter_map(|b| b.downcast_ref::<Sgpd>()).collect();
+    let mut mappings = vec![];
+    for sbgp in boxes.filter_map(|b| b.downcast_ref::<Sbgp>()) {
+        let local_sgpd = sgpds
+            .iter()
+            .find(|sgpd| sgpd.get_grouping_type() == sbgp.grouping_type);
+        let global_descriptions = track
+            .and_then(|t| t.sample_group_descriptions.get(&sbgp.grouping_type));
+        let mut sample_number: u32 = 1;
+        for entry in sbgp.entries.iter() {
+            if entry.sample_count == 0 {
+                continue;
+            }
+            let index = entry.group_description_index;
+            let description = if !is_fragment {
+                local_sgpd.and_then(|sgpd| sgpd.get_entry(index))
+            } else if index > FRAGMENT_LOCAL_INDEX_OFFSET {
+                local_sgpd.and_then(|sgpd| sgpd.get_entry(index - FRAGMENT_LOCAL_INDEX_OFFSET))
+            } else if index > 0 {
+                global_descriptions.and_then(|d| d.get(index as usize - 1))
+            } else {
+                None
+            };
+            mappings.push(SampleToGroup {
+                first_sample_number: sample_number,
+                sample_count: entry.sample_count,
+                grouping_type: sbgp.grouping_type.clone(),
+                group_description_index: index,
+                description: description.cloned(),
+            });
+            // Sample numbers past the largest 32-bit one cannot be described
+            sample_number = match sample_number.checked_add(entry.sample_count) {
+                Some(sample_number) => sample_number,
+                None => break,
+            };
+        }
+    }
+    mappings
+}
+
+impl IsoBoxSerializer for Sbgp {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_4cc(&self.grouping_type)?;
+        if let Some(parameter) = self.grouping_type_parameter {
+            writer.write_u32(parameter)?;
+        }
+        writer.write_u32(self.entry_count)?;
+        for entry in self.entries.iter() {
+            writer.write_u32(entry.sample_count)?;
+            writer.write_u32(entry.group_description_index)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    FlagNames,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+    utils::decode_text,
+};
+
+pub struct Schm {
+    version: u8,
+    flags: Flags,
+    scheme_type: String,
+    scheme_version: u32,
+    scheme_uri: Option<String>,
+    /// The scheme URI as encoded in the box.
+    scheme_uri_data: Vec<u8>,
+}
+
+const FLAG_NAMES: FlagNames = &[
+    (0x000001, "scheme-uri-present"),
+];
+
+impl Schm {
+    /// Returns the protection scheme, such as "cenc" or "cbcs".
+    pub fn get_scheme_type(&self) -> &str {
+        &self.scheme_type
+    }
+}
+
+impl IsoBoxParser for Schm {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let scheme_type = reader.read_4cc()?;
+        let scheme_version = reader.read_u32()?;
+        let scheme_uri_data = if flags.has_flag(0x01) {
+            match content_size {
+                Some(size) => reader.read_bytes(size.saturating_sub(12) as usize)?,
+                None => reader.read_to_end()?,
+            }
+        } else {
+            vec![]
+        };
+        let scheme_uri = if flags.has_flag(0x01) {
+            Some(decode_text(&scheme_uri_data))
+        } else {
+            None
+        };
+        Ok(Self { version, flags, scheme_type, scheme_version, scheme_uri, scheme_uri_data })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        let mut values = vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("scheme_type", BoxValue::from(self.scheme_type.as_str())),
+            ("scheme_version", BoxValue::from(self.scheme_version)),
+        ];
+        if let Some(uri) = &self.scheme_uri {
+            values.push(("scheme_uri", BoxValue::from(uri.as_str())));
+        }
+        values
+    }
+
+    fn get_short_name() -> &'static str {
+        "schm"
+    }
+
+    fn get_flag_names() -> FlagNames {
+        FLAG_NAMES
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Schm {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_4cc(&self.scheme_type)?;
+        writer.write_u32(self.scheme_version)?;
+        writer.write_bytes(&self.scheme_uri_data)
+    }
+}
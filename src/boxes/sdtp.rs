@@ -0,0 +1,80 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub struct Sdtp {
+    version: u8,
+    flags: Flags,
+
+    /// One byte per sample, each containing the `is_leading`,
+    /// `sample_depends_on`, `sample_is_depended_on` and `sample_has_redundancy`
+    /// values, on two bits each.
+    entries: Vec<u8>,
+}
+
+impl IsoBoxParser for Sdtp {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+
+        // The sample count is not part of this box, it has to be deduced from
+        // its size.
+        let entries = if let Some(size) = content_size {
+            let mut entries = Vec::with_capacity(size.saturating_sub(4) as usize);
+            for _ in 4..size {
+                entries.push(reader.read_u8()?);
+            }
+            entries
+        } else {
+            reader.read_to_end()?
+        };
+        Ok(Self { version, flags, entries })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("sample_count", BoxValue::from(self.entries.len() as u32)),
+            ("samples", BoxValue::Collection(
+                    self.entries.iter().map(|entry| {
+                        vec![
+                            ("is_leading", BoxValue::from((entry >> 6) & 0x03)),
+                            ("sample_depends_on", BoxValue::from((entry >> 4) & 0x03)),
+                            ("sample_is_depended_on", BoxValue::from((entry >> 2) & 0x03)),
+                            ("sample_has_redundancy", BoxValue::from(entry & 0x03)),
+                        ]
+                    }).collect()
+            )),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "sdtp"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Sdtp {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_bytes(&self.entries)
+    }
+}
@@ -0,0 +1,172 @@
+use std::io::BufRead;
+use std::rc::Rc;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    FlagNames,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+/// Encryption parameters of a single sample.
+#[derive(Clone)]
+pub struct SampleEncryption {
+    /// Initialization vector, empty if a constant IV is used.
+    pub iv: Vec<u8>,
+
+    /// Number of clear bytes followed by the number of protected bytes, for
+    /// each subsample. Empty if the whole sample is protected.
+    pub subsamples: Vec<(u16, u32)>,
+
+    /// The sample's entry as stored in the `senc` box, which is also the
+    /// format of the sample's auxiliary information.
+    pub data: Vec<u8>,
+}
+
+pub struct Senc {
+    version: u8,
+    flags: Flags,
+    sample_count: u32,
+    samples: Vec<SampleEncryption>,
+}
+
+const FLAG_NAMES: FlagNames = &[
+    (0x000002, "use-subsample-encryption"),
+];
+
+impl Senc {
+    pub fn get_sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    pub fn get_samples(&self) -> &[SampleEncryption] {
+        &self.samples
+    }
+}
+
+impl SampleEncryption {
+    /// Parse the encryption parameters of a sample stored as auxiliary
+    /// information, which have subsamples only if `data` goes beyond the IV.
+    /// `None` if they do not exactly fill `data`.
+    pub fn parse_auxiliary_information(data: &[u8], iv_size: usize) -> Option<Self> {
+        parse_entries(data, 1, iv_size, data.len() > iv_size)?.pop()
+    }
+}
+
+/// Parse the `sample_count` entries of a `senc` box with the given IV size.
+/// `None` if they do not exactly fill `data`.
+fn parse_entries(
+    data: &[u8],
+    sample_count: u32,
+    iv_size: usize,
+    has_subsamples: bool
+) -> Option<Vec<SampleEncryption>> {
+    let mut samples = Vec::with_capacity(sample_count.min(data.len() as u32) as usize);
+    let mut pos = 0;
+    for _ in 0..sample_count {
+        let start = pos;
+        let iv = data.get(pos..pos + iv_size)?.to_vec();
+        pos += iv_size;
+        let mut subsamples = vec![];
+        if has_subsamples {
+            let count = u16::from_be_bytes([*data.get(pos)?, *data.get(pos + 1)?]);
+            pos += 2;
+            for _ in 0..count {
+                let entry = data.get(pos..pos + 6)?;
+                subsamples.push((
+                    u16::from_be_bytes([entry[0], entry[1]]),
+                    u32::from_be_bytes([entry[2], entry[3], entry[4], entry[5]])));
+                pos += 6;
+            }
+        }
+        samples.push(SampleEncryption { iv, subsamples, data: data[start..pos].to_vec() });
+    }
+    if pos == data.len() { Some(samples) } else { None }
+}
+
+impl IsoBoxParser for Senc {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        box_info: &Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let sample_count = reader.read_u32()?;
+        let data = match content_size {
+            Some(size) => reader.read_bytes(size.saturating_sub(8) as usize)?,
+            None => reader.read_to_end()?,
+        };
+        let has_subsamples = flags.has_flag(0x02);
+
+        // The IV size is only known if the track's `tenc` box has been
+        // encountered. Otherwise, try the sizes allowed for it.
+        let iv_sizes = match reader.get_context().get_current_track()
+            .and_then(|t| t.per_sample_iv_size)
+        {
+            Some(size) => vec![size as usize],
+            None => vec![16, 8, 0],
+        };
+        let samples = iv_sizes.into_iter()
+            .find_map(|iv_size| parse_entries(&data, sample_count, iv_size, has_subsamples))
+            .ok_or_else(|| BoxParsingError::InvalidFieldValue {
+                box_info: Rc::clone(box_info),
+                field: "sample_count",
+                value: sample_count as u64,
+            })?;
+        Ok(Self { version, flags, sample_count, samples })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("sample_count", BoxValue::from(self.sample_count)),
+            ("samples", BoxValue::Collection(self.samples.iter().map(|sample| {
+                let mut values = vec![];
+                if !sample.iv.is_empty() {
+                    values.push(("InitializationVector", BoxValue::Hex(&sample.iv)));
+                }
+                if self.flags.has_flag(0x02) {
+                    values.push(("subsample_count", BoxValue::from(sample.subsamples.len() as u32)));
+                    values.push(("subsamples", BoxValue::Collection(
+                        sample.subsamples.iter().map(|(clear, protected)| vec![
+                            ("BytesOfClearData", BoxValue::from(*clear)),
+                            ("BytesOfProtectedData", BoxValue::from(*protected)),
+                        ]).collect()
+                    )));
+                }
+                values
+            }).collect())),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "senc"
+    }
+
+    fn get_flag_names() -> FlagNames {
+        FLAG_NAMES
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Senc {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_u32(self.sample_count)?;
+        for sample in self.samples.iter() {
+            writer.write_bytes(&sample.data)?;
+        }
+        Ok(())
+    }
+}
@@ -1,42 +1,394 @@
 use std::io::BufRead;
+use std::rc::Rc;
 use super::{
     IsoBoxInfo,
     BoxParsingError,
     BoxReader,
+    BoxWriter,
     BoxValue,
+    Flags,
     IsoBoxEntry,
     IsoBoxParser,
+    IsoBoxSerializer,
 };
 
-pub struct Sgpd { }
+/// A single decoded sample group description.
+#[derive(Clone)]
+pub enum SampleGroupEntry {
+    /// `roll` and `prol` grouping types.
+    RollRecovery {
+        roll_distance: i16,
+    },
+
+    /// `rap ` grouping type.
+    RandomAccessPoint {
+        num_leading_samples_known: bool,
+        num_leading_samples: u8,
+    },
+
+    /// `seig` grouping type, for key rotation in encrypted content.
+    CencSampleEncryptionInfo {
+        crypt_byte_block: u8,
+        skip_byte_block: u8,
+        is_protected: u8,
+        per_sample_iv_size: u8,
+        kid: [u8; 16],
+        constant_iv: Vec<u8>,
+    },
+
+    /// `sync` grouping type.
+    SyncSample {
+        nal_unit_type: u8,
+    },
+
+    /// `tele` grouping type.
+    TemporalLevel {
+        level_independently_decodable: bool,
+    },
+
+    /// `alst` grouping type.
+    AlternativeStartup {
+        roll_count: u16,
+        first_output_sample: u16,
+        sample_offset: Vec<u32>,
+        /// `num_output_samples` and `num_total_samples` pairs.
+        output_samples: Vec<(u16, u16)>,
+    },
+
+    /// Any other grouping type, whose content is kept as is.
+    Unknown(Vec<u8>),
+}
+
+impl SampleGroupEntry {
+    /// Decode a sample group description entry from its raw bytes.
+    /// Fall back to `SampleGroupEntry::Unknown` if the grouping type is not
+    /// handled or if the data is not of the expected size.
+    pub fn decode(grouping_type: &str, data: Vec<u8>) -> Self {
+        match (grouping_type, data.len()) {
+            ("roll", 2) | ("prol", 2) => SampleGroupEntry::RollRecovery {
+                roll_distance: i16::from_be_bytes([data[0], data[1]]),
+            },
+            ("rap ", 1) => SampleGroupEntry::RandomAccessPoint {
+                num_leading_samples_known: data[0] & 0x80 != 0,
+                num_leading_samples: data[0] & 0x7F,
+            },
+            ("seig", len) if len >= 20 => {
+                let is_protected = data[2];
+                let per_sample_iv_size = data[3];
+                let mut kid = [0u8; 16];
+                kid.copy_from_slice(&data[4..20]);
+                let constant_iv = if is_protected == 1 && per_sample_iv_size == 0 && len > 20 {
+                    let iv_size = data[20] as usize;
+                    data[21..].iter().take(iv_size).copied().collect()
+                } else {
+                    vec![]
+                };
+                SampleGroupEntry::CencSampleEncryptionInfo {
+                    crypt_byte_block: data[1] >> 4,
+                    skip_byte_block: data[1] & 0x0F,
+                    is_protected,
+                    per_sample_iv_size,
+                    kid,
+                    constant_iv,
+                }
+            },
+            ("sync", 1) => SampleGroupEntry::SyncSample {
+                nal_unit_type: data[0] & 0x3F,
+            },
+            ("tele", 1) => SampleGroupEntry::TemporalLevel {
+                level_independently_decodable: data[0] & 0x80 != 0,
+            },
+            ("alst", len) if len >= 4 => {
+                let roll_count = u16::from_be_bytes([data[0], data[1]]);
+                let first_output_sample = u16::from_be_bytes([data[2], data[3]]);
+                let offsets_end = 4 + roll_count as usize * 4;
+                if offsets_end > len {
+                    return SampleGroupEntry::Unknown(data);
+                }
+                let sample_offset = data[4..offsets_end]
+                    .chunks_exact(4)
+                    .map(|c| u32::from_be_bytes([c[0], c[1], c[2], c[3]]))
+                    .collect();
+                let output_samples = data[offsets_end..]
+                    .chunks_exact(4)
+                    .map(|c| (u16::from_be_bytes([c[0], c[1]]), u16::from_be_bytes([c[2], c[3]])))
+                    .collect();
+                SampleGroupEntry::AlternativeStartup {
+                    roll_count,
+                    first_output_sample,
+                    sample_offset,
+                    output_samples,
+                }
+            },
+            _ => SampleGroupEntry::Unknown(data),
+        }
+    }
+
+    pub fn get_values(&self) -> Vec<(&'static str, BoxValue)> {
+        match self {
+            SampleGroupEntry::RollRecovery { roll_distance } => vec![
+                ("roll_distance", BoxValue::from(*roll_distance)),
+            ],
+            SampleGroupEntry::RandomAccessPoint {
+                num_leading_samples_known,
+                num_leading_samples
+            } => vec![
+                ("num_leading_samples_known", BoxValue::from(*num_leading_samples_known)),
+                ("num_leading_samples", BoxValue::from(*num_leading_samples)),
+            ],
+            SampleGroupEntry::CencSampleEncryptionInfo {
+                crypt_byte_block,
+                skip_byte_block,
+                is_protected,
+                per_sample_iv_size,
+                kid,
+                constant_iv,
+            } => {
+                let mut values = vec![
+                    ("crypt_byte_block", BoxValue::from(*crypt_byte_block)),
+                    ("skip_byte_block", BoxValue::from(*skip_byte_block)),
+                    ("is_protected", BoxValue::from(*is_protected)),
+                    ("per_sample_iv_size", BoxValue::from(*per_sample_iv_size)),
+                    ("kid", BoxValue::Hex(kid)),
+                ];
+                if !constant_iv.is_empty() {
+                    values.push(("constant_iv_size", BoxValue::from(constant_iv.len() as u8)));
+                    values.push(("constant_iv", BoxValue::Hex(constant_iv)));
+                }
+                values
+            },
+            SampleGroupEntry::SyncSample { nal_unit_type } => vec![
+                ("nal_unit_type", BoxValue::from(*nal_unit_type)),
+            ],
+            SampleGroupEntry::TemporalLevel { level_independently_decodable } => vec![
+                ("level_independently_decodable",
+                 BoxValue::from(*level_independently_decodable)),
+            ],
+            SampleGroupEntry::AlternativeStartup {
+                roll_count,
+                first_output_sample,
+                sample_offset,
+                output_samples,
+            } => vec![
+                ("roll_count", BoxValue::from(*roll_count)),
+                ("first_output_sample", BoxValue::from(*first_output_sample)),
+                ("sample_offset", BoxValue::from(sample_offset.as_slice())),
+                ("output_samples", BoxValue::Collection(
+                        output_samples.iter().map(|(output, total)| {
+                            vec![
+                                ("num_output_samples", BoxValue::from(*output)),
+                                ("num_total_samples", BoxValue::from(*total)),
+                            ]
+                        }).collect()
+                )),
+            ],
+            SampleGroupEntry::Unknown(data) => vec![
+                ("data", BoxValue::Hex(data)),
+            ],
+        }
+    }
+}
+
+pub struct SgpdEntry {
+    description_length: Option<u32>,
+    entry: SampleGroupEntry,
+    /// The entry as encoded in the box.
+    data: Vec<u8>,
+}
+
+pub struct Sgpd {
+    version: u8,
+    flags: Flags,
+    grouping_type: String,
+    default_length: Option<u32>,
+    default_sample_description_index: Option<u32>,
+    entry_count: u32,
+    entries: Vec<SgpdEntry>,
+}
+
+impl Sgpd {
+    pub fn get_grouping_type(&self) -> &str {
+        &self.grouping_type
+    }
+
+    /// Returns the group description at the given 1-based index.
+    pub fn get_entry(&self, index: u32) -> Option<&SampleGroupEntry> {
+        if index == 0 {
+            return None;
+        }
+        self.entries.get(index as usize - 1).map(|e| &e.entry)
+    }
+
+    pub fn get_entries(&self) -> Vec<SampleGroupEntry> {
+        self.entries.iter().map(|e| e.entry.clone()).collect()
+    }
+}
+
+/// Returns the size in bytes of a sample group description entry of the
+/// given grouping type, for when that size is not indicated in the `sgpd`.
+/// `None` if it cannot be deduced from the grouping type alone.
+fn get_known_entry_length<T: BufRead>(
+    reader: &mut BoxReader<T>,
+    grouping_type: &str,
+    prefix: &mut Vec<u8>
+) -> Result<Option<u32>, BoxParsingError> {
+    match grouping_type {
+        "roll" | "prol" => Ok(Some(2)),
+        "rap " | "sync" | "tele" => Ok(Some(1)),
+        "seig" => {
+            // The optional constant IV makes its size depend on its content
+            for _ in 0..20 {
+                prefix.push(reader.read_u8()?);
+            }
+            if prefix[2] == 1 && prefix[3] == 0 {
+                let iv_size = reader.read_u8()?;
+                prefix.push(iv_size);
+                Ok(Some(21 + iv_size as u32))
+            } else {
+                Ok(Some(20))
+            }
+        },
+        _ => Ok(None),
+    }
+}
 
-// XXX TODO
 impl IsoBoxParser for Sgpd {
     fn parse<T: BufRead>(
         reader: &mut BoxReader<T>,
-        _content_size: Option<u64>,
-        _box_info: &std::rc::Rc<IsoBoxInfo>
+        content_size: Option<u64>,
+        box_info: &Rc<IsoBoxInfo>
     ) -> Result<Self, BoxParsingError> {
-        Ok(Self { })
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let grouping_type = reader.read_4cc()?;
+        let mut bytes_read: u64 = 8;
+        let default_length = if version == 1 {
+            bytes_read += 4;
+            Some(reader.read_u32()?)
+        } else {
+            None
+        };
+        let default_sample_description_index = if version >= 2 {
+            bytes_read += 4;
+            Some(reader.read_u32()?)
+        } else {
+            None
+        };
+        let entry_count = reader.read_u32()?;
+        bytes_read += 4;
+
+        let mut entries = Vec::with_capacity(entry_count as usize);
+        for i in 0..entry_count {
+            let description_length = if default_length == Some(0) {
+                bytes_read += 4;
+                Some(reader.read_u32()?)
+            } else {
+                None
+            };
+            let mut data = vec![];
+            let length = match description_length.or(default_length) {
+                Some(length) => length,
+                None => match get_known_entry_length(reader, &grouping_type, &mut data)? {
+                    Some(length) => length,
+                    None => {
+                        // Unknown grouping type with no indicated size:
+                        // consider that all remaining entries share the
+                        // remaining data equally.
+                        let remaining = content_size
+                            .map_or(0, |size| size.saturating_sub(bytes_read));
+                        (remaining / (entry_count - i) as u64) as u32
+                    }
+                },
+            };
+            while (data.len() as u32) < length {
+                data.push(reader.read_u8()?);
+            }
+            bytes_read += length as u64;
+            entries.push(SgpdEntry {
+                description_length,
+                entry: SampleGroupEntry::decode(&grouping_type, data.clone()),
+                data,
+            });
+        }
+
+        let sgpd = Self {
+            version,
+            flags,
+            grouping_type,
+            default_length,
+            default_sample_description_index,
+            entry_count,
+            entries,
+        };
+
+        // Descriptions in the sample table may be referenced by the
+        // fragments of that track.
+        let is_in_stbl = box_info.parent_box_info
+            .as_ref()
+            .is_some_and(|parent| parent.short_name == "stbl");
+        if is_in_stbl {
+            if let Some(track) = reader.get_context_mut().get_current_track_mut() {
+                track.sample_group_descriptions
+                    .insert(sgpd.grouping_type.clone(), sgpd.get_entries());
+            }
+        }
+        Ok(sgpd)
     }
 
     fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
-        vec![]
+        let mut values = vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("grouping_type", BoxValue::from(self.grouping_type.as_str())),
+        ];
+        if let Some(val) = self.default_length {
+            values.push(("default_length", BoxValue::from(val)));
+        }
+        if let Some(val) = self.default_sample_description_index {
+            values.push(("default_sample_description_index", BoxValue::from(val)));
+        }
+        values.push(("entry_count", BoxValue::from(self.entry_count)));
+        values.push(
+            ("entries", BoxValue::Collection(
+                    self.entries.iter().map(|entry| {
+                        let mut entry_values = vec![];
+                        if let Some(val) = entry.description_length {
+                            entry_values.push(("description_length", BoxValue::from(val)));
+                        }
+                        entry_values.append(&mut entry.entry.get_values());
+                        entry_values
+                    }).collect()
+            )));
+        values
     }
 
     fn get_short_name() -> &'static str {
         "sgpd"
     }
 
-    fn get_long_name() -> &'static str {
-        "Sample Group Description Box"
-    }
-
-    fn get_inner_boxes(self) -> Option<Vec<super::IsoBoxData>> {
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
         None
     }
+}
 
-    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
-        None
+impl IsoBoxSerializer for Sgpd {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_4cc(&self.grouping_type)?;
+        if let Some(default_length) = self.default_length {
+            writer.write_u32(default_length)?;
+        }
+        if let Some(index) = self.default_sample_description_index {
+            writer.write_u32(index)?;
+        }
+        writer.write_u32(self.entry_count)?;
+        for entry in self.entries.iter() {
+            if entry.description_length.is_some() {
+                writer.write_u32(entry.data.len() as u32)?;
+            }
+            writer.write_bytes(&entry.data)?;
+        }
+        Ok(())
     }
 }
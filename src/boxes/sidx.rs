@@ -4,10 +4,12 @@ use super::{
     IsoBoxInfo,
     BoxParsingError,
     BoxReader,
+    BoxWriter,
     BoxValue,
     Flags,
     IsoBoxEntry,
     IsoBoxParser,
+    IsoBoxSerializer,
 };
 
 pub struct SidxReference {
@@ -29,13 +31,106 @@ pub struct Sidx {
     reserved: u16,
     reference_count: u16,
     references: Vec<SidxReference>,
+
+    /// Offset in the file of the first byte following this `sidx` box, from
+    /// which the `first_offset` is expressed.
+    anchor_point: u64,
+}
+
+/// A `sidx` reference, related to the whole file.
+pub struct ResolvedSidxReference {
+    /// If `true`, this reference points to another `sidx` box.
+    /// If `false`, it points to media content.
+    pub is_sidx_reference: bool,
+    /// Offset of the first byte referenced in the file.
+    pub start_offset: u64,
+    /// Offset of the first byte following the referenced data in the file.
+    pub end_offset: u64,
+    /// Presentation time at which the referenced data starts, in the `sidx`'s
+    /// timescale.
+    pub start_time: u64,
+    /// Presentation time at which the referenced data ends, in the `sidx`'s
+    /// timescale.
+    pub end_time: u64,
+}
+
+impl SidxReference {
+    /// Create a reference to media content.
+    pub fn new(
+        referenced_size: u32,
+        subsegment_duration: u32,
+        starts_with_sap: bool,
+        sap_type: u8
+    ) -> Self {
+        Self {
+            reference_type: 0,
+            referenced_size,
+            subsegment_duration,
+            starts_with_sap,
+            sap_type,
+            sap_delta_time: 0,
+        }
+    }
+}
+
+impl Sidx {
+    /// Create a `sidx` box whose first reference directly follows it.
+    pub fn new(
+        reference_id: u32,
+        timescale: u32,
+        earliest_presentation_time: u64,
+        references: Vec<SidxReference>
+    ) -> Self {
+        let version = if earliest_presentation_time > u32::MAX as u64 { 1 } else { 0 };
+        Self {
+            version,
+            flags: Flags::from(0),
+            reference_id,
+            timescale,
+            earliest_presentation_time,
+            first_offset: 0,
+            reserved: 0,
+            reference_count: references.len() as u16,
+            references,
+            anchor_point: 0,
+        }
+    }
+
+    pub fn get_timescale(&self) -> u32 {
+        self.timescale
+    }
+
+    pub fn get_earliest_presentation_time(&self) -> u64 {
+        self.earliest_presentation_time
+    }
+
+    /// Compute the absolute byte range and the presentation times of each of
+    /// this `sidx`'s references.
+    /// Offsets and times which would not fit in 64 bits, which can only
+    /// happen with a corrupted box, are capped to `u64::MAX`.
+    pub fn resolve_references(&self) -> Vec<ResolvedSidxReference> {
+        let mut offset = self.anchor_point.saturating_add(self.first_offset);
+        let mut time = self.earliest_presentation_time;
+        self.references.iter().map(|r| {
+            let resolved = ResolvedSidxReference {
+                is_sidx_reference: r.reference_type == 1,
+                start_offset: offset,
+                end_offset: offset.saturating_add(r.referenced_size as u64),
+                start_time: time,
+                end_time: time.saturating_add(r.subsegment_duration as u64),
+            };
+            offset = resolved.end_offset;
+            time = resolved.end_time;
+            resolved
+        }).collect()
+    }
 }
 
 impl IsoBoxParser for Sidx {
     fn parse<T: BufRead>(
         reader: &mut BoxReader<T>,
         _content_size: Option<u64>,
-        _box_info: &Rc<IsoBoxInfo>
+        box_info: &Rc<IsoBoxInfo>
     ) -> Result<Self, BoxParsingError> {
         let version = reader.read_u8()?;
         let flags = Flags::read(reader)?;
@@ -64,7 +159,7 @@ impl IsoBoxParser for Sidx {
                 sap_delta_time: third_4_bytes & 0x0FFFFFFF,
             });
         }
-        Ok(Self {
+        let sidx = Self {
             version,
             flags,
             reference_id,
@@ -74,7 +169,14 @@ impl IsoBoxParser for Sidx {
             reserved,
             reference_count,
             references,
-        })
+            anchor_point: box_info.offset + box_info.size,
+        };
+        let byte_ranges = sidx.resolve_references()
+            .iter()
+            .map(|r| (r.start_offset, r.end_offset))
+            .collect();
+        reader.get_context_mut().set_sidx_references(byte_ranges);
+        Ok(sidx)
     }
 
     fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
@@ -83,22 +185,46 @@ impl IsoBoxParser for Sidx {
             ("flags", BoxValue::from(self.flags)),
             ("reference_id", BoxValue::from(self.reference_id)),
             ("timescale", BoxValue::from(self.timescale)),
-            ("earliest_presentation_time", BoxValue::from(self.earliest_presentation_time)),
+            ("earliest_presentation_time",
+             BoxValue::from_ticks(self.earliest_presentation_time, Some(self.timescale))),
             ("first_offset", BoxValue::from(self.first_offset)),
             ("reserved", BoxValue::from(self.reserved)),
             ("reference_count", BoxValue::from(self.reference_count)),
             ("references", BoxValue::Collection(
                         self.references
                          .iter()
-                         .map(|r| {
-                            vec![
+                         .zip(self.resolve_references())
+                         .map(|(r, resolved)| {
+                            let mut values = vec![
                                 ("reference_type", BoxValue::from(r.reference_type)),
                                 ("referenced_size", BoxValue::from(r.referenced_size)),
-                                ("subsegment_duration", BoxValue::from(r.subsegment_duration)),
+                                ("subsegment_duration",
+                                 BoxValue::from_ticks(
+                                     r.subsegment_duration as u64,
+                                     Some(self.timescale))),
                                 ("starts_with_sap", BoxValue::from(r.starts_with_sap)),
                                 ("sap_type", BoxValue::from(r.sap_type)),
-                                ("sap_delta_time", BoxValue::from(r.sap_delta_time)),
-                            ]
+                                ("sap_delta_time",
+                                 BoxValue::from_ticks(
+                                     r.sap_delta_time as u64,
+                                     Some(self.timescale))),
+                                ("start_offset", BoxValue::from(resolved.start_offset)),
+                                ("end_offset", BoxValue::from(resolved.end_offset)),
+                                ("start_time",
+                                 BoxValue::from_ticks(resolved.start_time, Some(self.timescale))),
+                                ("end_time",
+                                 BoxValue::from_ticks(resolved.end_time, Some(self.timescale))),
+                            ];
+                            // Presentation time of the first SAP, unless it
+                            // does not fit in 64 bits
+                            let sap_time = resolved.start_time
+                                .checked_add(r.sap_delta_time as u64)
+                                .filter(|_| r.sap_type != 0);
+                            if let Some(sap_time) = sap_time {
+                                values.push(
+                                    ("sap_time", BoxValue::from_ticks(sap_time, Some(self.timescale))));
+                            }
+                            values
                          })
                          .collect::<Vec<Vec<(&str, BoxValue)>>>()
                     )
@@ -110,15 +236,98 @@ impl IsoBoxParser for Sidx {
         "sidx"
     }
 
-    fn get_long_name() -> &'static str {
-        "Segment index Box"
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
     }
+}
 
-    fn get_inner_boxes(self) -> Option<Vec<super::IsoBoxData>> {
-        None
+impl IsoBoxSerializer for Sidx {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_u32(self.reference_id)?;
+        writer.write_u32(self.timescale)?;
+        if self.version == 0 {
+            writer.write_u32(self.earliest_presentation_time as u32)?;
+            writer.write_u32(self.first_offset as u32)?;
+        } else {
+            writer.write_u64(self.earliest_presentation_time)?;
+            writer.write_u64(self.first_offset)?;
+        }
+        writer.write_u16(self.reserved)?;
+        writer.write_u16(self.reference_count)?;
+        for reference in self.references.iter() {
+            writer.write_u32((reference.reference_type as u32) << 31 | reference.referenced_size)?;
+            writer.write_u32(reference.subsegment_duration)?;
+            writer.write_u32(
+                (reference.starts_with_sap as u32) << 31 |
+                (reference.sap_type as u32) << 28 |
+                reference.sap_delta_time)?;
+        }
+        Ok(())
     }
+}
 
-    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
-        None
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use super::{Sidx, SidxReference};
+    use super::super::{parse_isobmff, utils::collect_boxes};
+
+    /// is_sidx_reference, start_offset, end_offset, start_time and end_time
+    /// of a resolved reference.
+    type ReferenceSummary = (bool, u64, u64, u64, u64);
+
+    /// Returns the resolved references of each `sidx` box of the given file.
+    fn read_resolved_references(file: &[u8]) -> Vec<Vec<ReferenceSummary>> {
+        let data = parse_isobmff(Cursor::new(file)).unwrap();
+        let mut found: Vec<(_, &Sidx)> = vec![];
+        for (info, entry) in data.iter() {
+            collect_boxes(info, entry.as_deref(), &mut found);
+        }
+        found.into_iter()
+            .map(|(_, sidx)| sidx.resolve_references()
+                .into_iter()
+                .map(|r| (r.is_sidx_reference, r.start_offset, r.end_offset, r.start_time, r.end_time))
+                .collect())
+            .collect()
+    }
+
+    #[test]
+    fn resolve_hierarchical_references() {
+        let path = format!("{}/tests/fixtures/sidx.mp4", env!("CARGO_MANIFEST_DIR"));
+        let input = std::fs::read(&path).unwrap();
+        assert_eq!(read_resolved_references(&input), vec![
+            // Reference to the following `sidx` box
+            vec![(true, 60, 380, 1000, 5000)],
+            vec![(false, 116, 248, 1000, 3000), (false, 248, 376, 3000, 5000)],
+        ]);
+    }
+
+    #[test]
+    fn resolve_references_after_first_offset() {
+        let path = format!("{}/tests/fixtures/ssix.mp4", env!("CARGO_MANIFEST_DIR"));
+        let input = std::fs::read(&path).unwrap();
+        // The `ssix` box stands between the `sidx` box and its first reference
+        assert_eq!(read_resolved_references(&input), vec![
+            vec![(false, 151, 283, 0, 2000), (false, 283, 415, 2000, 4000)],
+        ]);
+    }
+
+    #[test]
+    fn cap_overflowing_references() {
+        let mut sidx = Sidx::new(1, 1000, u64::MAX - 1000, vec![
+            SidxReference::new(100, 600, true, 1),
+            SidxReference::new(100, 600, true, 1),
+        ]);
+        sidx.anchor_point = u64::MAX - 150;
+        let resolved: Vec<_> = sidx.resolve_references()
+            .into_iter()
+            .map(|r| (r.start_offset, r.end_offset, r.start_time, r.end_time))
+            .collect();
+        assert_eq!(resolved, vec![
+            (u64::MAX - 150, u64::MAX - 50, u64::MAX - 1000, u64::MAX - 400),
+            (u64::MAX - 50, u64::MAX, u64::MAX - 400, u64::MAX),
+        ]);
     }
 }
@@ -0,0 +1,240 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+    leva::LevelAssignment,
+};
+
+/// Offsets in the file of the first byte of each range of each subsegment
+/// and of the first byte following it.
+/// `None` for the ranges of subsegments with an unknown `sidx` reference.
+pub type SubsegmentByteRanges = Vec<Vec<Option<(u64, u64)>>>;
+
+pub struct SsixRange {
+    level: u8,
+    range_size: u32,
+
+    /// Offsets in the file of the first byte of this range and of the first
+    /// byte following it.
+    /// `None` if the corresponding `sidx` reference is unknown.
+    byte_range: Option<(u64, u64)>,
+
+    /// Assignment of this range's level, as indicated by a `leva` box.
+    /// `None` if unknown.
+    level_assignment: Option<LevelAssignment>,
+}
+
+pub struct SsixSubsegment {
+    range_count: u32,
+    ranges: Vec<SsixRange>,
+}
+
+pub struct Ssix {
+    version: u8,
+    flags: Flags,
+    subsegment_count: u32,
+    subsegments: Vec<SsixSubsegment>,
+}
+
+impl Ssix {
+    pub fn get_subsegment_count(&self) -> u32 {
+        self.subsegment_count
+    }
+
+    /// Returns the combined size of the ranges of each subsegment.
+    pub fn get_subsegment_sizes(&self) -> Vec<u64> {
+        self.subsegments
+            .iter()
+            .map(|s| s.ranges.iter().map(|r| r.range_size as u64).sum())
+            .collect()
+    }
+
+    /// Returns, for each subsegment, the offsets in the file of the first
+    /// byte of each of its ranges and of the first byte following it.
+    /// `None` for the ranges of subsegments with an unknown `sidx` reference.
+    pub fn get_byte_ranges(&self) -> SubsegmentByteRanges {
+        self.subsegments
+            .iter()
+            .map(|s| s.ranges.iter().map(|r| r.byte_range).collect())
+            .collect()
+    }
+}
+
+impl IsoBoxParser for Ssix {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let subsegment_count = reader.read_u32()?;
+
+        // Each subsegment refines the corresponding reference of the
+        // preceding `sidx` box
+        let context = reader.get_context();
+        let sidx_references = context.get_sidx_references().to_vec();
+        let level_assignments = context.get_level_assignments().to_vec();
+
+        // Do not trust the counts more than the size of the box, each
+        // subsegment and range taking at least 4 bytes
+        let max_count = content_size.map_or(0, |size| size.saturating_sub(8) / 4);
+        let mut subsegments = Vec::with_capacity(max_count.min(subsegment_count as u64) as usize);
+        for i in 0..subsegment_count {
+            let mut offset = sidx_references.get(i as usize).map(|r| r.0);
+            let range_count = reader.read_u32()?;
+            let mut ranges = Vec::with_capacity(max_count.min(range_count as u64) as usize);
+            for _ in 0..range_count {
+                let val = reader.read_u32()?;
+                let level = (val >> 24) as u8;
+                let range_size = val & 0x00FFFFFF;
+                // The following ranges are unresolved once an offset does
+                // not fit in 64 bits
+                let byte_range = offset.and_then(|start| {
+                    Some((start, start.checked_add(range_size as u64)?))
+                });
+                offset = byte_range.map(|r| r.1);
+                let level_assignment = if level > 0 {
+                    level_assignments.get(level as usize - 1).cloned()
+                } else {
+                    None
+                };
+                ranges.push(SsixRange { level, range_size, byte_range, level_assignment });
+            }
+            subsegments.push(SsixSubsegment { range_count, ranges });
+        }
+        Ok(Self { version, flags, subsegment_count, subsegments })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("subsegment_count", BoxValue::from(self.subsegment_count)),
+            ("subsegments", BoxValue::Collection(
+                    self.subsegments.iter().map(|subsegment| {
+                        vec![
+                            ("range_count", BoxValue::from(subsegment.range_count)),
+                            ("ranges", BoxValue::Collection(
+                                    subsegment.ranges.iter().map(|range| {
+                                        let mut values = vec![
+                                            ("level", BoxValue::from(range.level)),
+                                            ("range_size", BoxValue::from(range.range_size)),
+                                        ];
+                                        if let Some((start, end)) = range.byte_range {
+                                            values.push(("start_offset", BoxValue::from(start)));
+                                            values.push(("end_offset", BoxValue::from(end)));
+                                        }
+                                        if let Some(assignment) = &range.level_assignment {
+                                            values.append(&mut assignment.get_values());
+                                        }
+                                        values
+                                    }).collect()
+                            )),
+                        ]
+                    }).collect()
+            )),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "ssix"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Ssix {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_u32(self.subsegment_count)?;
+        for subsegment in self.subsegments.iter() {
+            writer.write_u32(subsegment.range_count)?;
+            for range in subsegment.ranges.iter() {
+                writer.write_u32((range.level as u32) << 24 | range.range_size)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use super::Ssix;
+    use super::super::{parse_isobmff, utils::collect_boxes, leva::LevelAssignmentType};
+
+    fn read_ssix_boxes(file: &[u8], check: impl Fn(&Ssix)) {
+        let data = parse_isobmff(Cursor::new(file)).unwrap();
+        let mut found: Vec<(_, &Ssix)> = vec![];
+        for (info, entry) in data.iter() {
+            collect_boxes(info, entry.as_deref(), &mut found);
+        }
+        assert_eq!(found.len(), 1);
+        check(found[0].1);
+    }
+
+    #[test]
+    fn resolve_ranges_and_levels() {
+        let path = format!("{}/tests/fixtures/ssix.mp4", env!("CARGO_MANIFEST_DIR"));
+        let input = std::fs::read(&path).unwrap();
+        read_ssix_boxes(&input, |ssix| {
+            assert_eq!(ssix.get_subsegment_count(), 2);
+            assert_eq!(ssix.get_subsegment_sizes(), vec![132, 131]);
+            assert_eq!(ssix.get_byte_ranges(), vec![
+                vec![Some((151, 175)), Some((175, 283))],
+                vec![Some((283, 414))],
+            ]);
+            let levels: Vec<Vec<_>> = ssix.subsegments.iter()
+                .map(|s| s.ranges.iter()
+                    .map(|r| {
+                        let assignment = r.level_assignment.as_ref().unwrap();
+                        let assignment_type = match &assignment.assignment_type {
+                            LevelAssignmentType::Track => "track".to_owned(),
+                            LevelAssignmentType::SampleGroup { grouping_type } =>
+                                format!("sample group {}", grouping_type),
+                            _ => panic!("unexpected assignment type"),
+                        };
+                        (r.level, assignment.track_id, assignment_type)
+                    })
+                    .collect())
+                .collect();
+            assert_eq!(levels, vec![
+                vec![(1, 1, "track".to_owned()), (2, 2, "sample group tele".to_owned())],
+                vec![(1, 1, "track".to_owned())],
+            ]);
+        });
+    }
+
+    #[test]
+    fn leave_ranges_past_64_bits_unresolved() {
+        // A `sidx` whose only reference starts 20 bytes before the largest
+        // 64-bit offset
+        let mut file = vec![0, 0, 0, 52];
+        file.extend_from_slice(b"sidx");
+        file.extend_from_slice(&[1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0x03, 0xE8]);
+        file.extend_from_slice(&0u64.to_be_bytes());
+        file.extend_from_slice(&(u64::MAX - 52 - 20).to_be_bytes());
+        file.extend_from_slice(&[0, 0, 0, 1, 0, 0, 0, 100, 0, 0, 0x03, 0xE8, 0x90, 0, 0, 0]);
+        // A `ssix` whose second range would end past that offset
+        file.extend_from_slice(&[0, 0, 0, 32]);
+        file.extend_from_slice(b"ssix");
+        file.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 3]);
+        file.extend_from_slice(&[1, 0, 0, 10, 1, 0, 0, 20, 1, 0, 0, 1]);
+        read_ssix_boxes(&file, |ssix| {
+            assert_eq!(ssix.get_byte_ranges(), vec![
+                vec![Some((u64::MAX - 20, u64::MAX - 10)), None, None],
+            ]);
+        });
+    }
+}
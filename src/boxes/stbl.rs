@@ -0,0 +1,69 @@
+use std::io::{BufRead, Seek};
+use std::rc::Rc;
+use super::{
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxInfo,
+
+    IsoBoxParser,
+    IsoBoxSerializer,
+
+    sbgp::{map_samples_to_groups, SampleToGroup},
+    utils::parse_children,
+};
+
+pub struct Stbl {
+    content: Vec<super::IsoBoxData>,
+
+    /// Group description of each sample, derived from the contained `sbgp`
+    /// and `sgpd` boxes.
+    sample_to_group: Vec<SampleToGroup>,
+}
+
+impl IsoBoxParser for Stbl {
+    fn parse<T: BufRead + Seek>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        box_info: &Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let content = parse_children(reader, content_size, Some(box_info))?;
+        let sample_to_group = map_samples_to_groups(
+            &content,
+            false,
+            reader.get_context().get_current_track());
+        Ok(Self { content, sample_to_group })
+    }
+
+    fn get_inner_boxes_mut(&mut self) -> Option<&mut Vec<super::IsoBoxData>> {
+        Some(&mut self.content)
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<super::ContainedBoxInfo<'_>>> {
+        Some(self.content.iter().map(|c|
+                (c.0.as_ref(), c.1.as_ref().map(|boxed| { std::boxed::Box::as_ref(boxed) }))
+        ).collect())
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        if self.sample_to_group.is_empty() {
+            return vec![];
+        }
+        vec![
+            ("sample_to_group", BoxValue::Collection(
+                    self.sample_to_group.iter().map(|s| s.get_values()).collect()
+            )),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "stbl"
+    }
+}
+
+impl IsoBoxSerializer for Stbl {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_boxes(&self.content)
+    }
+}
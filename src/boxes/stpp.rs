@@ -0,0 +1,104 @@
+use std::io::{BufRead, Seek};
+use std::rc::Rc;
+use super::{
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxInfo,
+
+    IsoBoxParser,
+    IsoBoxSerializer,
+
+    utils::{decode_text, parse_children},
+};
+
+/// Sample entry of a TTML subtitle track, whose samples are XML documents.
+pub struct Stpp {
+    reserved: [u8; 6],
+    data_reference_index: u16,
+
+    /// Space-separated list of the XML namespaces the documents conform to.
+    namespace: String,
+    schema_location: String,
+    auxiliary_mime_types: String,
+    /// The null-terminated strings above, as encoded in the box.
+    strings_data: Vec<u8>,
+    content: Vec<super::IsoBoxData>,
+}
+
+impl IsoBoxParser for Stpp {
+    fn parse<T: BufRead + Seek>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        box_info: &Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let mut reserved = [0u8; 6];
+        reserved.copy_from_slice(&reader.read_bytes(6)?);
+        let data_reference_index = reader.read_u16()?;
+        let mut header_size = 8;
+
+        let namespace = reader.read_null_terminated()?;
+        let schema_location = reader.read_null_terminated()?;
+        header_size += namespace.len() as u64 + schema_location.len() as u64 + 2;
+
+        // `auxiliary_mime_types` is optional and may be directly followed by
+        // the contained boxes, or by nothing at all
+        let remaining = content_size.map(|size| size.saturating_sub(header_size));
+        let mut strings_data = [namespace.as_slice(), &[0], schema_location.as_slice(), &[0]].concat();
+        let auxiliary_mime_types = if remaining != Some(0) && !reader.is_empty()? {
+            let data = reader.read_null_terminated()?;
+            header_size += data.len() as u64 + 1;
+            strings_data.extend(data.iter().chain(&[0]));
+            data
+        } else {
+            vec![]
+        };
+        let content = parse_children(
+            reader,
+            content_size.map(|size| size.saturating_sub(header_size)),
+            Some(box_info))?;
+        Ok(Self {
+            reserved,
+            data_reference_index,
+            namespace: decode_text(&namespace),
+            schema_location: decode_text(&schema_location),
+            auxiliary_mime_types: decode_text(&auxiliary_mime_types),
+            strings_data,
+            content,
+        })
+    }
+
+    fn get_inner_boxes_mut(&mut self) -> Option<&mut Vec<super::IsoBoxData>> {
+        Some(&mut self.content)
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<super::ContainedBoxInfo<'_>>> {
+        Some(self.content.iter().map(|c|
+                (c.0.as_ref(), c.1.as_ref().map(|boxed| { std::boxed::Box::as_ref(boxed) }))
+        ).collect())
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("reserved", BoxValue::from(self.reserved.as_ref())),
+            ("data_reference_index", BoxValue::from(self.data_reference_index)),
+            ("namespace", BoxValue::from(self.namespace.as_str())),
+            ("schema_location", BoxValue::from(self.schema_location.as_str())),
+            ("auxiliary_mime_types", BoxValue::from(self.auxiliary_mime_types.as_str())),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "stpp"
+    }
+}
+
+impl IsoBoxSerializer for Stpp {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_bytes(&self.reserved)?;
+        writer.write_u16(self.data_reference_index)?;
+        writer.write_bytes(&self.strings_data)?;
+        writer.write_boxes(&self.content)
+    }
+}
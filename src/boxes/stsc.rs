@@ -0,0 +1,135 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub struct StscEntry {
+    first_chunk: u32,
+    samples_per_chunk: u32,
+    sample_description_index: u32,
+}
+
+pub struct Stsc {
+    version: u8,
+    flags: Flags,
+    entry_count: u32,
+    entries: Vec<StscEntry>,
+}
+
+impl Stsc {
+    /// Create a `stsc` box from the number of samples and the sample
+    /// description index of each chunk.
+    pub fn new(chunks: &[(u32, u32)]) -> Self {
+        let mut entries: Vec<StscEntry> = vec![];
+        for (i, (samples_per_chunk, sample_description_index)) in chunks.iter().enumerate() {
+            match entries.last() {
+                Some(entry) if entry.samples_per_chunk == *samples_per_chunk &&
+                    entry.sample_description_index == *sample_description_index => {},
+                _ => entries.push(StscEntry {
+                    first_chunk: i as u32 + 1,
+                    samples_per_chunk: *samples_per_chunk,
+                    sample_description_index: *sample_description_index,
+                }),
+            }
+        }
+        Self {
+            version: 0,
+            flags: Flags::from(0),
+            entry_count: entries.len() as u32,
+            entries,
+        }
+    }
+
+    /// Returns the number of samples in each of the `chunk_count` chunks of
+    /// the track.
+    pub fn get_samples_per_chunk(&self, chunk_count: u32) -> Vec<u32> {
+        self.get_chunk_values(chunk_count, |entry| entry.samples_per_chunk)
+    }
+
+    /// Returns the index of the sample entry describing the samples of each
+    /// of the `chunk_count` chunks of the track.
+    pub fn get_sample_description_indexes(&self, chunk_count: u32) -> Vec<u32> {
+        self.get_chunk_values(chunk_count, |entry| entry.sample_description_index)
+    }
+
+    fn get_chunk_values(&self, chunk_count: u32, get_value: impl Fn(&StscEntry) -> u32) -> Vec<u32> {
+        let mut result = Vec::with_capacity(chunk_count as usize);
+        for (i, entry) in self.entries.iter().enumerate() {
+            let last_chunk = self.entries
+                .get(i + 1)
+                .map_or(chunk_count, |next| next.first_chunk.saturating_sub(1).min(chunk_count));
+            for _ in entry.first_chunk..=last_chunk {
+                result.push(get_value(entry));
+            }
+        }
+        result
+    }
+}
+
+impl IsoBoxParser for Stsc {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let entry_count = reader.read_u32()?;
+        let mut entries = Vec::with_capacity(entry_count as usize);
+        for _ in 0..entry_count {
+            let first_chunk = reader.read_u32()?;
+            let samples_per_chunk = reader.read_u32()?;
+            let sample_description_index = reader.read_u32()?;
+            entries.push(StscEntry { first_chunk, samples_per_chunk, sample_description_index });
+        }
+        Ok(Self { version, flags, entry_count, entries })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("entry_count", BoxValue::from(self.entry_count)),
+            ("entries", BoxValue::Collection(
+                    self.entries.iter().map(|entry| {
+                        vec![
+                            ("first_chunk", BoxValue::from(entry.first_chunk)),
+                            ("samples_per_chunk", BoxValue::from(entry.samples_per_chunk)),
+                            ("sample_description_index",
+                             BoxValue::from(entry.sample_description_index)),
+                        ]
+                    }).collect()
+            )),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "stsc"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Stsc {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_u32(self.entry_count)?;
+        for entry in self.entries.iter() {
+            writer.write_u32(entry.first_chunk)?;
+            writer.write_u32(entry.samples_per_chunk)?;
+            writer.write_u32(entry.sample_description_index)?;
+        }
+        Ok(())
+    }
+}
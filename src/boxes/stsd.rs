@@ -0,0 +1,86 @@
+use std::io::{BufRead, Seek};
+use std::rc::Rc;
+use super::{
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxInfo,
+
+    IsoBoxParser,
+    IsoBoxSerializer,
+
+    utils::parse_children,
+};
+
+pub struct Stsd {
+    version: u8,
+    flags: Flags,
+    entry_count: u32,
+    content: Vec<super::IsoBoxData>,
+}
+
+impl Stsd {
+    /// Returns the sample entries described by this box.
+    pub fn get_entries(&self) -> &[super::IsoBoxData] {
+        &self.content
+    }
+}
+
+impl IsoBoxParser for Stsd {
+    fn parse<T: BufRead + Seek>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        box_info: &Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let entry_count = reader.read_u32()?;
+        let content = parse_children(
+            reader,
+            content_size.map(|size| size.saturating_sub(8)),
+            Some(box_info))?;
+
+        // The name of a sample entry identifies the coding used in the track
+        if let Some(first_entry) = content.first() {
+            let codec = first_entry.0.short_name.clone();
+            if let Some(track) = reader.get_context_mut().get_current_track_mut() {
+                track.codec = Some(codec);
+            }
+        }
+        Ok(Self { version, flags, entry_count, content })
+    }
+
+    fn get_inner_boxes_mut(&mut self) -> Option<&mut Vec<super::IsoBoxData>> {
+        Some(&mut self.content)
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<super::ContainedBoxInfo<'_>>> {
+        Some(self.content.iter().map(|c|
+                (c.0.as_ref(), c.1.as_ref().map(|boxed| { std::boxed::Box::as_ref(boxed) }))
+        ).collect())
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("entry_count", BoxValue::from(self.entry_count)),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "stsd"
+    }
+}
+
+impl IsoBoxSerializer for Stsd {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        // Sample entries may have been added or removed since parsing
+        writer.write_u32(self.content.len() as u32)?;
+        writer.write_boxes(&self.content)
+    }
+}
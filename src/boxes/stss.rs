@@ -0,0 +1,82 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub struct Stss {
+    version: u8,
+    flags: Flags,
+    entry_count: u32,
+    sample_numbers: Vec<u32>,
+}
+
+impl Stss {
+    /// Create a `stss` box from the 1-based numbers of the sync samples.
+    pub fn new(sample_numbers: Vec<u32>) -> Self {
+        Self {
+            version: 0,
+            flags: Flags::from(0),
+            entry_count: sample_numbers.len() as u32,
+            sample_numbers,
+        }
+    }
+
+    /// Returns the 1-based numbers of the sync samples, in increasing order.
+    pub fn get_sync_samples(&self) -> &[u32] {
+        &self.sample_numbers
+    }
+}
+
+impl IsoBoxParser for Stss {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let entry_count = reader.read_u32()?;
+        let mut sample_numbers = Vec::with_capacity(entry_count as usize);
+        for _ in 0..entry_count {
+            sample_numbers.push(reader.read_u32()?);
+        }
+        Ok(Self { version, flags, entry_count, sample_numbers })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("entry_count", BoxValue::from(self.entry_count)),
+            ("sample_numbers", BoxValue::from(self.sample_numbers.as_slice())),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "stss"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Stss {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_u32(self.entry_count)?;
+        for sample_number in self.sample_numbers.iter() {
+            writer.write_u32(*sample_number)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub struct Stsz {
+    version: u8,
+    flags: Flags,
+    sample_size: u32,
+    sample_count: u32,
+
+    /// Size of each sample, only present when `sample_size` is `0`.
+    entry_sizes: Vec<u32>,
+}
+
+impl Stsz {
+    /// Create a `stsz` box from the size of each sample.
+    pub fn new(sizes: Vec<u32>) -> Self {
+        Self {
+            version: 0,
+            flags: Flags::from(0),
+            sample_size: 0,
+            sample_count: sizes.len() as u32,
+            entry_sizes: sizes,
+        }
+    }
+
+    pub fn get_sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    /// Returns the size of each sample, in order.
+    /// As for `Stts::get_sample_durations`, a common size is repeated lazily.
+    pub fn get_sample_sizes(&self) -> Box<dyn Iterator<Item = u32> + '_> {
+        if self.sample_size != 0 {
+            let sample_size = self.sample_size;
+            Box::new((0..self.sample_count).map(move |_| sample_size))
+        } else {
+            Box::new(self.entry_sizes.iter().copied())
+        }
+    }
+}
+
+impl IsoBoxParser for Stsz {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let sample_size = reader.read_u32()?;
+        let sample_count = reader.read_u32()?;
+        let mut entry_sizes = vec![];
+        if sample_size == 0 {
+            // Do not trust the sample count more than the size of the box
+            let max_count = content_size.map_or(0, |size| size.saturating_sub(12) / 4);
+            entry_sizes.reserve(max_count.min(sample_count as u64) as usize);
+            for _ in 0..sample_count {
+                entry_sizes.push(reader.read_u32()?);
+            }
+        }
+        Ok(Self { version, flags, sample_size, sample_count, entry_sizes })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        let mut values = vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("sample_size", BoxValue::from(self.sample_size)),
+            ("sample_count", BoxValue::from(self.sample_count)),
+        ];
+        if self.sample_size == 0 {
+            values.push(("entry_sizes", BoxValue::from(self.entry_sizes.as_slice())));
+        }
+        values
+    }
+
+    fn get_short_name() -> &'static str {
+        "stsz"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Stsz {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_u32(self.sample_size)?;
+        writer.write_u32(self.sample_count)?;
+        for entry_size in self.entry_sizes.iter() {
+            writer.write_u32(*entry_size)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub struct SttsEntry {
+    sample_count: u32,
+    sample_delta: u32,
+}
+
+pub struct Stts {
+    version: u8,
+    flags: Flags,
+    entry_count: u32,
+    entries: Vec<SttsEntry>,
+}
+
+impl Stts {
+    /// Create a `stts` box from the duration of each sample.
+    pub fn new(durations: &[u32]) -> Self {
+        let mut entries: Vec<SttsEntry> = vec![];
+        for duration in durations {
+            match entries.last_mut() {
+                Some(entry) if entry.sample_delta == *duration => entry.sample_count += 1,
+                _ => entries.push(SttsEntry { sample_count: 1, sample_delta: *duration }),
+            }
+        }
+        Self {
+            version: 0,
+            flags: Flags::from(0),
+            entry_count: entries.len() as u32,
+            entries,
+        }
+    }
+
+    /// Returns the duration of each sample, in order.
+    /// The entries are walked as the durations are read, so an untrusted
+    /// sample count does not lead to a large allocation.
+    pub fn get_sample_durations(&self) -> impl Iterator<Item = u32> + '_ {
+        self.entries.iter()
+            .flat_map(|entry| (0..entry.sample_count).map(move |_| entry.sample_delta))
+    }
+}
+
+impl IsoBoxParser for Stts {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let entry_count = reader.read_u32()?;
+        let mut entries = Vec::with_capacity(entry_count as usize);
+        for _ in 0..entry_count {
+            let sample_count = reader.read_u32()?;
+            let sample_delta = reader.read_u32()?;
+            entries.push(SttsEntry { sample_count, sample_delta });
+        }
+        Ok(Self { version, flags, entry_count, entries })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("entry_count", BoxValue::from(self.entry_count)),
+            ("entries", BoxValue::Collection(
+                    self.entries.iter().map(|entry| {
+                        vec![
+                            ("sample_count", BoxValue::from(entry.sample_count)),
+                            ("sample_delta", BoxValue::from(entry.sample_delta)),
+                        ]
+                    }).collect()
+            )),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "stts"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Stts {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_u32(self.entry_count)?;
+        for entry in self.entries.iter() {
+            writer.write_u32(entry.sample_count)?;
+            writer.write_u32(entry.sample_delta)?;
+        }
+        Ok(())
+    }
+}
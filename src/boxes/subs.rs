@@ -3,26 +3,37 @@ use super::{
     IsoBoxInfo,
     BoxParsingError,
     BoxReader,
+    BoxWriter,
     BoxValue,
     Flags,
     IsoBoxEntry,
     IsoBoxParser,
+    IsoBoxSerializer,
 };
 
-pub struct SubsEntry {
-    sample_delta: u32,
-    subsample_count: u16,
-    subsample_size: Vec<u32>,
+pub struct Subsample {
+    subsample_size: u32,
     subsample_priority: u8,
     discardable: u8,
     codec_specific_parameters: u32,
 }
 
+pub struct SubsEntry {
+    sample_delta: u32,
+    subsample_count: u16,
+    subsamples: Vec<Subsample>,
+}
+
 pub struct Subs {
     version: u8,
     flags: Flags,
     entry_count: u32,
     entries: Vec<SubsEntry>,
+
+    /// Coding name of the corresponding track, if known.
+    /// The meaning of both the `flags` and of the `codec_specific_parameters`
+    /// depend on it.
+    codec: Option<String>,
 }
 
 impl IsoBoxParser for Subs {
@@ -38,72 +49,243 @@ impl IsoBoxParser for Subs {
         for _ in 0..entry_count {
             let sample_delta = reader.read_u32()?;
             let subsample_count = reader.read_u16()?;
-            let mut subsample_size = Vec::with_capacity(subsample_count as usize);
+            let mut subsamples = Vec::with_capacity(subsample_count as usize);
             for _ in 0..subsample_count {
-                let size =
+                let subsample_size =
                     if version == 1 {
                         reader.read_u32()?
                     } else {
                         reader.read_u16()? as u32
                     };
-                subsample_size.push(size);
+                let subsample_priority = reader.read_u8()?;
+                let discardable = reader.read_u8()?;
+                let codec_specific_parameters = reader.read_u32()?;
+                subsamples.push(Subsample {
+                    subsample_size,
+                    subsample_priority,
+                    discardable,
+                    codec_specific_parameters,
+                });
             }
-            // XXX TODO Re-check
-            let subsample_priority = reader.read_u8()?;
-            let discardable = reader.read_u8()?;
-            let codec_specific_parameters = reader.read_u32()?;
             entries.push(SubsEntry {
                 sample_delta,
                 subsample_count,
-                subsample_size,
-                subsample_priority,
-                discardable,
-                codec_specific_parameters
+                subsamples,
             });
         }
 
+        let codec = reader.get_context().get_current_track_codec().map(|c| c.to_owned());
         Ok(Self {
             version,
             flags,
             entry_count,
             entries,
+            codec,
         })
     }
 
     fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
-        vec![
+        let codec = self.codec.as_deref();
+        let mut values = vec![
             ("version", BoxValue::from(self.version)),
             ("flags", BoxValue::from(self.flags)),
-            ("entry_count", BoxValue::from(self.entry_count)),
+        ];
+        if let Some(subsample_type) = get_subsample_type_name(codec, self.flags) {
+            values.push(("subsample_type", BoxValue::from(subsample_type)));
+        }
+        values.push(("entry_count", BoxValue::from(self.entry_count)));
+        values.push(
             ("entries", BoxValue::Collection(
                     self.entries.iter().map(|entry| {
                         vec![
                             ("sample_delta", BoxValue::from(entry.sample_delta)),
                             ("subsample_count", BoxValue::from(entry.subsample_count)),
-                            ("subsample_size", BoxValue::from(entry.subsample_size.as_slice())),
-                            ("subsample_priority", BoxValue::from(entry.subsample_priority)),
-                            ("discardable", BoxValue::from(entry.discardable)),
-                            ("codec_specific_parameters",
-                             BoxValue::from(entry.codec_specific_parameters)),
+                            ("subsamples", BoxValue::Collection(
+                                    entry.subsamples.iter().map(|subsample| {
+                                        let mut subsample_values = vec![
+                                            ("subsample_size",
+                                             BoxValue::from(subsample.subsample_size)),
+                                            ("subsample_priority",
+                                             BoxValue::from(subsample.subsample_priority)),
+                                            ("discardable",
+                                             BoxValue::from(subsample.discardable)),
+                                            ("codec_specific_parameters",
+                                             BoxValue::from(subsample.codec_specific_parameters)),
+                                        ];
+                                        subsample_values.append(
+                                            &mut get_codec_specific_values(
+                                                codec,
+                                                self.flags,
+                                                subsample.codec_specific_parameters));
+                                        subsample_values
+                                    }).collect()
+                            )),
                         ]
                     }).collect()
-            ))
-    ]
+            )));
+        values
     }
 
     fn get_short_name() -> &'static str {
         "subs"
     }
 
-    fn get_long_name() -> &'static str {
-        "Sub-Sample Information Box"
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
     }
+}
 
-    fn get_inner_boxes(self) -> Option<Vec<super::IsoBoxData>> {
-        None
+fn is_hevc(codec: Option<&str>) -> bool {
+    matches!(codec, Some("hvc1") | Some("hev1") | Some("hvc2") | Some("hev2"))
+}
+
+fn is_vvc(codec: Option<&str>) -> bool {
+    matches!(codec, Some("vvc1") | Some("vvi1"))
+}
+
+fn is_av1(codec: Option<&str>) -> bool {
+    codec == Some("av01")
+}
+
+/// For codecs which define it, returns the type of sub-samples announced by
+/// the `flags` of the `subs` box.
+fn get_subsample_type_name(codec: Option<&str>, flags: Flags) -> Option<&'static str> {
+    if is_av1(codec) {
+        // Sub-samples of AV1 samples are made of whole OBUs, and no other
+        // type is defined
+        return match u32::from(flags) {
+            0 => Some("OBU-based"),
+            _ => Some("reserved"),
+        };
+    }
+    if !is_hevc(codec) {
+        return None;
+    }
+    match u32::from(flags) {
+        0 => Some("NAL-unit-based"),
+        1 => Some("decoding-unit-based"),
+        2 => Some("tile-based"),
+        3 => Some("CTU-row-based"),
+        4 => Some("slice-based"),
+        _ => Some("reserved"),
     }
+}
 
-    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
-        None
+/// Decode the `codec_specific_parameters` of a sub-sample for the codecs and
+/// sub-sample types for which it is defined.
+/// Returns an empty vector if it has no known meaning, which is the case for
+/// AV1, whose sub-samples do not rely on it.
+fn get_codec_specific_values(
+    codec: Option<&str>,
+    flags: Flags,
+    params: u32
+) -> Vec<(&'static str, BoxValue<'static>)> {
+    // This layout only applies to NAL-unit-based sub-samples
+    if (is_hevc(codec) || is_vvc(codec)) && u32::from(flags) == 0 {
+        vec![
+            ("discardable_flag", BoxValue::from(params >> 31 != 0)),
+            ("no_inter_layer_pred_flag", BoxValue::from((params >> 30) & 0x01 != 0)),
+            ("layer_id", BoxValue::from(((params >> 24) & 0x3F) as u8)),
+            ("temp_id", BoxValue::from(((params >> 21) & 0x07) as u8)),
+        ]
+    } else {
+        vec![]
+    }
+}
+
+impl IsoBoxSerializer for Subs {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_u32(self.entry_count)?;
+        for entry in self.entries.iter() {
+            writer.write_u32(entry.sample_delta)?;
+            writer.write_u16(entry.subsample_count)?;
+            for subsample in entry.subsamples.iter() {
+                if self.version == 1 {
+                    writer.write_u32(subsample.subsample_size)?;
+                } else {
+                    writer.write_u16(subsample.subsample_size as u16)?;
+                }
+                writer.write_u8(subsample.subsample_priority)?;
+                writer.write_u8(subsample.discardable)?;
+                writer.write_u32(subsample.codec_specific_parameters)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use super::Subs;
+    use super::super::{BoxValue, IsoBoxParser, parse_isobmff, utils::collect_boxes};
+
+    /// Values of each sub-sample which do not depend on the codec.
+    const COMMON_SUBSAMPLE_VALUES: [&str; 4] =
+        ["subsample_size", "subsample_priority", "discardable", "codec_specific_parameters"];
+
+    /// Returns for each `subs` box of the "subsamples.mp4" fixture its
+    /// sub-sample type and the values decoded from the
+    /// `codec_specific_parameters` of each of its sub-samples.
+    fn read_subs_values() -> Vec<(Option<String>, Vec<Vec<String>>)> {
+        let path = format!("{}/tests/fixtures/subsamples.mp4", env!("CARGO_MANIFEST_DIR"));
+        let input = std::fs::read(&path).unwrap();
+        let data = parse_isobmff(Cursor::new(&input)).unwrap();
+        let mut found: Vec<(_, &Subs)> = vec![];
+        for (info, entry) in data.iter() {
+            collect_boxes(info, entry.as_deref(), &mut found);
+        }
+        found.into_iter().map(|(_, subs)| {
+            let values = subs.get_inner_values_ref();
+            let subsample_type = values.iter().find_map(|(name, value)| match value {
+                BoxValue::Utf8(s) if *name == "subsample_type" => Some(s.to_string()),
+                _ => None,
+            });
+            let entries = match &values.iter().find(|(name, _)| *name == "entries").unwrap().1 {
+                BoxValue::Collection(entries) => entries,
+                _ => panic!("entries are not a collection"),
+            };
+            let subsamples = match &entries[0].iter().find(|(name, _)| *name == "subsamples").unwrap().1 {
+                BoxValue::Collection(subsamples) => subsamples,
+                _ => panic!("subsamples are not a collection"),
+            };
+            let decoded = subsamples.iter()
+                .map(|subsample| subsample.iter()
+                    .filter(|(name, _)| !COMMON_SUBSAMPLE_VALUES.contains(name))
+                    .map(|(name, value)| match value {
+                        BoxValue::Bool(b) => format!("{}={}", name, b),
+                        BoxValue::UInt8(v) => format!("{}={}", name, v),
+                        _ => panic!("unexpected value for {}", name),
+                    })
+                    .collect())
+                .collect();
+            (subsample_type, decoded)
+        }).collect()
+    }
+
+    #[test]
+    fn hevc_nal_unit_based_subsamples() {
+        let subs = read_subs_values();
+        assert_eq!(subs[0].0.as_deref(), Some("NAL-unit-based"));
+        assert_eq!(subs[0].1, vec![
+            vec!["discardable_flag=true", "no_inter_layer_pred_flag=false", "layer_id=2", "temp_id=3"],
+            vec!["discardable_flag=false", "no_inter_layer_pred_flag=true", "layer_id=0", "temp_id=0"],
+        ]);
+    }
+
+    #[test]
+    fn hevc_tile_based_subsamples() {
+        let subs = read_subs_values();
+        assert_eq!(subs[1].0.as_deref(), Some("tile-based"));
+        assert_eq!(subs[1].1, vec![Vec::<String>::new()]);
+    }
+
+    #[test]
+    fn av1_subsamples() {
+        let subs = read_subs_values();
+        assert_eq!(subs[2].0.as_deref(), Some("OBU-based"));
+        assert_eq!(subs[2].1, vec![Vec::<String>::new()]);
     }
 }
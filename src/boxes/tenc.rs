@@ -0,0 +1,137 @@
+use std::io::BufRead;
+use std::rc::Rc;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+/// Default encryption parameters of a protected track.
+#[derive(Clone)]
+pub struct TrackEncryption {
+    /// Number of encrypted 16-byte blocks in the encryption pattern.
+    pub crypt_byte_block: u8,
+    /// Number of clear 16-byte blocks in the encryption pattern.
+    pub skip_byte_block: u8,
+    pub is_protected: u8,
+    /// Size of the initialization vector of each sample, `0` if a constant
+    /// IV is used instead.
+    pub per_sample_iv_size: u8,
+    pub kid: [u8; 16],
+    pub constant_iv: Vec<u8>,
+}
+
+pub struct Tenc {
+    version: u8,
+    flags: Flags,
+    encryption: TrackEncryption,
+}
+
+impl Tenc {
+    pub fn get_track_encryption(&self) -> &TrackEncryption {
+        &self.encryption
+    }
+}
+
+impl IsoBoxParser for Tenc {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        box_info: &Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        if version > 1 {
+            return Err(BoxParsingError::InvalidVersion {
+                box_info: Rc::clone(box_info),
+                expected: vec![0, 1],
+                actual: version,
+            });
+        }
+        let flags = Flags::read(reader)?;
+        // reserved
+        reader.read_u8()?;
+        let byte = reader.read_u8()?;
+        let (crypt_byte_block, skip_byte_block) = if version == 0 {
+            (0, 0)
+        } else {
+            (byte >> 4, byte & 0x0F)
+        };
+        let is_protected = reader.read_u8()?;
+        let per_sample_iv_size = reader.read_u8()?;
+        let mut kid = [0u8; 16];
+        kid.copy_from_slice(&reader.read_bytes(16)?);
+        let constant_iv = if is_protected == 1 && per_sample_iv_size == 0 {
+            let constant_iv_size = reader.read_u8()?;
+            reader.read_bytes(constant_iv_size as usize)?
+        } else {
+            vec![]
+        };
+        if let Some(track) = reader.get_context_mut().get_current_track_mut() {
+            track.per_sample_iv_size = Some(per_sample_iv_size);
+        }
+        Ok(Self {
+            version,
+            flags,
+            encryption: TrackEncryption {
+                crypt_byte_block,
+                skip_byte_block,
+                is_protected,
+                per_sample_iv_size,
+                kid,
+                constant_iv,
+            },
+        })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        let encryption = &self.encryption;
+        let mut values = vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+        ];
+        if self.version > 0 {
+            values.push(("default_crypt_byte_block", BoxValue::from(encryption.crypt_byte_block)));
+            values.push(("default_skip_byte_block", BoxValue::from(encryption.skip_byte_block)));
+        }
+        values.push(("default_isProtected", BoxValue::from(encryption.is_protected)));
+        values.push(("default_Per_Sample_IV_Size", BoxValue::from(encryption.per_sample_iv_size)));
+        values.push(("default_KID", BoxValue::Hex(&encryption.kid)));
+        if !encryption.constant_iv.is_empty() {
+            values.push(("default_constant_IV", BoxValue::Hex(&encryption.constant_iv)));
+        }
+        values
+    }
+
+    fn get_short_name() -> &'static str {
+        "tenc"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Tenc {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        let encryption = &self.encryption;
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        // reserved
+        writer.write_u8(0)?;
+        writer.write_u8(encryption.crypt_byte_block << 4 | encryption.skip_byte_block)?;
+        writer.write_u8(encryption.is_protected)?;
+        writer.write_u8(encryption.per_sample_iv_size)?;
+        writer.write_bytes(&encryption.kid)?;
+        if encryption.is_protected == 1 && encryption.per_sample_iv_size == 0 {
+            writer.write_u8(encryption.constant_iv.len() as u8)?;
+            writer.write_bytes(&encryption.constant_iv)?;
+        }
+        Ok(())
+    }
+}
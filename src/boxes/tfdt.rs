@@ -3,16 +3,47 @@ use super::{
     IsoBoxInfo,
     BoxParsingError,
     BoxReader,
+    BoxWriter,
     BoxValue,
     Flags,
     IsoBoxEntry,
     IsoBoxParser,
+    IsoBoxSerializer,
 };
 
 pub struct Tfdt {
     version: u8,
     flags: Flags,
     base_media_decode_time: u64,
+
+    /// Timescale of the corresponding track, if known.
+    timescale: Option<u32>,
+}
+
+impl Tfdt {
+    pub fn new(base_media_decode_time: u64) -> Self {
+        let version = if base_media_decode_time > u32::MAX as u64 { 1 } else { 0 };
+        Self {
+            version,
+            flags: Flags::from(0),
+            base_media_decode_time,
+            timescale: None,
+        }
+    }
+
+    /// Returns the decode time of the fragment's first sample, in the
+    /// track's timescale.
+    pub fn get_base_media_decode_time(&self) -> u64 {
+        self.base_media_decode_time
+    }
+
+    /// Returns the decode time of the fragment's first sample, in seconds.
+    /// `None` if the track's timescale is unknown.
+    pub fn get_start_time(&self) -> Option<f64> {
+        self.timescale
+            .filter(|timescale| *timescale > 0)
+            .map(|timescale| self.base_media_decode_time as f64 / timescale as f64)
+    }
 }
 
 impl IsoBoxParser for Tfdt {
@@ -29,10 +60,12 @@ impl IsoBoxParser for Tfdt {
             reader.read_u32()? as u64
         };
 
+        let timescale = reader.get_context().get_current_track_timescale();
         Ok(Self {
             version,
             flags,
-            base_media_decode_time
+            base_media_decode_time,
+            timescale,
         })
     }
 
@@ -40,7 +73,8 @@ impl IsoBoxParser for Tfdt {
         vec![
             ("version", BoxValue::from(self.version)),
             ("flags", BoxValue::from(self.flags)),
-            ("base_media_decode_time", BoxValue::from(self.base_media_decode_time))
+            ("base_media_decode_time",
+             BoxValue::from_ticks(self.base_media_decode_time, self.timescale))
         ]
     }
 
@@ -48,15 +82,19 @@ impl IsoBoxParser for Tfdt {
         "tfdt"
     }
 
-    fn get_long_name() -> &'static str {
-        "Track fragment decode time"
-    }
-
-    fn get_inner_boxes(self) -> Option<Vec<super::IsoBoxData>> {
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
         None
     }
+}
 
-    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
-        None
+impl IsoBoxSerializer for Tfdt {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        if self.version == 1 {
+            writer.write_u64(self.base_media_decode_time)
+        } else {
+            writer.write_u32(self.base_media_decode_time as u32)
+        }
     }
 }
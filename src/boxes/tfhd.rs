@@ -3,10 +3,14 @@ use super::{
     IsoBoxInfo,
     BoxParsingError,
     BoxReader,
+    BoxWriter,
     BoxValue,
+    FlagNames,
     Flags,
     IsoBoxEntry,
+    SampleFlags,
     IsoBoxParser,
+    IsoBoxSerializer,
 };
 
 pub struct Tfhd {
@@ -17,7 +21,70 @@ pub struct Tfhd {
     sample_description_index: Option<u32>,
     default_sample_duration: Option<u32>,
     default_sample_size: Option<u32>,
-    default_sample_flags: Option<u32>,
+    default_sample_flags: Option<SampleFlags>,
+
+    /// Timescale of the corresponding track, if known.
+    timescale: Option<u32>,
+}
+
+const FLAG_NAMES: FlagNames = &[
+    (0x000001, "base-data-offset-present"),
+    (0x000002, "sample-description-index-present"),
+    (0x000008, "default-sample-duration-present"),
+    (0x000010, "default-sample-size-present"),
+    (0x000020, "default-sample-flags-present"),
+    (0x010000, "duration-is-empty"),
+    (0x020000, "default-base-is-moof"),
+];
+
+impl Tfhd {
+    /// Create a `tfhd` box whose data offsets are relative to the start of
+    /// the enclosing `moof`, and whose samples are described by the sample
+    /// entry with the given index if it is not the default one.
+    pub fn new(track_id: u32, sample_description_index: Option<u32>) -> Self {
+        let flags = 0x020000 | sample_description_index.map_or(0, |_| 0x000002);
+        Self {
+            version: 0,
+            flags: Flags::from(flags),
+            track_id,
+            base_data_offset: None,
+            sample_description_index,
+            default_sample_duration: None,
+            default_sample_size: None,
+            default_sample_flags: None,
+            timescale: None,
+        }
+    }
+
+    pub fn get_track_id(&self) -> u32 {
+        self.track_id
+    }
+
+    pub fn get_sample_description_index(&self) -> Option<u32> {
+        self.sample_description_index
+    }
+
+    pub fn get_default_sample_duration(&self) -> Option<u32> {
+        self.default_sample_duration
+    }
+
+    pub fn get_default_sample_flags(&self) -> Option<SampleFlags> {
+        self.default_sample_flags
+    }
+
+    pub fn get_base_data_offset(&self) -> Option<u64> {
+        self.base_data_offset
+    }
+
+    pub fn get_default_sample_size(&self) -> Option<u32> {
+        self.default_sample_size
+    }
+
+    /// Returns `true` if data offsets are relative to the start of the
+    /// enclosing `moof` when no `base_data_offset` is given.
+    pub fn is_default_base_moof(&self) -> bool {
+        self.flags.has_flag(0x020000)
+    }
 }
 
 impl IsoBoxParser for Tfhd {
@@ -35,11 +102,9 @@ impl IsoBoxParser for Tfhd {
         let flag_default_sample_size = flags.has_flag(0x000010);
         let flag_default_sample_flags = flags.has_flag(0x000020);
 
-        // TODO indicate flags values in get_inner_values_ref
-        // let flag_duration_is_empty = flags.has_flag(0x010000);
-        // let flag_default_base_is_moof = flags.has_flag(0x020000);
-
         let track_id = reader.read_u32()?;
+        reader.get_context_mut().set_current_track_id(track_id);
+        let timescale = reader.get_context().get_current_track_timescale();
         let base_data_offset = if flag_base_data_offset {
            Some(reader.read_u64()?)
         } else { None };
@@ -53,7 +118,7 @@ impl IsoBoxParser for Tfhd {
             Some(reader.read_u32()?)
         } else { None };
         let default_sample_flags = if flag_default_sample_flags {
-            Some(reader.read_u32()?)
+            Some(SampleFlags::read(reader)?)
         } else { None };
         Ok(Self {
             version,
@@ -64,6 +129,7 @@ impl IsoBoxParser for Tfhd {
             default_sample_duration,
             default_sample_size,
             default_sample_flags,
+            timescale,
         })
     }
 
@@ -80,7 +146,9 @@ impl IsoBoxParser for Tfhd {
             values.push(("sample_description_index", BoxValue::from(val)));
         }
         if let Some(val) = self.default_sample_duration {
-            values.push(("default_sample_duration", BoxValue::from(val)));
+            values.push(
+                ("default_sample_duration",
+                 BoxValue::from_ticks(val as u64, self.timescale)));
         }
         if let Some(val) = self.default_sample_size {
             values.push(("default_sample_size", BoxValue::from(val)));
@@ -95,15 +163,32 @@ impl IsoBoxParser for Tfhd {
         "tfhd"
     }
 
-    fn get_long_name() -> &'static str {
-        "Track Fragment Header Box"
+    fn get_flag_names() -> FlagNames {
+        FLAG_NAMES
     }
 
-    fn get_inner_boxes(self) -> Option<Vec<super::IsoBoxData>> {
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
         None
     }
+}
 
-    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
-        None
+impl IsoBoxSerializer for Tfhd {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_u32(self.track_id)?;
+        if let Some(base_data_offset) = self.base_data_offset {
+            writer.write_u64(base_data_offset)?;
+        }
+        let optional_fields = [
+            self.sample_description_index,
+            self.default_sample_duration,
+            self.default_sample_size,
+            self.default_sample_flags.map(u32::from),
+        ];
+        for field in optional_fields.iter().flatten() {
+            writer.write_u32(*field)?;
+        }
+        Ok(())
     }
 }
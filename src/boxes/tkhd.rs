@@ -0,0 +1,186 @@
+use std::io::BufRead;
+use std::rc::Rc;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    FlagNames,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+pub struct Tkhd {
+    version: u8,
+    flags: Flags,
+    creation_time: u64,
+    modification_time: u64,
+    track_id: u32,
+    reserved_1: u32,
+    duration: u64,
+    reserved_2: [u32; 2],
+    layer: i16,
+    alternate_group: i16,
+    volume: i16,
+    reserved_3: u16,
+    matrix: [u32; 9],
+    width: u32,
+    height: u32,
+
+    /// Timescale of the whole presentation, in which `duration` is expressed.
+    movie_timescale: Option<u32>,
+}
+
+const FLAG_NAMES: FlagNames = &[
+    (0x000001, "track-enabled"),
+    (0x000002, "track-in-movie"),
+    (0x000004, "track-in-preview"),
+    (0x000008, "track-size-is-aspect-ratio"),
+];
+
+impl Tkhd {
+    pub fn get_track_id(&self) -> u32 {
+        self.track_id
+    }
+
+    /// Set the duration, switching to the 64-bit version of the box if it
+    /// does not fit in 32 bits.
+    pub fn set_duration(&mut self, duration: u64) {
+        if duration > u32::MAX as u64 {
+            self.version = 1;
+        }
+        self.duration = duration;
+    }
+}
+
+impl IsoBoxParser for Tkhd {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        box_info: &Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let (creation_time, modification_time, track_id, reserved_1, duration) =
+            match version {
+                0 => (
+                    reader.read_u32()? as u64,
+                    reader.read_u32()? as u64,
+                    reader.read_u32()?,
+                    reader.read_u32()?,
+                    reader.read_u32()? as u64),
+                1 => (
+                    reader.read_u64()?,
+                    reader.read_u64()?,
+                    reader.read_u32()?,
+                    reader.read_u32()?,
+                    reader.read_u64()?),
+                v => {
+                    return Err(BoxParsingError::InvalidVersion {
+                        box_info: Rc::clone(box_info),
+                        expected: vec![0, 1],
+                        actual: v });
+                }
+            };
+        let reserved_2 = [reader.read_u32()?, reader.read_u32()?];
+        let layer = reader.read_u16()? as i16;
+        let alternate_group = reader.read_u16()? as i16;
+        let volume = reader.read_u16()? as i16;
+        let reserved_3 = reader.read_u16()?;
+        let matrix = [
+            reader.read_u32()?, reader.read_u32()?, reader.read_u32()?,
+            reader.read_u32()?, reader.read_u32()?, reader.read_u32()?,
+            reader.read_u32()?, reader.read_u32()?, reader.read_u32()?];
+        let width = reader.read_u32()?;
+        let height = reader.read_u32()?;
+
+        let context = reader.get_context_mut();
+        context.set_current_track_id(track_id);
+        let movie_timescale = context.get_movie_timescale();
+        Ok(Self {
+            version,
+            flags,
+            creation_time,
+            modification_time,
+            track_id,
+            reserved_1,
+            duration,
+            reserved_2,
+            layer,
+            alternate_group,
+            volume,
+            reserved_3,
+            matrix,
+            width,
+            height,
+            movie_timescale,
+        })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("creation_time", BoxValue::Date(self.creation_time)),
+            ("modification_time", BoxValue::Date(self.modification_time)),
+            ("track_id", BoxValue::from(self.track_id)),
+            ("reserved_1", BoxValue::from(self.reserved_1)),
+            ("duration", BoxValue::from_ticks(self.duration, self.movie_timescale)),
+            ("reserved_2", BoxValue::from(self.reserved_2.as_ref())),
+            ("layer", BoxValue::from(self.layer)),
+            ("alternate_group", BoxValue::from(self.alternate_group)),
+            ("volume", BoxValue::FixedPoint8_8(self.volume)),
+            ("reserved_3", BoxValue::from(self.reserved_3)),
+            ("matrix", BoxValue::Matrix3_3(&self.matrix)),
+            ("width", BoxValue::UFixedPoint16_16(self.width)),
+            ("height", BoxValue::UFixedPoint16_16(self.height)),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "tkhd"
+    }
+
+    fn get_flag_names() -> FlagNames {
+        FLAG_NAMES
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Tkhd {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        if self.version == 1 {
+            writer.write_u64(self.creation_time)?;
+            writer.write_u64(self.modification_time)?;
+            writer.write_u32(self.track_id)?;
+            writer.write_u32(self.reserved_1)?;
+            writer.write_u64(self.duration)?;
+        } else {
+            writer.write_u32(self.creation_time as u32)?;
+            writer.write_u32(self.modification_time as u32)?;
+            writer.write_u32(self.track_id)?;
+            writer.write_u32(self.reserved_1)?;
+            writer.write_u32(self.duration as u32)?;
+        }
+        for reserved in self.reserved_2.iter() {
+            writer.write_u32(*reserved)?;
+        }
+        writer.write_u16(self.layer as u16)?;
+        writer.write_u16(self.alternate_group as u16)?;
+        writer.write_u16(self.volume as u16)?;
+        writer.write_u16(self.reserved_3)?;
+        for value in self.matrix.iter() {
+            writer.write_u32(*value)?;
+        }
+        writer.write_u32(self.width)?;
+        writer.write_u32(self.height)
+    }
+}
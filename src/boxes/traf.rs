@@ -1,49 +1,73 @@
 use std::io::{BufRead, Seek};
-use std::rc::Rc;
 use super::{
     IsoBoxInfo,
     BoxParsingError,
     BoxReader,
+    BoxWriter,
     BoxValue,
-    IsoBoxEntry,
     IsoBoxParser,
+    IsoBoxSerializer,
 
+    sbgp::{map_samples_to_groups, SampleToGroup},
     utils::parse_children,
 };
 
 pub struct Traf {
-    content: Vec<(Rc<IsoBoxInfo>, Option<Box<dyn IsoBoxEntry>>)>,
+    content: Vec<super::IsoBoxData>,
+
+    /// Group description of each sample, derived from the contained `sbgp`
+    /// and `sgpd` boxes.
+    sample_to_group: Vec<SampleToGroup>,
 }
 
-impl<'a> IsoBoxParser for Traf {
+impl Traf {
+    pub fn new(content: Vec<super::IsoBoxData>) -> Self {
+        Self { content, sample_to_group: vec![] }
+    }
+}
+
+impl IsoBoxParser for Traf {
     fn parse<T: BufRead + Seek>(
         reader: &mut BoxReader<T>,
         content_size: Option<u64>,
         box_info: &std::rc::Rc<IsoBoxInfo>
     ) -> Result<Self, BoxParsingError> {
         let content = parse_children(reader, content_size, Some(box_info))?;
-        Ok(Self { content })
+        let sample_to_group = map_samples_to_groups(
+            &content,
+            true,
+            reader.get_context().get_current_track());
+        Ok(Self { content, sample_to_group })
     }
 
-    fn get_inner_boxes(self) -> Option<Vec<super::IsoBoxData>> {
-        Some(self.content)
+    fn get_inner_boxes_mut(&mut self) -> Option<&mut Vec<super::IsoBoxData>> {
+        Some(&mut self.content)
     }
 
-    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+    fn get_inner_boxes_ref(&self) -> Option<Vec<super::ContainedBoxInfo<'_>>> {
         Some(self.content.iter().map(|c|
-                (c.0.as_ref(), c.1.as_ref().map(|boxed| { std::boxed::Box::as_ref(&boxed) }))
+                (c.0.as_ref(), c.1.as_ref().map(|boxed| { std::boxed::Box::as_ref(boxed) }))
         ).collect())
     }
 
     fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
-        vec![]
+        if self.sample_to_group.is_empty() {
+            return vec![];
+        }
+        vec![
+            ("sample_to_group", BoxValue::Collection(
+                    self.sample_to_group.iter().map(|s| s.get_values()).collect()
+            )),
+        ]
     }
 
     fn get_short_name() -> &'static str {
         "traf"
     }
+}
 
-    fn get_long_name() -> &'static str {
-        "Track Fragment Box"
+impl IsoBoxSerializer for Traf {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_boxes(&self.content)
     }
 }
@@ -0,0 +1,78 @@
+use std::io::{BufRead, Seek};
+use std::rc::Rc;
+use super::{
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxInfo,
+
+    IsoBoxParser,
+    IsoBoxSerializer,
+
+    edts::Edts,
+    elst::{EditSegment, Elst},
+    utils::parse_children,
+};
+
+pub struct Trak {
+    content: Vec<super::IsoBoxData>,
+
+    /// Presentation timeline of the track, derived from its edit list.
+    timeline: Vec<EditSegment>,
+}
+
+impl IsoBoxParser for Trak {
+    fn parse<T: BufRead + Seek>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        box_info: &Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let content = parse_children(reader, content_size, Some(box_info))?;
+
+        // The edit list precedes the `mdhd` defining the media timescale, so
+        // the timeline can only be derived once the whole track is parsed.
+        let context = reader.get_context();
+        let timeline = content
+            .iter()
+            .filter_map(|c| c.1.as_ref().and_then(|b| b.as_any().downcast_ref::<Edts>()))
+            .flat_map(|edts| edts.get_content().iter())
+            .find_map(|c| c.1.as_ref().and_then(|b| b.as_any().downcast_ref::<Elst>()))
+            .map(|elst| elst.derive_timeline(
+                    context.get_movie_timescale(),
+                    context.get_current_track_timescale()))
+            .unwrap_or_default();
+        Ok(Self { content, timeline })
+    }
+
+    fn get_inner_boxes_mut(&mut self) -> Option<&mut Vec<super::IsoBoxData>> {
+        Some(&mut self.content)
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<super::ContainedBoxInfo<'_>>> {
+        Some(self.content.iter().map(|c|
+                (c.0.as_ref(), c.1.as_ref().map(|boxed| { std::boxed::Box::as_ref(boxed) }))
+        ).collect())
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        if self.timeline.is_empty() {
+            return vec![];
+        }
+        vec![
+            ("timeline", BoxValue::Collection(
+                    self.timeline.iter().map(|s| s.get_values()).collect()
+            )),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "trak"
+    }
+}
+
+impl IsoBoxSerializer for Trak {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_boxes(&self.content)
+    }
+}
@@ -0,0 +1,151 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+/// A `TrackReferenceTypeBox`: references of a given type to other tracks.
+pub struct TrackReference {
+    box_size: u32,
+    reference_type: String,
+    track_ids: Vec<u32>,
+
+    /// Bytes following the last track ID, too few to form another one, kept
+    /// as is.
+    trailing_data: Vec<u8>,
+}
+
+impl TrackReference {
+    pub fn get_reference_type(&self) -> &str {
+        &self.reference_type
+    }
+
+    pub fn get_track_ids(&self) -> &[u32] {
+        &self.track_ids
+    }
+}
+
+pub struct Tref {
+    references: Vec<TrackReference>,
+
+    /// Bytes following the last reference, too few to form another one, kept
+    /// as is.
+    trailing_data: Vec<u8>,
+}
+
+impl Tref {
+    pub fn get_references(&self) -> &[TrackReference] {
+        &self.references
+    }
+}
+
+/// Returns what a well-known track reference type indicates.
+fn get_reference_type_description(reference_type: &str) -> Option<&'static str> {
+    match reference_type {
+        "hint" => Some("hinted media"),
+        "cdsc" => Some("described track"),
+        "font" => Some("font track"),
+        "hind" => Some("hint dependency"),
+        "vdep" => Some("auxiliary depth video"),
+        "vplx" => Some("auxiliary parallax video"),
+        "subt" => Some("subtitle track"),
+        "chap" => Some("chapter track"),
+        "sync" => Some("synchronization source"),
+        "tmcd" => Some("timecode track"),
+        "scal" => Some("scalable base"),
+        "sbas" => Some("base track"),
+        "dpnd" => Some("decoding dependency"),
+        "ipir" => Some("IPI rights"),
+        "mpod" => Some("MPEG-4 object descriptor"),
+        "forc" => Some("forced subtitle track"),
+        "auxl" => Some("auxiliary track"),
+        _ => None,
+    }
+}
+
+impl IsoBoxParser for Tref {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let mut remaining = content_size;
+        let mut references = vec![];
+        while remaining.map_or(!reader.is_empty()?, |r| r >= 8) {
+            let box_size = reader.read_u32()?;
+            let reference_type = reader.read_4cc()?;
+            let content_size = match remaining {
+                Some(r) => (box_size as u64).clamp(8, r) - 8,
+                None => (box_size as u64).max(8) - 8,
+            };
+            remaining = remaining.map(|r| r - 8 - content_size);
+            let mut track_ids = Vec::with_capacity((content_size / 4) as usize);
+            for _ in 0..content_size / 4 {
+                track_ids.push(reader.read_u32()?);
+            }
+            let trailing_data = reader.read_bytes((content_size % 4) as usize)?;
+            references.push(TrackReference { box_size, reference_type, track_ids, trailing_data });
+        }
+        let trailing_data = match remaining {
+            Some(r) => reader.read_bytes(r as usize)?,
+            None => vec![],
+        };
+        Ok(Self { references, trailing_data })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        let mut values = vec![
+            ("references", BoxValue::Collection(
+                    self.references.iter().map(|reference| {
+                        let mut values = vec![
+                            ("box_size", BoxValue::from(reference.box_size)),
+                            ("reference_type",
+                             BoxValue::from(reference.reference_type.as_str())),
+                        ];
+                        if let Some(description) =
+                            get_reference_type_description(&reference.reference_type)
+                        {
+                            values.push(("description", BoxValue::from(description)));
+                        }
+                        values.push(("track_ids", BoxValue::from(reference.track_ids.as_slice())));
+                        if !reference.trailing_data.is_empty() {
+                            values.push(("trailing_data", BoxValue::Hex(&reference.trailing_data)));
+                        }
+                        values
+                    }).collect()
+            )),
+        ];
+        if !self.trailing_data.is_empty() {
+            values.push(("trailing_data", BoxValue::Hex(&self.trailing_data)));
+        }
+        values
+    }
+
+    fn get_short_name() -> &'static str {
+        "tref"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Tref {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        for reference in self.references.iter() {
+            writer.write_u32(reference.box_size)?;
+            writer.write_4cc(&reference.reference_type)?;
+            for track_id in reference.track_ids.iter() {
+                writer.write_u32(*track_id)?;
+            }
+            writer.write_bytes(&reference.trailing_data)?;
+        }
+        writer.write_bytes(&self.trailing_data)
+    }
+}
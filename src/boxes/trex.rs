@@ -0,0 +1,128 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+    SampleFlags,
+};
+
+pub struct Trex {
+    version: u8,
+    flags: Flags,
+    track_id: u32,
+    default_sample_description_index: u32,
+    default_sample_duration: u32,
+    default_sample_size: u32,
+    default_sample_flags: SampleFlags,
+
+    /// Timescale of the corresponding track, if known.
+    timescale: Option<u32>,
+}
+
+impl Trex {
+    /// Create a `trex` box without meaningful defaults, the fragments then
+    /// describing each sample.
+    pub fn new(track_id: u32) -> Self {
+        Self {
+            version: 0,
+            flags: Flags::from(0),
+            track_id,
+            default_sample_description_index: 1,
+            default_sample_duration: 0,
+            default_sample_size: 0,
+            default_sample_flags: SampleFlags::new(0),
+            timescale: None,
+        }
+    }
+
+    pub fn get_track_id(&self) -> u32 {
+        self.track_id
+    }
+
+    pub fn get_default_sample_description_index(&self) -> u32 {
+        self.default_sample_description_index
+    }
+
+    pub fn get_default_sample_duration(&self) -> u32 {
+        self.default_sample_duration
+    }
+
+    pub fn get_default_sample_size(&self) -> u32 {
+        self.default_sample_size
+    }
+
+    pub fn get_default_sample_flags(&self) -> SampleFlags {
+        self.default_sample_flags
+    }
+}
+
+impl IsoBoxParser for Trex {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        _content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let track_id = reader.read_u32()?;
+        let default_sample_description_index = reader.read_u32()?;
+        let default_sample_duration = reader.read_u32()?;
+        let default_sample_size = reader.read_u32()?;
+        let default_sample_flags = SampleFlags::read(reader)?;
+
+        // `trex` boxes are found after the `trak` boxes they relate to
+        let context = reader.get_context_mut();
+        context.set_current_track_id(track_id);
+        let timescale = context.get_current_track_timescale();
+        Ok(Self {
+            version,
+            flags,
+            track_id,
+            default_sample_description_index,
+            default_sample_duration,
+            default_sample_size,
+            default_sample_flags,
+            timescale,
+        })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("track_id", BoxValue::from(self.track_id)),
+            ("default_sample_description_index",
+             BoxValue::from(self.default_sample_description_index)),
+            ("default_sample_duration",
+             BoxValue::from_ticks(self.default_sample_duration as u64, self.timescale)),
+            ("default_sample_size", BoxValue::from(self.default_sample_size)),
+            ("default_sample_flags", BoxValue::from(self.default_sample_flags)),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "trex"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Trex {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_u32(self.track_id)?;
+        writer.write_u32(self.default_sample_description_index)?;
+        writer.write_u32(self.default_sample_duration)?;
+        writer.write_u32(self.default_sample_size)?;
+        writer.write_u32(self.default_sample_flags.into())
+    }
+}
@@ -0,0 +1,124 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+/// A `TrackGroupTypeBox`: membership of the track to a group of a given
+/// type.
+pub struct TrackGroup {
+    box_size: u32,
+    track_group_type: String,
+    version: u8,
+    flags: Flags,
+    track_group_id: u32,
+
+    /// Data specific to the group type, kept as is.
+    data: Vec<u8>,
+}
+
+pub struct Trgr {
+    groups: Vec<TrackGroup>,
+}
+
+/// Returns what a well-known track group type indicates.
+fn get_track_group_type_description(track_group_type: &str) -> Option<&'static str> {
+    match track_group_type {
+        "msrc" => Some("multi-source presentation"),
+        "ster" => Some("stereo video pair"),
+        "2dcc" => Some("2D spatial relationship"),
+        "alte" => Some("alternative tracks"),
+        _ => None,
+    }
+}
+
+impl IsoBoxParser for Trgr {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let mut remaining = content_size;
+        let mut groups = vec![];
+        while remaining.map_or(!reader.is_empty()?, |r| r >= 16) {
+            let box_size = reader.read_u32()?;
+            let track_group_type = reader.read_4cc()?;
+            let content_size = match remaining {
+                Some(r) => (box_size as u64).clamp(16, r) - 8,
+                None => (box_size as u64).max(16) - 8,
+            };
+            remaining = remaining.map(|r| r - 8 - content_size);
+            let version = reader.read_u8()?;
+            let flags = Flags::read(reader)?;
+            let track_group_id = reader.read_u32()?;
+            let data = reader.read_bytes(content_size as usize - 8)?;
+            groups.push(TrackGroup {
+                box_size,
+                track_group_type,
+                version,
+                flags,
+                track_group_id,
+                data,
+            });
+        }
+        if let Some(r) = remaining {
+            reader.read_bytes(r as usize)?;
+        }
+        Ok(Self { groups })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("groups", BoxValue::Collection(
+                    self.groups.iter().map(|group| {
+                        let mut values = vec![
+                            ("box_size", BoxValue::from(group.box_size)),
+                            ("track_group_type",
+                             BoxValue::from(group.track_group_type.as_str())),
+                        ];
+                        if let Some(description) =
+                            get_track_group_type_description(&group.track_group_type)
+                        {
+                            values.push(("description", BoxValue::from(description)));
+                        }
+                        values.push(("version", BoxValue::from(group.version)));
+                        values.push(("flags", BoxValue::from(group.flags)));
+                        values.push(("track_group_id", BoxValue::from(group.track_group_id)));
+                        if !group.data.is_empty() {
+                            values.push(("data", BoxValue::Hex(&group.data)));
+                        }
+                        values
+                    }).collect()
+            )),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "trgr"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Trgr {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        for group in self.groups.iter() {
+            writer.write_u32(16 + group.data.len() as u32)?;
+            writer.write_4cc(&group.track_group_type)?;
+            writer.write_u8(group.version)?;
+            writer.write_flags(group.flags)?;
+            writer.write_u32(group.track_group_id)?;
+            writer.write_bytes(&group.data)?;
+        }
+        Ok(())
+    }
+}
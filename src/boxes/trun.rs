@@ -3,16 +3,20 @@ use super::{
     IsoBoxInfo,
     BoxParsingError,
     BoxReader,
+    BoxWriter,
     BoxValue,
+    FlagNames,
     Flags,
     IsoBoxEntry,
+    SampleFlags,
     IsoBoxParser,
+    IsoBoxSerializer,
 };
 
 pub struct TrunSample {
     duration: Option<u32>,
     size: Option<u32>,
-    flags: Option<u32>,
+    flags: Option<SampleFlags>,
 
     // To make place both for an u32 and a i32
     // Alternatives (e.g. enums) require us to also make place for both in
@@ -25,8 +29,93 @@ pub struct Trun {
     flags: Flags,
     sample_count: u32,
     data_offset: Option<i32>,
-    first_sample_flags: Option<u32>,
+    first_sample_flags: Option<SampleFlags>,
     samples: Vec<TrunSample>,
+
+    /// Timescale of the corresponding track, if known.
+    timescale: Option<u32>,
+}
+
+const FLAG_NAMES: FlagNames = &[
+    (0x000001, "data-offset-present"),
+    (0x000004, "first-sample-flags-present"),
+    (0x000100, "sample-duration-present"),
+    (0x000200, "sample-size-present"),
+    (0x000400, "sample-flags-present"),
+    (0x000800, "sample-composition-time-offsets-present"),
+];
+
+impl TrunSample {
+    pub fn new(duration: u32, size: u32, flags: SampleFlags, composition_time_offset: i64) -> Self {
+        Self {
+            duration: Some(duration),
+            size: Some(size),
+            flags: Some(flags),
+            composition_time_offset: Some(composition_time_offset),
+        }
+    }
+}
+
+impl Trun {
+    /// Create a `trun` box describing the duration, size, flags and
+    /// composition time offset of each of its samples.
+    /// Composition time offsets are omitted if they are all `0`.
+    pub fn new(data_offset: i32, mut samples: Vec<TrunSample>) -> Self {
+        let offsets = samples.iter().filter_map(|s| s.composition_time_offset);
+        let has_offsets = offsets.clone().any(|offset| offset != 0);
+        let version = if offsets.clone().any(|offset| offset < 0) { 1 } else { 0 };
+        if !has_offsets {
+            for sample in samples.iter_mut() {
+                sample.composition_time_offset = None;
+            }
+        }
+        let flags = 0x000001 | 0x000100 | 0x000200 | 0x000400 |
+            if has_offsets { 0x000800 } else { 0 };
+        Self {
+            version,
+            flags: Flags::from(flags),
+            sample_count: samples.len() as u32,
+            data_offset: Some(data_offset),
+            first_sample_flags: None,
+            samples,
+            timescale: None,
+        }
+    }
+
+    pub fn get_sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    pub fn get_data_offset(&self) -> Option<i32> {
+        self.data_offset
+    }
+
+    /// Returns the size of each sample, `None` for samples relying on a
+    /// default size.
+    pub fn get_sample_sizes(&self) -> Vec<Option<u32>> {
+        self.samples.iter().map(|s| s.size).collect()
+    }
+
+    /// Returns the duration of each sample, `None` for samples relying on a
+    /// default duration.
+    pub fn get_sample_durations(&self) -> Vec<Option<u32>> {
+        self.samples.iter().map(|s| s.duration).collect()
+    }
+
+    /// Returns the flags of each sample, `None` for samples relying on
+    /// default flags.
+    pub fn get_sample_flags(&self) -> Vec<Option<SampleFlags>> {
+        self.samples.iter()
+            .enumerate()
+            .map(|(i, s)| if i == 0 { self.first_sample_flags.or(s.flags) } else { s.flags })
+            .collect()
+    }
+
+    /// Returns the difference between the composition and decoding times of
+    /// each sample.
+    pub fn get_composition_time_offsets(&self) -> Vec<i64> {
+        self.samples.iter().map(|s| s.composition_time_offset.unwrap_or(0)).collect()
+    }
 }
 
 impl IsoBoxParser for Trun {
@@ -49,7 +138,7 @@ impl IsoBoxParser for Trun {
         let data_offset = if flag_data_offset { Some(reader.read_i32()?) }
         else { None };
 
-        let first_sample_flags = if flag_first_sample { Some(reader.read_u32()?) }
+        let first_sample_flags = if flag_first_sample { Some(SampleFlags::read(reader)?) }
         else { None };
 
         let mut samples: Vec<TrunSample> = Vec::with_capacity(sample_count as usize);
@@ -61,7 +150,7 @@ impl IsoBoxParser for Trun {
                 Some(reader.read_u32()?)
             } else { None };
             let flags = if flag_sample {
-                Some(reader.read_u32()?)
+                Some(SampleFlags::read(reader)?)
             } else { None };
             let composition_time_offset = if flag_sample_composition_time_offset {
                 if version == 0 {
@@ -74,6 +163,7 @@ impl IsoBoxParser for Trun {
             };
             samples.push(TrunSample { duration, size, flags, composition_time_offset });
         }
+        let timescale = reader.get_context().get_current_track_timescale();
         Ok(Self {
             version,
             flags,
@@ -81,6 +171,7 @@ impl IsoBoxParser for Trun {
             data_offset,
             first_sample_flags,
             samples,
+            timescale,
         })
     }
 
@@ -102,7 +193,9 @@ impl IsoBoxParser for Trun {
                  self.samples.iter().map(|sample| {
                      let mut sample_values = vec![];
                      if let Some(duration) = sample.duration {
-                         sample_values.push(("duration", BoxValue::from(duration)));
+                         sample_values.push(
+                             ("duration",
+                              BoxValue::from_ticks(duration as u64, self.timescale)));
                      }
                      if let Some(size) = sample.size {
                          sample_values.push(("size", BoxValue::from(size)));
@@ -113,7 +206,9 @@ impl IsoBoxParser for Trun {
                      if let Some(composition_time_offset) = sample.composition_time_offset {
                          sample_values.push(
                              ("composition_time_offset",
-                              BoxValue::from(composition_time_offset)));
+                              BoxValue::from_signed_ticks(
+                                  composition_time_offset,
+                                  self.timescale)));
                      }
                      sample_values
                  }).collect()
@@ -126,15 +221,37 @@ impl IsoBoxParser for Trun {
         "trun"
     }
 
-    fn get_long_name() -> &'static str {
-        "Track Fragment Run Box"
+    fn get_flag_names() -> FlagNames {
+        FLAG_NAMES
     }
 
-    fn get_inner_boxes(self) -> Option<Vec<super::IsoBoxData>> {
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
         None
     }
+}
 
-    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
-        None
+impl IsoBoxSerializer for Trun {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        writer.write_u32(self.sample_count)?;
+        if let Some(data_offset) = self.data_offset {
+            writer.write_i32(data_offset)?;
+        }
+        if let Some(first_sample_flags) = self.first_sample_flags {
+            writer.write_u32(first_sample_flags.into())?;
+        }
+        for sample in self.samples.iter() {
+            let fields = [
+                sample.duration,
+                sample.size,
+                sample.flags.map(u32::from),
+                sample.composition_time_offset.map(|offset| offset as u32),
+            ];
+            for field in fields.iter().flatten() {
+                writer.write_u32(*field)?;
+            }
+        }
+        Ok(())
     }
 }
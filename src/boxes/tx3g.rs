@@ -0,0 +1,188 @@
+use std::io::{BufRead, Seek};
+use std::rc::Rc;
+use super::{
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxInfo,
+
+    IsoBoxParser,
+    IsoBoxSerializer,
+
+    utils::parse_children,
+};
+
+/// Size of the fields of a `tx3g` sample entry before its contained boxes.
+const TX3G_SAMPLE_ENTRY_HEADER_SIZE: u64 = 38;
+
+/// Default text box of a 3GPP timed text track, relative to the track's
+/// region.
+pub struct BoxRecord {
+    top: i16,
+    left: i16,
+    bottom: i16,
+    right: i16,
+}
+
+/// Style applied to a range of characters of 3GPP timed text.
+pub struct StyleRecord {
+    start_char: u16,
+    end_char: u16,
+    font_id: u16,
+    face_style_flags: u8,
+
+    /// Names of the styles set in `face_style_flags`.
+    face_styles: Vec<String>,
+    font_size: u8,
+    text_color_rgba: [u8; 4],
+}
+
+impl StyleRecord {
+    pub fn write(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u16(self.start_char)?;
+        writer.write_u16(self.end_char)?;
+        writer.write_u16(self.font_id)?;
+        writer.write_u8(self.face_style_flags)?;
+        writer.write_u8(self.font_size)?;
+        writer.write_bytes(&self.text_color_rgba)
+    }
+
+    pub fn read<T: BufRead>(reader: &mut BoxReader<T>) -> Result<Self, std::io::Error> {
+        let start_char = reader.read_u16()?;
+        let end_char = reader.read_u16()?;
+        let font_id = reader.read_u16()?;
+        let face_style_flags = reader.read_u8()?;
+        let font_size = reader.read_u8()?;
+        let mut text_color_rgba = [0u8; 4];
+        text_color_rgba.copy_from_slice(&reader.read_bytes(4)?);
+        let face_styles = [(0x01, "bold"), (0x02, "italic"), (0x04, "underline")]
+            .iter()
+            .filter(|(flag, _)| face_style_flags & flag != 0)
+            .map(|(_, name)| name.to_string())
+            .collect();
+        Ok(Self {
+            start_char,
+            end_char,
+            font_id,
+            face_style_flags,
+            face_styles,
+            font_size,
+            text_color_rgba,
+        })
+    }
+
+    pub fn get_values(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("start_char", BoxValue::from(self.start_char)),
+            ("end_char", BoxValue::from(self.end_char)),
+            ("font_id", BoxValue::from(self.font_id)),
+            ("face_style_flags", BoxValue::from(self.face_style_flags)),
+            ("face_styles", BoxValue::from(self.face_styles.as_slice())),
+            ("font_size", BoxValue::from(self.font_size)),
+            ("text_color_rgba", BoxValue::Hex(&self.text_color_rgba)),
+        ]
+    }
+}
+
+/// Sample entry of a 3GPP timed text track, giving the default display
+/// parameters of its text samples.
+pub struct Tx3g {
+    reserved: [u8; 6],
+    data_reference_index: u16,
+    display_flags: u32,
+    horizontal_justification: i8,
+    vertical_justification: i8,
+    background_color_rgba: [u8; 4],
+    default_text_box: BoxRecord,
+    default_style: StyleRecord,
+    content: Vec<super::IsoBoxData>,
+}
+
+impl IsoBoxParser for Tx3g {
+    fn parse<T: BufRead + Seek>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        box_info: &Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let mut reserved = [0u8; 6];
+        reserved.copy_from_slice(&reader.read_bytes(6)?);
+        let data_reference_index = reader.read_u16()?;
+        let display_flags = reader.read_u32()?;
+        let horizontal_justification = reader.read_u8()? as i8;
+        let vertical_justification = reader.read_u8()? as i8;
+        let mut background_color_rgba = [0u8; 4];
+        background_color_rgba.copy_from_slice(&reader.read_bytes(4)?);
+        let default_text_box = BoxRecord {
+            top: reader.read_u16()? as i16,
+            left: reader.read_u16()? as i16,
+            bottom: reader.read_u16()? as i16,
+            right: reader.read_u16()? as i16,
+        };
+        let default_style = StyleRecord::read(reader)?;
+        let content = parse_children(
+            reader,
+            content_size.map(|size| size.saturating_sub(TX3G_SAMPLE_ENTRY_HEADER_SIZE)),
+            Some(box_info))?;
+        Ok(Self {
+            reserved,
+            data_reference_index,
+            display_flags,
+            horizontal_justification,
+            vertical_justification,
+            background_color_rgba,
+            default_text_box,
+            default_style,
+            content,
+        })
+    }
+
+    fn get_inner_boxes_mut(&mut self) -> Option<&mut Vec<super::IsoBoxData>> {
+        Some(&mut self.content)
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<super::ContainedBoxInfo<'_>>> {
+        Some(self.content.iter().map(|c|
+                (c.0.as_ref(), c.1.as_ref().map(|boxed| { std::boxed::Box::as_ref(boxed) }))
+        ).collect())
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("reserved", BoxValue::from(self.reserved.as_ref())),
+            ("data_reference_index", BoxValue::from(self.data_reference_index)),
+            ("display_flags", BoxValue::from(self.display_flags)),
+            ("horizontal_justification", BoxValue::from(self.horizontal_justification as i16)),
+            ("vertical_justification", BoxValue::from(self.vertical_justification as i16)),
+            ("background_color_rgba", BoxValue::Hex(&self.background_color_rgba)),
+            ("default_text_box", BoxValue::Collection(vec![vec![
+                ("top", BoxValue::from(self.default_text_box.top)),
+                ("left", BoxValue::from(self.default_text_box.left)),
+                ("bottom", BoxValue::from(self.default_text_box.bottom)),
+                ("right", BoxValue::from(self.default_text_box.right)),
+            ]])),
+            ("default_style", BoxValue::Collection(vec![self.default_style.get_values()])),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "tx3g"
+    }
+}
+
+impl IsoBoxSerializer for Tx3g {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_bytes(&self.reserved)?;
+        writer.write_u16(self.data_reference_index)?;
+        writer.write_u32(self.display_flags)?;
+        writer.write_u8(self.horizontal_justification as u8)?;
+        writer.write_u8(self.vertical_justification as u8)?;
+        writer.write_bytes(&self.background_color_rgba)?;
+        let text_box = &self.default_text_box;
+        for coordinate in [text_box.top, text_box.left, text_box.bottom, text_box.right].iter() {
+            writer.write_u16(*coordinate as u16)?;
+        }
+        self.default_style.write(writer)?;
+        writer.write_boxes(&self.content)
+    }
+}
@@ -9,24 +9,99 @@ use super::{
     IsoBoxParser,
     IsoBoxEntry,
 
+    audio_sample_entry,
+    av1c,
+    avcc,
+    btrt,
+    clap,
+    clli,
+    co64,
+    colr,
+    cprt,
+    ctts,
+    dac3,
+    dec3,
+    dops,
+    edts,
+    elng,
+    elst,
+    esds,
+    fiel,
     free,
+    frma,
+    ftab,
     ftyp,
     hdlr,
+    hvcc,
+    idat,
+    iinf,
+    iloc,
+    ilst,
+    imir,
+    infe,
+    ipco,
+    ipma,
+    iprp,
+    iref,
+    irot,
+    ispe,
+    keys,
+    leva,
     mdat,
+    mdcv,
+    mdhd,
+    mdia,
+    meta,
     mfhd,
+    minf,
     moof,
     moov,
+    mvex,
     mvhd,
+    name,
+    pasp,
     pdin,
+    pitm,
+    pixi,
+    prft,
     saio,
     saiz,
+    sbgp,
+    schi,
+    schm,
+    sdtp,
+    senc,
+    sgpd,
     sidx,
+    sinf,
+    ssix,
+    stbl,
+    stco,
+    stpp,
+    stsc,
+    stsd,
+    stss,
+    stsz,
+    stts,
     styp,
     subs,
+    tenc,
     tfdt,
     tfhd,
+    tkhd,
     traf,
+    trak,
+    tref,
+    trex,
+    trgr,
     trun,
+    tx3g,
+    udta,
+    visual_sample_entry,
+    vlab,
+    vttc,
+    vvcc,
+    wvtt,
 };
 
 /// Parse every box found from the current offset until `size_limit` is reached.
@@ -42,7 +117,9 @@ pub fn parse_children<T: BufRead + Seek>(
 
     if let Some(limit) = size_limit {
         size_limit_remaining = Some(limit);
-        if limit < 4 {
+
+        // A container may be empty, but not hold less than a box header
+        if limit != 0 && limit < 4 {
             let pos_before = reader.get_pos()?;
             let parent_box_info = container_box_info.map(|i| Rc::clone(i));
             return Err(
@@ -61,7 +138,7 @@ pub fn parse_children<T: BufRead + Seek>(
         let pos_before = reader.get_pos()?;
         let mut size = reader.read_u32()? as u64;
 
-        let box_name = reader.read_str(4)?;
+        let box_name = reader.read_4cc()?;
 
         let box_remaining_size = match size {
             0 => None,
@@ -104,11 +181,13 @@ pub fn parse_children<T: BufRead + Seek>(
             None
         };
 
+        let header_size = reader.get_pos()? - pos_before;
         let parent_box_info = container_box_info.as_ref().map(|info| {
             Rc::clone(info)
         });
         let box_info = Rc::new(IsoBoxInfo {
             size,
+            header_size,
             short_name: box_name,
             user_type,
             offset: pos_before,
@@ -128,42 +207,240 @@ pub fn parse_children<T: BufRead + Seek>(
         // TODO HashMap implementation? This might need to define a Sized return
         // type for the `parse` functions instead of the `Self` they return today.
         let data: Option<Box<dyn IsoBoxEntry>> = match box_info.short_name.as_ref() {
+            "Opus" =>
+                Some(Box::new(audio_sample_entry::AudioSampleEntry::parse(reader, box_remaining_size, &box_info)?)),
+            "ac-3" =>
+                Some(Box::new(audio_sample_entry::AudioSampleEntry::parse(reader, box_remaining_size, &box_info)?)),
+            "ac-4" =>
+                Some(Box::new(audio_sample_entry::AudioSampleEntry::parse(reader, box_remaining_size, &box_info)?)),
+            "av01" =>
+                Some(Box::new(visual_sample_entry::VisualSampleEntry::parse(reader, box_remaining_size, &box_info)?)),
+            "av1C" =>
+                Some(Box::new(av1c::Av1c::parse(reader, box_remaining_size, &box_info)?)),
+            "avc1" =>
+                Some(Box::new(visual_sample_entry::VisualSampleEntry::parse(reader, box_remaining_size, &box_info)?)),
+            "avc2" =>
+                Some(Box::new(visual_sample_entry::VisualSampleEntry::parse(reader, box_remaining_size, &box_info)?)),
+            "avc3" =>
+                Some(Box::new(visual_sample_entry::VisualSampleEntry::parse(reader, box_remaining_size, &box_info)?)),
+            "avc4" =>
+                Some(Box::new(visual_sample_entry::VisualSampleEntry::parse(reader, box_remaining_size, &box_info)?)),
+            "avcC" =>
+                Some(Box::new(avcc::Avcc::parse(reader, box_remaining_size, &box_info)?)),
+            "btrt" =>
+                Some(Box::new(btrt::Btrt::parse(reader, box_remaining_size, &box_info)?)),
+            "clap" =>
+                Some(Box::new(clap::Clap::parse(reader, box_remaining_size, &box_info)?)),
+            "clli" =>
+                Some(Box::new(clli::Clli::parse(reader, box_remaining_size, &box_info)?)),
+            "co64" =>
+                Some(Box::new(co64::Co64::parse(reader, box_remaining_size, &box_info)?)),
+            "colr" =>
+                Some(Box::new(colr::Colr::parse(reader, box_remaining_size, &box_info)?)),
+            "cprt" =>
+                Some(Box::new(cprt::Cprt::parse(reader, box_remaining_size, &box_info)?)),
+            "ctts" =>
+                Some(Box::new(ctts::Ctts::parse(reader, box_remaining_size, &box_info)?)),
+            "dOps" =>
+                Some(Box::new(dops::Dops::parse(reader, box_remaining_size, &box_info)?)),
+            "dac3" =>
+                Some(Box::new(dac3::Dac3::parse(reader, box_remaining_size, &box_info)?)),
+            "dec3" =>
+                Some(Box::new(dec3::Dec3::parse(reader, box_remaining_size, &box_info)?)),
+            "dva1" =>
+                Some(Box::new(visual_sample_entry::VisualSampleEntry::parse(reader, box_remaining_size, &box_info)?)),
+            "dvav" =>
+                Some(Box::new(visual_sample_entry::VisualSampleEntry::parse(reader, box_remaining_size, &box_info)?)),
+            "dvh1" =>
+                Some(Box::new(visual_sample_entry::VisualSampleEntry::parse(reader, box_remaining_size, &box_info)?)),
+            "dvhe" =>
+                Some(Box::new(visual_sample_entry::VisualSampleEntry::parse(reader, box_remaining_size, &box_info)?)),
+            "ec-3" =>
+                Some(Box::new(audio_sample_entry::AudioSampleEntry::parse(reader, box_remaining_size, &box_info)?)),
+            "edts" =>
+                Some(Box::new(edts::Edts::parse(reader, box_remaining_size, &box_info)?)),
+            "elng" =>
+                Some(Box::new(elng::Elng::parse(reader, box_remaining_size, &box_info)?)),
+            "elst" =>
+                Some(Box::new(elst::Elst::parse(reader, box_remaining_size, &box_info)?)),
+            "enca" =>
+                Some(Box::new(audio_sample_entry::AudioSampleEntry::parse(reader, box_remaining_size, &box_info)?)),
+            "encv" =>
+                Some(Box::new(visual_sample_entry::VisualSampleEntry::parse(reader, box_remaining_size, &box_info)?)),
+            "esds" =>
+                Some(Box::new(esds::Esds::parse(reader, box_remaining_size, &box_info)?)),
+            "fLaC" =>
+                Some(Box::new(audio_sample_entry::AudioSampleEntry::parse(reader, box_remaining_size, &box_info)?)),
+            "fiel" =>
+                Some(Box::new(fiel::Fiel::parse(reader, box_remaining_size, &box_info)?)),
             "free" =>
                 Some(Box::new(free::Free::parse(reader, box_remaining_size, &box_info)?)),
+            "frma" =>
+                Some(Box::new(frma::Frma::parse(reader, box_remaining_size, &box_info)?)),
+            "ftab" =>
+                Some(Box::new(ftab::Ftab::parse(reader, box_remaining_size, &box_info)?)),
             "ftyp" =>
                 Some(Box::new(ftyp::Ftyp::parse(reader, box_remaining_size, &box_info)?)),
             "hdlr" =>
                 Some(Box::new(hdlr::Hdlr::parse(reader, box_remaining_size, &box_info)?)),
+            "hev1" =>
+                Some(Box::new(visual_sample_entry::VisualSampleEntry::parse(reader, box_remaining_size, &box_info)?)),
+            "hev2" =>
+                Some(Box::new(visual_sample_entry::VisualSampleEntry::parse(reader, box_remaining_size, &box_info)?)),
+            "hvc1" =>
+                Some(Box::new(visual_sample_entry::VisualSampleEntry::parse(reader, box_remaining_size, &box_info)?)),
+            "hvc2" =>
+                Some(Box::new(visual_sample_entry::VisualSampleEntry::parse(reader, box_remaining_size, &box_info)?)),
+            "hvcC" =>
+                Some(Box::new(hvcc::Hvcc::parse(reader, box_remaining_size, &box_info)?)),
+            "idat" =>
+                Some(Box::new(idat::Idat::parse(reader, box_remaining_size, &box_info)?)),
+            "iinf" =>
+                Some(Box::new(iinf::Iinf::parse(reader, box_remaining_size, &box_info)?)),
+            "iloc" =>
+                Some(Box::new(iloc::Iloc::parse(reader, box_remaining_size, &box_info)?)),
+            "ilst" =>
+                Some(Box::new(ilst::Ilst::parse(reader, box_remaining_size, &box_info)?)),
+            "imir" =>
+                Some(Box::new(imir::Imir::parse(reader, box_remaining_size, &box_info)?)),
+            "infe" =>
+                Some(Box::new(infe::Infe::parse(reader, box_remaining_size, &box_info)?)),
+            "ipco" =>
+                Some(Box::new(ipco::Ipco::parse(reader, box_remaining_size, &box_info)?)),
+            "ipma" =>
+                Some(Box::new(ipma::Ipma::parse(reader, box_remaining_size, &box_info)?)),
+            "iprp" =>
+                Some(Box::new(iprp::Iprp::parse(reader, box_remaining_size, &box_info)?)),
+            "iref" =>
+                Some(Box::new(iref::Iref::parse(reader, box_remaining_size, &box_info)?)),
+            "irot" =>
+                Some(Box::new(irot::Irot::parse(reader, box_remaining_size, &box_info)?)),
+            "ispe" =>
+                Some(Box::new(ispe::Ispe::parse(reader, box_remaining_size, &box_info)?)),
+            "keys" =>
+                Some(Box::new(keys::Keys::parse(reader, box_remaining_size, &box_info)?)),
+            "leva" =>
+                Some(Box::new(leva::Leva::parse(reader, box_remaining_size, &box_info)?)),
             "mdat" =>
                 Some(Box::new(mdat::Mdat::parse(reader, box_remaining_size, &box_info)?)),
+            "mdcv" =>
+                Some(Box::new(mdcv::Mdcv::parse(reader, box_remaining_size, &box_info)?)),
+            "mdhd" =>
+                Some(Box::new(mdhd::Mdhd::parse(reader, box_remaining_size, &box_info)?)),
+            "mdia" =>
+                Some(Box::new(mdia::Mdia::parse(reader, box_remaining_size, &box_info)?)),
+            "meta" =>
+                Some(Box::new(meta::Meta::parse(reader, box_remaining_size, &box_info)?)),
             "mfhd" =>
                 Some(Box::new(mfhd::Mfhd::parse(reader, box_remaining_size, &box_info)?)),
+            "minf" =>
+                Some(Box::new(minf::Minf::parse(reader, box_remaining_size, &box_info)?)),
             "moof" =>
                 Some(Box::new(moof::Moof::parse(reader, box_remaining_size, &box_info)?)),
             "moov" =>
                 Some(Box::new(moov::Moov::parse(reader, box_remaining_size, &box_info)?)),
+            "mp4a" =>
+                Some(Box::new(audio_sample_entry::AudioSampleEntry::parse(reader, box_remaining_size, &box_info)?)),
+            "mp4v" =>
+                Some(Box::new(visual_sample_entry::VisualSampleEntry::parse(reader, box_remaining_size, &box_info)?)),
+            "mvex" =>
+                Some(Box::new(mvex::Mvex::parse(reader, box_remaining_size, &box_info)?)),
             "mvhd" =>
                 Some(Box::new(mvhd::Mvhd::parse(reader, box_remaining_size, &box_info)?)),
+            "name" =>
+                Some(Box::new(name::Name::parse(reader, box_remaining_size, &box_info)?)),
+            "pasp" =>
+                Some(Box::new(pasp::Pasp::parse(reader, box_remaining_size, &box_info)?)),
             "pdin" =>
                 Some(Box::new(pdin::Pdin::parse(reader, box_remaining_size, &box_info)?)),
+            "pitm" =>
+                Some(Box::new(pitm::Pitm::parse(reader, box_remaining_size, &box_info)?)),
+            "pixi" =>
+                Some(Box::new(pixi::Pixi::parse(reader, box_remaining_size, &box_info)?)),
+            "prft" =>
+                Some(Box::new(prft::Prft::parse(reader, box_remaining_size, &box_info)?)),
             "saio" =>
                 Some(Box::new(saio::Saio::parse(reader, box_remaining_size, &box_info)?)),
             "saiz" =>
                 Some(Box::new(saiz::Saiz::parse(reader, box_remaining_size, &box_info)?)),
+            "sbgp" =>
+                Some(Box::new(sbgp::Sbgp::parse(reader, box_remaining_size, &box_info)?)),
+            "schi" =>
+                Some(Box::new(schi::Schi::parse(reader, box_remaining_size, &box_info)?)),
+            "schm" =>
+                Some(Box::new(schm::Schm::parse(reader, box_remaining_size, &box_info)?)),
+            "sdtp" =>
+                Some(Box::new(sdtp::Sdtp::parse(reader, box_remaining_size, &box_info)?)),
+            "senc" =>
+                Some(Box::new(senc::Senc::parse(reader, box_remaining_size, &box_info)?)),
+            "sgpd" =>
+                Some(Box::new(sgpd::Sgpd::parse(reader, box_remaining_size, &box_info)?)),
             "sidx" =>
                 Some(Box::new(sidx::Sidx::parse(reader, box_remaining_size, &box_info)?)),
+            "sinf" =>
+                Some(Box::new(sinf::Sinf::parse(reader, box_remaining_size, &box_info)?)),
+            "ssix" =>
+                Some(Box::new(ssix::Ssix::parse(reader, box_remaining_size, &box_info)?)),
+            "stbl" =>
+                Some(Box::new(stbl::Stbl::parse(reader, box_remaining_size, &box_info)?)),
+            "stco" =>
+                Some(Box::new(stco::Stco::parse(reader, box_remaining_size, &box_info)?)),
+            "stpp" =>
+                Some(Box::new(stpp::Stpp::parse(reader, box_remaining_size, &box_info)?)),
+            "stsc" =>
+                Some(Box::new(stsc::Stsc::parse(reader, box_remaining_size, &box_info)?)),
+            "stsd" =>
+                Some(Box::new(stsd::Stsd::parse(reader, box_remaining_size, &box_info)?)),
+            "stss" =>
+                Some(Box::new(stss::Stss::parse(reader, box_remaining_size, &box_info)?)),
+            "stsz" =>
+                Some(Box::new(stsz::Stsz::parse(reader, box_remaining_size, &box_info)?)),
+            "stts" =>
+                Some(Box::new(stts::Stts::parse(reader, box_remaining_size, &box_info)?)),
             "styp" =>
                 Some(Box::new(styp::Styp::parse(reader, box_remaining_size, &box_info)?)),
             "subs" =>
                 Some(Box::new(subs::Subs::parse(reader, box_remaining_size, &box_info)?)),
+            "tenc" =>
+                Some(Box::new(tenc::Tenc::parse(reader, box_remaining_size, &box_info)?)),
             "tfdt" =>
                 Some(Box::new(tfdt::Tfdt::parse(reader, box_remaining_size, &box_info)?)),
             "tfhd" =>
                 Some(Box::new(tfhd::Tfhd::parse(reader, box_remaining_size, &box_info)?)),
+            "tkhd" =>
+                Some(Box::new(tkhd::Tkhd::parse(reader, box_remaining_size, &box_info)?)),
             "traf" =>
                 Some(Box::new(traf::Traf::parse(reader, box_remaining_size, &box_info)?)),
+            "trak" =>
+                Some(Box::new(trak::Trak::parse(reader, box_remaining_size, &box_info)?)),
+            "tref" =>
+                Some(Box::new(tref::Tref::parse(reader, box_remaining_size, &box_info)?)),
+            "trex" =>
+                Some(Box::new(trex::Trex::parse(reader, box_remaining_size, &box_info)?)),
+            "trgr" =>
+                Some(Box::new(trgr::Trgr::parse(reader, box_remaining_size, &box_info)?)),
             "trun" =>
                 Some(Box::new(trun::Trun::parse(reader, box_remaining_size, &box_info)?)),
+            "tx3g" =>
+                Some(Box::new(tx3g::Tx3g::parse(reader, box_remaining_size, &box_info)?)),
+            "udta" =>
+                Some(Box::new(udta::Udta::parse(reader, box_remaining_size, &box_info)?)),
+            "vlab" =>
+                Some(Box::new(vlab::Vlab::parse(reader, box_remaining_size, &box_info)?)),
+            "vp08" =>
+                Some(Box::new(visual_sample_entry::VisualSampleEntry::parse(reader, box_remaining_size, &box_info)?)),
+            "vp09" =>
+                Some(Box::new(visual_sample_entry::VisualSampleEntry::parse(reader, box_remaining_size, &box_info)?)),
+            "vttC" =>
+                Some(Box::new(vttc::Vttc::parse(reader, box_remaining_size, &box_info)?)),
+            "vvc1" =>
+                Some(Box::new(visual_sample_entry::VisualSampleEntry::parse(reader, box_remaining_size, &box_info)?)),
+            "vvcC" =>
+                Some(Box::new(vvcc::Vvcc::parse(reader, box_remaining_size, &box_info)?)),
+            "vvi1" =>
+                Some(Box::new(visual_sample_entry::VisualSampleEntry::parse(reader, box_remaining_size, &box_info)?)),
+            "wvtt" =>
+                Some(Box::new(wvtt::Wvtt::parse(reader, box_remaining_size, &box_info)?)),
             _ => {
                 if let Some(size_to_read) = box_remaining_size {
                     reader.skip_bytes(size_to_read as u64)?;
@@ -200,3 +477,50 @@ pub fn parse_children<T: BufRead + Seek>(
     }
     Ok(contents)
 }
+
+/// Decode a string as found in metadata boxes: UTF-16 if it starts with a
+/// byte order mark, UTF-8 otherwise, ignoring the terminating null
+/// character(s).
+/// Invalid sequences are replaced rather than causing an error, as such
+/// strings are only displayed.
+pub fn decode_text(data: &[u8]) -> String {
+    if data.len() >= 2 && data[0] == 0xFE && data[1] == 0xFF {
+        let units: Vec<u16> = data[2..]
+            .chunks_exact(2)
+            .map(|c| u16::from_be_bytes([c[0], c[1]]))
+            .take_while(|u| *u != 0)
+            .collect();
+        String::from_utf16_lossy(&units)
+    } else {
+        let end = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+        String::from_utf8_lossy(&data[..end]).into_owned()
+    }
+}
+
+/// Recursively look for boxes parsed as `B` in `entry` and its children.
+pub fn collect_boxes<'a, B: 'static>(
+    info: &'a IsoBoxInfo,
+    entry: Option<&'a dyn IsoBoxEntry>,
+    found: &mut Vec<(&'a IsoBoxInfo, &'a B)>
+) {
+    let entry = match entry {
+        Some(entry) => entry,
+        None => return,
+    };
+    if let Some(parsed) = entry.as_any().downcast_ref::<B>() {
+        found.push((info, parsed));
+    }
+    for (child_info, child) in entry.get_inner_boxes_ref().unwrap_or_default() {
+        collect_boxes(child_info, child, found);
+    }
+}
+
+/// Returns every box parsed as `B` in the given box and its children.
+pub fn find_in<'a, B: 'static>(
+    info: &'a IsoBoxInfo,
+    entry: &'a dyn IsoBoxEntry
+) -> Vec<(&'a IsoBoxInfo, &'a B)> {
+    let mut found = vec![];
+    collect_boxes(info, Some(entry), &mut found);
+    found
+}
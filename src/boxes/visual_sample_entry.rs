@@ -0,0 +1,134 @@
+use std::io::{BufRead, Seek};
+use std::rc::Rc;
+use super::{
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxInfo,
+
+    IsoBoxParser,
+    IsoBoxSerializer,
+
+    utils::{decode_text, parse_children},
+};
+
+/// Size of the fields of a visual sample entry before its contained boxes.
+const VISUAL_SAMPLE_ENTRY_HEADER_SIZE: u64 = 78;
+
+/// Sample entry of a video track (e.g. `avc1`, `hvc1` or `av01`), whose
+/// contained boxes describe the coding and display parameters.
+pub struct VisualSampleEntry {
+    reserved: [u8; 6],
+    data_reference_index: u16,
+    pre_defined: [u8; 16],
+    width: u16,
+    height: u16,
+    horizresolution: u32,
+    vertresolution: u32,
+    reserved_2: u32,
+    frame_count: u16,
+    compressorname: String,
+    /// The compressor name as encoded, a Pascal string padded to 32 bytes.
+    compressorname_data: Vec<u8>,
+    depth: u16,
+    pre_defined_2: u16,
+    content: Vec<super::IsoBoxData>,
+}
+
+impl IsoBoxParser for VisualSampleEntry {
+    fn parse<T: BufRead + Seek>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        box_info: &Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let mut reserved = [0u8; 6];
+        reserved.copy_from_slice(&reader.read_bytes(6)?);
+        let data_reference_index = reader.read_u16()?;
+        // pre_defined, reserved and pre_defined[3]
+        let mut pre_defined = [0u8; 16];
+        pre_defined.copy_from_slice(&reader.read_bytes(16)?);
+        let width = reader.read_u16()?;
+        let height = reader.read_u16()?;
+        let horizresolution = reader.read_u32()?;
+        let vertresolution = reader.read_u32()?;
+        let reserved_2 = reader.read_u32()?;
+        let frame_count = reader.read_u16()?;
+
+        // Pascal string, padded to 32 bytes
+        let compressorname_data = reader.read_bytes(32)?;
+        let name_length = (compressorname_data[0] as usize).min(31);
+        let compressorname = decode_text(&compressorname_data[1..=name_length]);
+
+        let depth = reader.read_u16()?;
+        let pre_defined_2 = reader.read_u16()?;
+        let content = parse_children(
+            reader,
+            content_size.map(|size| size.saturating_sub(VISUAL_SAMPLE_ENTRY_HEADER_SIZE)),
+            Some(box_info))?;
+        Ok(Self {
+            reserved,
+            data_reference_index,
+            pre_defined,
+            width,
+            height,
+            horizresolution,
+            vertresolution,
+            reserved_2,
+            frame_count,
+            compressorname,
+            compressorname_data,
+            depth,
+            pre_defined_2,
+            content,
+        })
+    }
+
+    fn get_inner_boxes_mut(&mut self) -> Option<&mut Vec<super::IsoBoxData>> {
+        Some(&mut self.content)
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<super::ContainedBoxInfo<'_>>> {
+        Some(self.content.iter().map(|c|
+                (c.0.as_ref(), c.1.as_ref().map(|boxed| { std::boxed::Box::as_ref(boxed) }))
+        ).collect())
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("reserved", BoxValue::from(self.reserved.as_ref())),
+            ("data_reference_index", BoxValue::from(self.data_reference_index)),
+            ("width", BoxValue::from(self.width)),
+            ("height", BoxValue::from(self.height)),
+            ("horizresolution", BoxValue::UFixedPoint16_16(self.horizresolution)),
+            ("vertresolution", BoxValue::UFixedPoint16_16(self.vertresolution)),
+            ("frame_count", BoxValue::from(self.frame_count)),
+            ("compressorname", BoxValue::from(self.compressorname.as_str())),
+            ("depth", BoxValue::from(self.depth)),
+        ]
+    }
+
+    /// Visual sample entries are named after their coding, which is only
+    /// known from the box information.
+    fn get_short_name() -> &'static str {
+        "vide"
+    }
+}
+
+impl IsoBoxSerializer for VisualSampleEntry {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_bytes(&self.reserved)?;
+        writer.write_u16(self.data_reference_index)?;
+        writer.write_bytes(&self.pre_defined)?;
+        writer.write_u16(self.width)?;
+        writer.write_u16(self.height)?;
+        writer.write_u32(self.horizresolution)?;
+        writer.write_u32(self.vertresolution)?;
+        writer.write_u32(self.reserved_2)?;
+        writer.write_u16(self.frame_count)?;
+        writer.write_bytes(&self.compressorname_data)?;
+        writer.write_u16(self.depth)?;
+        writer.write_u16(self.pre_defined_2)?;
+        writer.write_boxes(&self.content)
+    }
+}
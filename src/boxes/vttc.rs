@@ -0,0 +1,53 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+    utils::decode_text,
+};
+
+/// Header of the WebVTT file the cues of a track come from, e.g. "WEBVTT".
+pub struct Vttc {
+    config: String,
+    /// The config as encoded in the box.
+    data: Vec<u8>,
+}
+
+impl IsoBoxParser for Vttc {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let data = match content_size {
+            Some(size) => reader.read_bytes(size as usize)?,
+            None => reader.read_to_end()?,
+        };
+        Ok(Self { config: decode_text(&data), data })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("config", BoxValue::from(self.config.as_str())),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "vttC"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Vttc {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_bytes(&self.data)
+    }
+}
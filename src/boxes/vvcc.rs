@@ -0,0 +1,77 @@
+use std::io::BufRead;
+use super::{
+    IsoBoxInfo,
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    Flags,
+    IsoBoxEntry,
+    IsoBoxParser,
+    IsoBoxSerializer,
+};
+
+/// VVC decoder configuration. Only the fields preceding the profile, tier
+/// and level information are decoded, the rest is kept as is.
+pub struct Vvcc {
+    version: u8,
+    flags: Flags,
+    length_size_minus_one: u8,
+    ptl_present_flag: bool,
+    remaining_data: Vec<u8>,
+}
+
+impl Vvcc {
+    /// Returns the size in bytes of the length field preceding each NAL unit
+    /// in the samples.
+    pub fn get_nal_length_size(&self) -> u8 {
+        self.length_size_minus_one + 1
+    }
+}
+
+impl IsoBoxParser for Vvcc {
+    fn parse<T: BufRead>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        _box_info: &std::rc::Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let version = reader.read_u8()?;
+        let flags = Flags::read(reader)?;
+        let byte = reader.read_u8()?;
+        let length_size_minus_one = (byte >> 1) & 0x03;
+        let ptl_present_flag = byte & 0x01 != 0;
+        let remaining_data = match content_size {
+            Some(size) => reader.read_bytes(size.saturating_sub(5) as usize)?,
+            None => reader.read_to_end()?,
+        };
+        Ok(Self { version, flags, length_size_minus_one, ptl_present_flag, remaining_data })
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("version", BoxValue::from(self.version)),
+            ("flags", BoxValue::from(self.flags)),
+            ("length_size_minus_one", BoxValue::from(self.length_size_minus_one)),
+            ("ptl_present_flag", BoxValue::from(self.ptl_present_flag)),
+            ("remaining_data", BoxValue::Hex(&self.remaining_data)),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "vvcC"
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<(&IsoBoxInfo, Option<&dyn IsoBoxEntry>)>> {
+        None
+    }
+}
+
+impl IsoBoxSerializer for Vvcc {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_u8(self.version)?;
+        writer.write_flags(self.flags)?;
+        // reserved bits are all set to 1
+        writer.write_u8(0xF8 | self.length_size_minus_one << 1 | self.ptl_present_flag as u8)?;
+        writer.write_bytes(&self.remaining_data)
+    }
+}
@@ -0,0 +1,68 @@
+use std::io::{BufRead, Seek};
+use std::rc::Rc;
+use super::{
+    BoxParsingError,
+    BoxReader,
+    BoxWriter,
+    BoxValue,
+    IsoBoxInfo,
+
+    IsoBoxParser,
+    IsoBoxSerializer,
+
+    utils::parse_children,
+};
+
+/// Sample entry of a WebVTT track. Its `vttC` box carries the header of the
+/// WebVTT file the cues come from.
+pub struct Wvtt {
+    reserved: [u8; 6],
+    data_reference_index: u16,
+    content: Vec<super::IsoBoxData>,
+}
+
+impl IsoBoxParser for Wvtt {
+    fn parse<T: BufRead + Seek>(
+        reader: &mut BoxReader<T>,
+        content_size: Option<u64>,
+        box_info: &Rc<IsoBoxInfo>
+    ) -> Result<Self, BoxParsingError> {
+        let mut reserved = [0u8; 6];
+        reserved.copy_from_slice(&reader.read_bytes(6)?);
+        let data_reference_index = reader.read_u16()?;
+        let content = parse_children(
+            reader,
+            content_size.map(|size| size.saturating_sub(8)),
+            Some(box_info))?;
+        Ok(Self { reserved, data_reference_index, content })
+    }
+
+    fn get_inner_boxes_mut(&mut self) -> Option<&mut Vec<super::IsoBoxData>> {
+        Some(&mut self.content)
+    }
+
+    fn get_inner_boxes_ref(&self) -> Option<Vec<super::ContainedBoxInfo<'_>>> {
+        Some(self.content.iter().map(|c|
+                (c.0.as_ref(), c.1.as_ref().map(|boxed| { std::boxed::Box::as_ref(boxed) }))
+        ).collect())
+    }
+
+    fn get_inner_values_ref(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("reserved", BoxValue::from(self.reserved.as_ref())),
+            ("data_reference_index", BoxValue::from(self.data_reference_index)),
+        ]
+    }
+
+    fn get_short_name() -> &'static str {
+        "wvtt"
+    }
+}
+
+impl IsoBoxSerializer for Wvtt {
+    fn serialize_content(&self, writer: &mut BoxWriter) -> Result<(), std::io::Error> {
+        writer.write_bytes(&self.reserved)?;
+        writer.write_u16(self.data_reference_index)?;
+        writer.write_boxes(&self.content)
+    }
+}
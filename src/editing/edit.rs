@@ -0,0 +1,703 @@
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
+use std::rc::Rc;
+use super::offsets::{
+    UpdateError,
+    get_sidx_field,
+    get_sidx_referenced_size_field,
+    get_updated_field,
+};
+use super::super::boxes::{
+    BoxWriter,
+    ContainedBoxInfo,
+    IsoBoxData,
+    IsoBoxInfo,
+    find_offset_fields,
+    get_sidx_references,
+    get_ssix_ranges,
+    parse_isobmff,
+};
+
+/// Designates boxes in a file, e.g. `moov/trak[2]/mdia/hdlr`.
+///
+/// Each `/`-separated segment is the short name of a box contained in the one
+/// designated by the previous segment, `*` designating any box. It may be
+/// followed by a 1-based index in brackets to only designate one of the boxes
+/// with that name, in order. A `**` segment designates any number of levels
+/// of nesting, e.g. `**/free` designates every `free` box of the file.
+pub struct BoxPath {
+    path: String,
+    segments: Vec<PathSegment>,
+}
+
+enum PathSegment {
+    Boxes {
+        name: String,
+        index: Option<usize>,
+    },
+    AnyDepth,
+}
+
+impl std::str::FromStr for BoxPath {
+    type Err = String;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        let segments = path.trim_matches('/').split('/').map(|segment| {
+            if segment == "**" {
+                return Ok(PathSegment::AnyDepth);
+            }
+            let (name, index) = match segment.strip_suffix(']').and_then(|s| s.split_once('[')) {
+                Some((name, index)) => match index.parse::<usize>() {
+                    Ok(index) if index > 0 => (name, Some(index)),
+                    _ => return Err(format!("invalid index \"{}\" in \"{}\"", index, path)),
+                },
+                None => (segment, None),
+            };
+            if name.is_empty() {
+                return Err(format!("empty box name in \"{}\"", path));
+            }
+            Ok(PathSegment::Boxes { name: name.to_owned(), index })
+        }).collect::<Result<Vec<PathSegment>, String>>()?;
+        Ok(Self { path: path.to_owned(), segments })
+    }
+}
+
+impl std::fmt::Display for BoxPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.path)
+    }
+}
+
+/// An edit to perform on the boxes of a file.
+///
+/// The data given to replace or insert boxes is made of one or more complete
+/// boxes, header included.
+pub enum EditOperation {
+    /// Remove every designated box.
+    Remove(BoxPath),
+
+    /// Replace every designated box by the given boxes.
+    Replace(BoxPath, Vec<u8>),
+
+    /// Insert the given boxes before every designated box.
+    InsertBefore(BoxPath, Vec<u8>),
+
+    /// Insert the given boxes after every designated box.
+    InsertAfter(BoxPath, Vec<u8>),
+
+    /// Insert the given boxes at the end of every designated container box.
+    InsertInto(BoxPath, Vec<u8>),
+}
+
+#[derive(Clone, Copy)]
+enum Action {
+    Remove,
+    Replace,
+    InsertBefore,
+    InsertAfter,
+    InsertInto,
+}
+
+/// The input file followed by the data of the boxes to insert, so that the
+/// boxes of both can be parsed and copied alike.
+/// Each inserted part ends as if it was a file of its own, as does the input.
+struct EditSource<'a, R: Read + Seek> {
+    input: &'a mut R,
+    input_size: u64,
+    /// Position of `input`, when known.
+    input_position: Option<u64>,
+
+    /// Data of each part to insert, one after the other.
+    inserted: Vec<u8>,
+    /// Offset, in `inserted`, at which each part ends.
+    part_ends: Vec<usize>,
+
+    position: u64,
+}
+
+impl<'a, R: Read + Seek> EditSource<'a, R> {
+    fn create(input: &'a mut R) -> io::Result<Self> {
+        let input_size = input.seek(SeekFrom::End(0))?;
+        Ok(Self {
+            input,
+            input_size,
+            input_position: None,
+            inserted: vec![],
+            part_ends: vec![],
+            position: 0,
+        })
+    }
+
+    /// Add the given data after the previous parts, and return the offset it
+    /// starts at.
+    fn add_part(&mut self, data: &[u8]) -> u64 {
+        let start = self.input_size + self.inserted.len() as u64;
+        self.inserted.extend_from_slice(data);
+        self.part_ends.push(self.inserted.len());
+        start
+    }
+
+    /// Parse the boxes of the part starting at the given offset.
+    fn parse_part(&mut self, start: u64) -> io::Result<Vec<IsoBoxData>> {
+        self.seek(SeekFrom::Start(start))?;
+        let boxes = parse_isobmff(BufReader::new(&mut *self)).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("invalid boxes to insert: {}", e))
+        })?;
+        if boxes.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "no box to insert"));
+        }
+        Ok(boxes)
+    }
+}
+
+impl<'a, R: Read + Seek> Read for EditSource<'a, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position < self.input_size {
+            let max_size = buf.len().min((self.input_size - self.position) as usize);
+            if self.input_position != Some(self.position) {
+                self.input.seek(SeekFrom::Start(self.position))?;
+            }
+            let read = self.input.read(&mut buf[..max_size])?;
+            self.position += read as u64;
+            self.input_position = Some(self.position);
+            return Ok(read);
+        }
+        let start = (self.position - self.input_size) as usize;
+        let end = self.part_ends.iter()
+            .find(|end| **end > start)
+            .copied()
+            .unwrap_or(start);
+        let size = buf.len().min(end.saturating_sub(start));
+        let data = self.inserted.get(start..start + size).ok_or_else(|| io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "read past the end of the edited file"))?;
+        buf[..size].copy_from_slice(data);
+        self.position += size as u64;
+        Ok(size)
+    }
+}
+
+impl<'a, R: Read + Seek> Seek for EditSource<'a, R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let total_size = self.input_size + self.inserted.len() as u64;
+        let new_position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => add_signed(total_size, offset),
+            SeekFrom::Current(offset) => add_signed(self.position, offset),
+        };
+        self.position = new_position.ok_or_else(|| io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "invalid seek to a negative position"))?;
+        Ok(self.position)
+    }
+}
+
+/// Add a signed offset to a position, returning `None` if the result is
+/// negative or overflows.
+fn add_signed(position: u64, offset: i64) -> Option<u64> {
+    if offset < 0 {
+        position.checked_sub(offset.unsigned_abs())
+    } else {
+        position.checked_add(offset as u64)
+    }
+}
+
+/// Where a range of the input file is written in the edited file.
+struct Placement {
+    offset: u64,
+    size: u64,
+    new_offset: u64,
+}
+
+/// Perform `action` on the boxes designated by the `path` segments, among
+/// `boxes` and their inner boxes.
+/// `get_new_boxes` gives the boxes to insert.
+/// Returns the number of boxes the action was performed on.
+fn apply_action(
+    boxes: &mut Vec<IsoBoxData>,
+    path: &[PathSegment],
+    action: Action,
+    get_new_boxes: &mut dyn FnMut() -> io::Result<Vec<IsoBoxData>>
+) -> io::Result<usize> {
+    let (name, index, remaining_path) = match path.split_first() {
+        None => return Ok(0),
+        Some((PathSegment::AnyDepth, remaining_path)) => {
+            // Inner boxes are processed first, so boxes inserted at this
+            // level are never looked into
+            let mut count = 0;
+            for (_, entry) in boxes.iter_mut() {
+                if let Some(inner_boxes) = entry.as_mut().and_then(|e| e.get_inner_boxes_mut()) {
+                    count += apply_action(inner_boxes, path, action, get_new_boxes)?;
+                }
+            }
+            return Ok(count + apply_action(boxes, remaining_path, action, get_new_boxes)?);
+        },
+        Some((PathSegment::Boxes { name, index }, remaining_path)) =>
+            (name, index, remaining_path),
+    };
+    let matching: Vec<usize> = boxes.iter()
+        .enumerate()
+        .filter(|(_, (info, _))| name == "*" || info.short_name == *name)
+        .map(|(i, _)| i)
+        .collect();
+    let designated = match index {
+        Some(index) => matching.get(index - 1).into_iter().copied().collect(),
+        None => matching,
+    };
+
+    let mut count = 0;
+    // The last boxes are processed first so that the indexes of the others
+    // stay valid
+    for i in designated.into_iter().rev() {
+        if !remaining_path.is_empty() {
+            if let Some(inner_boxes) = boxes[i].1.as_mut().and_then(|e| e.get_inner_boxes_mut()) {
+                count += apply_action(inner_boxes, remaining_path, action, get_new_boxes)?;
+            }
+            continue;
+        }
+        match action {
+            Action::Remove => {
+                boxes.remove(i);
+            },
+            Action::Replace => {
+                boxes.splice(i..=i, get_new_boxes()?);
+            },
+            Action::InsertBefore => {
+                boxes.splice(i..i, get_new_boxes()?);
+            },
+            Action::InsertAfter => {
+                boxes.splice(i + 1..i + 1, get_new_boxes()?);
+            },
+            Action::InsertInto => {
+                match boxes[i].1.as_mut().and_then(|e| e.get_inner_boxes_mut()) {
+                    Some(inner_boxes) => inner_boxes.extend(get_new_boxes()?),
+                    None => continue,
+                }
+            },
+        }
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Compute where the boxes of the input file are written in the edited file,
+/// `boxes` being written from `new_offset`.
+///
+/// For container boxes, only their header is considered, as their other
+/// fields and inner boxes may have moved relatively to it.
+fn place_boxes(
+    boxes: &[ContainedBoxInfo],
+    mut new_offset: u64,
+    input_size: u64,
+    placements: &mut Vec<Placement>
+) -> io::Result<()> {
+    for (info, entry) in boxes {
+        let size = BoxWriter::get_box_size(info, *entry)?;
+        let is_from_input = info.offset < input_size;
+        match entry.and_then(|e| e.get_inner_boxes_ref()) {
+            Some(inner_boxes) => {
+                if is_from_input {
+                    placements.push(Placement {
+                        offset: info.offset,
+                        size: info.header_size,
+                        new_offset,
+                    });
+                }
+                // Inner boxes are always the last part of a container
+                let mut inner_size = 0;
+                for (inner_info, inner_entry) in inner_boxes.iter() {
+                    inner_size += BoxWriter::get_box_size(inner_info, *inner_entry)?;
+                }
+                place_boxes(&inner_boxes, new_offset + size - inner_size, input_size, placements)?;
+            },
+            None if is_from_input => {
+                // A size of 0 means that the box extends to the end of the file
+                let input_box_size = if info.size == 0 { input_size - info.offset } else { info.size };
+                placements.push(Placement { offset: info.offset, size: input_box_size, new_offset });
+            },
+            None => {},
+        }
+        new_offset += size;
+    }
+    Ok(())
+}
+
+/// Returns the offset in the edited file of the given offset of the input
+/// file. `None` if it points to data which was removed.
+fn map_offset(placements: &[Placement], offset: u64) -> Option<u64> {
+    placements.iter()
+        .find(|p| p.offset <= offset && offset < p.offset + p.size)
+        .map(|p| p.new_offset + offset - p.offset)
+}
+
+/// Same as `map_offset` for the offset following the end of some data.
+fn map_end_offset(placements: &[Placement], end_offset: u64) -> Option<u64> {
+    end_offset.checked_sub(1).and_then(|last| map_offset(placements, last)).map(|o| o + 1)
+}
+
+/// Write to `output` the boxes of the input file, parsed as `results`, once
+/// the given operations are performed on them in order.
+///
+/// The offsets found in the boxes of the input file, as well as the byte
+/// ranges of its `sidx` and `ssix` boxes, are updated so that they still
+/// point to the same data.
+/// Returns a description of each issue encountered.
+pub fn edit(
+    reader: &mut (impl Read + Seek),
+    mut results: Vec<IsoBoxData>,
+    operations: &[EditOperation],
+    output: &mut (impl Write + Seek)
+) -> io::Result<Vec<String>> {
+    let mut warnings = vec![];
+    let mut source = EditSource::create(reader)?;
+    let input_size = source.input_size;
+
+    for operation in operations {
+        let (path, action, data) = match operation {
+            EditOperation::Remove(path) => (path, Action::Remove, None),
+            EditOperation::Replace(path, data) => (path, Action::Replace, Some(data)),
+            EditOperation::InsertBefore(path, data) => (path, Action::InsertBefore, Some(data)),
+            EditOperation::InsertAfter(path, data) => (path, Action::InsertAfter, Some(data)),
+            EditOperation::InsertInto(path, data) => (path, Action::InsertInto, Some(data)),
+        };
+        let part_start = match data {
+            Some(data) => {
+                let start = source.add_part(data);
+                // Check that the data is valid even if no box is designated
+                source.parse_part(start)?;
+                Some(start)
+            },
+            None => None,
+        };
+        let mut get_new_boxes = || match part_start {
+            Some(start) => source.parse_part(start),
+            None => Ok(vec![]),
+        };
+        let count = apply_action(&mut results, &path.segments, action, &mut get_new_boxes)?;
+        if count == 0 {
+            warnings.push(format!("\"{}\" does not designate any box to edit", path));
+        }
+    }
+
+    // A box extending to the end of the file needs an actual size if other
+    // boxes now follow it
+    let last_index = results.len().saturating_sub(1);
+    for (info, entry) in results[..last_index].iter_mut() {
+        if info.size != 0 {
+            continue;
+        }
+        let is_container = entry.as_ref().and_then(|e| e.get_inner_boxes_ref()).is_some();
+        if !is_container {
+            // Copied as is, with its new size
+            *entry = None;
+        }
+        *info = Rc::new(IsoBoxInfo {
+            offset: info.offset,
+            size: input_size - info.offset,
+            header_size: info.header_size,
+            short_name: info.short_name.clone(),
+            user_type: info.user_type,
+            parent_box_info: info.parent_box_info.clone(),
+        });
+    }
+
+    let mut placements = vec![];
+    let top_level_boxes: Vec<ContainedBoxInfo> = results.iter()
+        .map(|(info, entry)| (info.as_ref(), entry.as_deref()))
+        .collect();
+    place_boxes(&top_level_boxes, 0, input_size, &mut placements)?;
+
+    // Bytes to overwrite in the edited file, by offset in that file
+    let mut patches: Vec<(u64, Vec<u8>)> = vec![];
+    let mut unmapped_count = 0;
+    for field in find_offset_fields(&results) {
+        // Fields of inserted boxes are left as is
+        let position = match map_offset(&placements, field.get_position()) {
+            Some(position) => position,
+            None => continue,
+        };
+        match get_updated_field(&field, |offset| map_offset(&placements, offset)) {
+            Ok(bytes) => patches.push((position, bytes)),
+            Err(UpdateError::Unmapped) => unmapped_count += 1,
+            Err(UpdateError::Overflow(new_value)) => warnings.push(format!(
+                "the offset at {} cannot hold its new value {}",
+                position, new_value)),
+        }
+    }
+
+    // Original and new start offset of the references of each `sidx` box, by
+    // index in `results`
+    let mut sidx_new_starts: Vec<(usize, Vec<(u64, u64)>)> = vec![];
+    for (index, (info, entry)) in results.iter().enumerate() {
+        let references = match entry.as_deref().and_then(get_sidx_references) {
+            Some(references) => references,
+            None => continue,
+        };
+        let new_offset = match map_offset(&placements, info.offset) {
+            Some(new_offset) => new_offset,
+            None => continue,
+        };
+        // References are stored at the end of the box, after the fixed
+        // fields and the earliest presentation time and first offset, which
+        // are both either 32 or 64-bit
+        let references_size = references.len() as u64 * 12;
+        let time_and_offset_size = (info.size - info.header_size)
+            .saturating_sub(16 + references_size);
+        if time_and_offset_size != 8 && time_and_offset_size != 16 {
+            continue;
+        }
+        let references_position = new_offset + info.size - references_size;
+        let anchor_point = new_offset + info.size;
+
+        // Data inserted between two contiguous references is attributed to
+        // the second one
+        let mut first_start = None;
+        let mut new_starts = vec![];
+        let mut previous_end: Option<(u64, u64)> = None;
+        for (i, reference) in references.iter().enumerate() {
+            let new_start = match previous_end {
+                Some((end, new_end)) if end == reference.start_offset => Some(new_end),
+                None if reference.start_offset == info.offset + info.size => Some(anchor_point),
+                _ => map_offset(&placements, reference.start_offset),
+            };
+            let new_end = map_end_offset(&placements, reference.end_offset);
+            let (new_start, new_end) = match (new_start, new_end) {
+                (Some(new_start), Some(new_end)) if new_start <= new_end => (new_start, new_end),
+                _ => {
+                    unmapped_count += 1;
+                    previous_end = None;
+                    continue;
+                },
+            };
+            if i == 0 {
+                first_start = Some(new_start);
+            }
+            new_starts.push((reference.start_offset, new_start));
+            let referenced_size_field = get_sidx_referenced_size_field(
+                info, i, reference.is_sidx_reference, new_end - new_start, &mut warnings);
+            if let Some(field) = referenced_size_field {
+                patches.push((references_position + i as u64 * 12, field.to_vec()));
+            }
+            previous_end = Some((reference.end_offset, new_end));
+        }
+        if let Some(first_start) = first_start {
+            let first_offset = first_start.saturating_sub(anchor_point);
+            let first_offset_position = references_position - 4 - time_and_offset_size / 2;
+            let first_offset_field = get_sidx_field(
+                info, "first_offset", time_and_offset_size / 2, first_offset, &mut warnings);
+            if let Some(field) = first_offset_field {
+                patches.push((first_offset_position, field));
+            }
+        }
+        sidx_new_starts.push((index, new_starts));
+    }
+
+    // A `ssix` box divides the references of the `sidx` box preceding it into
+    // ranges, whose sizes are updated the same way
+    for (index, (info, entry)) in results.iter().enumerate() {
+        let subsegments = match entry.as_deref().and_then(get_ssix_ranges) {
+            Some(subsegments) => subsegments,
+            None => continue,
+        };
+        let new_offset = match map_offset(&placements, info.offset) {
+            Some(new_offset) => new_offset,
+            None => continue,
+        };
+        let sidx_starts: &[(u64, u64)] = sidx_new_starts.iter()
+            .find(|(i, _)| i + 1 == index)
+            .map_or(&[], |(_, starts)| starts);
+
+        // Ranges are stored after the fixed fields, each subsegment starting
+        // with its range count
+        let mut range_position = new_offset + info.header_size + 8;
+        let mut previous_end: Option<(u64, u64)> = None;
+        for ranges in subsegments.iter() {
+            range_position += 4;
+            for range in ranges.iter() {
+                let position = range_position;
+                range_position += 4;
+                let (start, end) = match range {
+                    Some(range) => *range,
+                    None => continue,
+                };
+                let new_start = match previous_end {
+                    Some((end, new_end)) if end == start => Some(new_end),
+                    _ => sidx_starts.iter()
+                        .find(|(s, _)| *s == start)
+                        .map(|(_, new_start)| *new_start)
+                        .or_else(|| map_offset(&placements, start)),
+                };
+                let new_end = map_end_offset(&placements, end);
+                let (new_start, new_end) = match (new_start, new_end) {
+                    (Some(new_start), Some(new_end)) if new_start <= new_end => (new_start, new_end),
+                    _ => {
+                        unmapped_count += 1;
+                        previous_end = None;
+                        continue;
+                    },
+                };
+                previous_end = Some((end, new_end));
+                let range_size = new_end - new_start;
+                if range_size > 0xFF_FFFF {
+                    warnings.push(format!(
+                        "the range size at {} cannot hold its new value {}",
+                        position, range_size));
+                    continue;
+                }
+                // The level, in the first byte, is left as is
+                patches.push((position + 1, range_size.to_be_bytes()[5..].to_vec()));
+            }
+        }
+    }
+    if unmapped_count > 0 {
+        warnings.push(format!(
+            "{} offsets point to data which was removed and were left unchanged",
+            unmapped_count));
+    }
+
+    BoxWriter::create(output, Some(&mut source)).write_boxes(&results)?;
+    for (position, bytes) in patches {
+        output.seek(SeekFrom::Start(position))?;
+        output.write_all(&bytes)?;
+    }
+    output.seek(SeekFrom::End(0))?;
+    Ok(warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use super::{edit, EditOperation};
+    use super::super::super::boxes::{
+        OffsetField,
+        find_offset_fields,
+        get_sidx_references,
+        get_ssix_ranges,
+        get_track_samples,
+        parse_isobmff,
+    };
+
+    /// A 20-byte `free` box.
+    fn create_free_box() -> Vec<u8> {
+        let mut free = vec![0, 0, 0, 20];
+        free.extend_from_slice(b"free");
+        free.resize(20, 0);
+        free
+    }
+
+    /// Perform the given operations on a fixture, returning both the input
+    /// and the edited file.
+    fn edit_fixture(file_name: &str, operations: &[EditOperation]) -> (Vec<u8>, Vec<u8>) {
+        let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), file_name);
+        let input = std::fs::read(&path).unwrap();
+        let data = parse_isobmff(Cursor::new(&input)).unwrap();
+        let mut output = Cursor::new(vec![]);
+        let warnings = edit(&mut Cursor::new(&input), data, operations, &mut output).unwrap();
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        (input, output.into_inner())
+    }
+
+    fn read_sample_data(file: &[u8]) -> Vec<Vec<&[u8]>> {
+        let data = parse_isobmff(Cursor::new(file)).unwrap();
+        get_track_samples(&data).into_iter()
+            .map(|track| track.samples.iter()
+                .map(|s| &file[s.offset as usize..(s.offset + s.size as u64) as usize])
+                .collect())
+            .collect()
+    }
+
+    /// Returns the offset in the file pointed to by each offset field.
+    fn read_offset_targets(file: &[u8]) -> Vec<u64> {
+        let data = parse_isobmff(Cursor::new(file)).unwrap();
+        find_offset_fields(&data).iter()
+            .map(|field| match field {
+                OffsetField::Absolute { value, .. } => *value,
+                OffsetField::Relative { base_offset, value, .. } =>
+                    base_offset.checked_add_signed(*value).unwrap(),
+            })
+            .collect()
+    }
+
+    /// Byte ranges of the references of a `sidx` box.
+    type SidxByteRanges = Vec<(u64, u64)>;
+
+    /// Byte ranges of each range of each subsegment of a `ssix` box.
+    type SubsegmentByteRanges = Vec<Vec<Option<(u64, u64)>>>;
+
+    /// Returns the byte ranges of the references of each `sidx` box, then
+    /// the ones of each `ssix` box's ranges.
+    fn read_byte_ranges(file: &[u8]) -> (Vec<SidxByteRanges>, Vec<SubsegmentByteRanges>) {
+        let data = parse_isobmff(Cursor::new(file)).unwrap();
+        let entries: Vec<_> = data.iter().filter_map(|(_, entry)| entry.as_deref()).collect();
+        let sidx_ranges = entries.iter()
+            .filter_map(|entry| get_sidx_references(*entry))
+            .map(|references| references.iter().map(|r| (r.start_offset, r.end_offset)).collect())
+            .collect();
+        let ssix_ranges = entries.iter().filter_map(|entry| get_ssix_ranges(*entry)).collect();
+        (sidx_ranges, ssix_ranges)
+    }
+
+    #[test]
+    fn update_chunk_offsets() {
+        let (input, output) = edit_fixture("progressive.mp4", &[
+            EditOperation::InsertInto("moov".parse().unwrap(), create_free_box()),
+        ]);
+        assert_eq!(output.len(), input.len() + 20);
+        let targets: Vec<u64> = read_offset_targets(&input).iter().map(|t| t + 20).collect();
+        assert!(!targets.is_empty());
+        assert_eq!(read_offset_targets(&output), targets);
+        assert_eq!(read_sample_data(&output), read_sample_data(&input));
+    }
+
+    #[test]
+    fn update_item_offsets() {
+        let (input, output) = edit_fixture("heif.avif", &[
+            EditOperation::InsertBefore("meta".parse().unwrap(), create_free_box()),
+            EditOperation::Remove("meta/iprp".parse().unwrap()),
+        ]);
+        // Items stored in the `mdat` box move by 20 bytes minus the size of
+        // the removed `iprp` box
+        let data = parse_isobmff(Cursor::new(&input)).unwrap();
+        let iprp_size = data[1].1.as_deref().unwrap()
+            .get_inner_boxes_ref().unwrap()
+            .iter()
+            .find(|(info, _)| info.short_name == "iprp")
+            .unwrap().0.size;
+        let shift = 20 - iprp_size as i64;
+        let targets = read_offset_targets(&input);
+        assert_eq!(targets.len(), 2);
+        let new_targets = read_offset_targets(&output);
+        assert_eq!(new_targets, targets.iter().map(|t| (*t as i64 + shift) as u64).collect::<Vec<_>>());
+        for (target, new_target) in targets.iter().zip(new_targets.iter()) {
+            assert_eq!(input[*target as usize..*target as usize + 20],
+                output[*new_target as usize..*new_target as usize + 20]);
+        }
+    }
+
+    #[test]
+    fn update_sidx_references() {
+        let (_, output) = edit_fixture("sidx.mp4", &[
+            EditOperation::InsertInto("moof[1]".parse().unwrap(), create_free_box()),
+        ]);
+        // Both the first reference and the `sidx` box referencing it grow
+        assert_eq!(read_byte_ranges(&output).0, vec![
+            vec![(60, 400)],
+            vec![(116, 268), (268, 396)],
+        ]);
+    }
+
+    #[test]
+    fn update_ssix_ranges() {
+        let (_, output) = edit_fixture("ssix.mp4", &[
+            EditOperation::InsertInto("moof[1]".parse().unwrap(), create_free_box()),
+        ]);
+        // Data inserted between two contiguous ranges belongs to the second
+        // one
+        assert_eq!(read_byte_ranges(&output), (
+            vec![vec![(151, 303), (303, 435)]],
+            vec![vec![
+                vec![Some((151, 175)), Some((175, 303))],
+                vec![Some((303, 434))],
+            ]],
+        ));
+    }
+}
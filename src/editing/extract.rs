@@ -0,0 +1,470 @@
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use super::offsets::{
+    UpdateError,
+    get_sidx_field,
+    get_sidx_referenced_size_field,
+    get_updated_field,
+};
+use super::super::boxes::{
+    IsoBoxData,
+    IsoBoxInfo,
+    ResolvedSidxReference,
+    find_offset_fields,
+    get_fragment_start_time,
+    get_sidx_references,
+};
+
+/// Part of a file to extract.
+pub enum Selection {
+    /// Every top-level box whose name is in the list.
+    Boxes(Vec<String>),
+
+    /// The initialization segment: the top-level boxes preceding the first
+    /// `moof` box, media data excluded.
+    InitSegment,
+
+    /// The initialization segment followed by the fragments whose 1-based
+    /// indexes are in the given inclusive range. Without a last index, every
+    /// fragment from the first one is extracted.
+    Fragments {
+        first: usize,
+        last: Option<usize>,
+    },
+
+    /// The initialization segment followed by the fragments whose decode time
+    /// starts in the given range, in seconds. Without an end, every fragment
+    /// from the start is extracted.
+    TimeRange {
+        start: f64,
+        end: Option<f64>,
+    },
+}
+
+/// Top-level boxes belonging to media segments, which are not part of the
+/// initialization segment.
+pub(super) const SEGMENT_BOXES: [&str; 8] = ["styp", "sidx", "ssix", "prft", "emsg", "moof", "mdat", "mfra"];
+
+/// Top-level boxes which come before the `moof` box of the fragment they
+/// belong to. Other boxes found after a `moof` belong to its fragment.
+const FRAGMENT_PREFIX_BOXES: [&str; 4] = ["styp", "prft", "emsg", "ssix"];
+
+/// A top-level box of the input file, and where it goes in the output file.
+struct BoxPlacement {
+    index: usize,
+    offset: u64,
+    size: u64,
+    new_offset: u64,
+    new_size: u64,
+
+    /// For `sidx` boxes, the 0-based indexes of the references kept.
+    kept_references: Option<Vec<usize>>,
+}
+
+/// Returns for each top-level box the 0-based index of the fragment it
+/// belongs to. `None` for boxes not part of any fragment.
+fn get_fragment_indexes(results: &[IsoBoxData]) -> Vec<Option<usize>> {
+    let names: Vec<&str> = results.iter().map(|r| r.0.short_name.as_str()).collect();
+    let mut indexes = vec![None; names.len()];
+    let mut moof_count = 0;
+    for (i, name) in names.iter().enumerate() {
+        match *name {
+            "moof" => {
+                indexes[i] = Some(moof_count);
+                moof_count += 1;
+            },
+            "sidx" | "mfra" => {},
+            // Attached to the next `moof`, if there is one
+            name if FRAGMENT_PREFIX_BOXES.contains(&name) && names[i..].contains(&"moof") =>
+                indexes[i] = Some(moof_count),
+            name if FRAGMENT_PREFIX_BOXES.contains(&name) => {},
+            _ if moof_count > 0 => indexes[i] = Some(moof_count - 1),
+            _ => {},
+        }
+    }
+    indexes
+}
+
+/// Returns the indexes of the top-level boxes to extract, `sidx` boxes
+/// excepted.
+fn select_boxes(results: &[IsoBoxData], selection: &Selection) -> Vec<bool> {
+    let fragment_indexes = get_fragment_indexes(results);
+    let first_moof = results.iter()
+        .position(|r| r.0.short_name == "moof")
+        .unwrap_or(results.len());
+    let is_init = |i: usize| {
+        i < first_moof && !SEGMENT_BOXES.contains(&results[i].0.short_name.as_str())
+    };
+    let fragment_starts: Vec<Option<f64>> = results.iter()
+        .filter_map(|(info, entry)| {
+            if info.short_name != "moof" {
+                return None;
+            }
+            Some(entry.as_deref().and_then(|entry| get_fragment_start_time(info, entry)))
+        })
+        .collect();
+    (0..results.len()).map(|i| match selection {
+        Selection::Boxes(names) => results[i].0.short_name != "sidx" &&
+            names.contains(&results[i].0.short_name),
+        Selection::InitSegment => is_init(i),
+        Selection::Fragments { first, last } => is_init(i) ||
+            fragment_indexes[i].is_some_and(|index| {
+                index + 1 >= *first && last.is_none_or(|last| index < last)
+            }),
+        Selection::TimeRange { start, end } => is_init(i) ||
+            fragment_indexes[i]
+                .and_then(|index| fragment_starts[index])
+                .is_some_and(|time| *start <= time && end.is_none_or(|end| time < end)),
+    }).collect()
+}
+
+/// Returns `true` if the given byte range of the input file is entirely made
+/// of selected top-level boxes.
+fn is_range_selected(placements: &[BoxPlacement], start: u64, end: u64) -> bool {
+    let mut covered_until = start;
+    for placement in placements {
+        if placement.offset == covered_until && covered_until < end {
+            covered_until = placement.offset + placement.size;
+        }
+    }
+    covered_until >= end && start < end
+}
+
+/// Returns the offset in the output file of the given offset of the input
+/// file. `None` if it points to data which is not extracted.
+fn map_offset(placements: &[BoxPlacement], offset: u64) -> Option<u64> {
+    placements.iter()
+        .find(|p| p.offset <= offset && offset < p.offset + p.size)
+        .map(|p| p.new_offset + offset - p.offset)
+}
+
+/// Same as `map_offset` for the offset following the end of some data.
+fn map_end_offset(placements: &[BoxPlacement], end_offset: u64) -> Option<u64> {
+    end_offset.checked_sub(1).and_then(|last| map_offset(placements, last)).map(|o| o + 1)
+}
+
+/// Write to `output` the part of the input file described by `selection`,
+/// updating the offsets found in the extracted boxes so that they still point
+/// to the same data.
+/// Returns a description of each issue encountered.
+pub fn extract(
+    reader: &mut (impl Read + Seek),
+    results: &[IsoBoxData],
+    selection: &Selection,
+    output: &mut impl Write
+) -> io::Result<Vec<String>> {
+    let mut warnings = vec![];
+    let file_size = reader.seek(SeekFrom::End(0))?;
+    let selected = select_boxes(results, selection);
+    let mut placements: Vec<BoxPlacement> = results.iter()
+        .enumerate()
+        .filter(|(i, _)| selected[*i])
+        .map(|(index, (info, _))| {
+            // A size of 0 means that the box extends to the end of the file
+            let size = if info.size == 0 { file_size - info.offset } else { info.size };
+            BoxPlacement { index, offset: info.offset, size, new_offset: 0, new_size: size, kept_references: None }
+        })
+        .collect();
+
+    // A `sidx` box is kept with its references to extracted data only. The
+    // last ones are processed first, as they may be referenced by previous
+    // ones.
+    for (index, (info, entry)) in results.iter().enumerate().rev() {
+        let references = match entry.as_deref().and_then(get_sidx_references) {
+            Some(references) => references,
+            None => continue,
+        };
+        let kept: Vec<usize> = references.iter()
+            .enumerate()
+            .filter(|(_, r)| is_range_selected(&placements, r.start_offset, r.end_offset))
+            .map(|(i, _)| i)
+            .collect();
+        // When boxes are selected by name, a `sidx` is only kept if named,
+        // otherwise if it still has references
+        let is_kept = match selection {
+            Selection::Boxes(names) => names.contains(&info.short_name),
+            _ => !kept.is_empty(),
+        };
+        if !is_kept {
+            continue;
+        }
+        if kept.len() < references.len() {
+            warnings.push(format!(
+                "sidx at offset {}: {} of its {} references point to data which is not \
+                extracted and were removed",
+                info.offset, references.len() - kept.len(), references.len()));
+        }
+        let new_size = info.size - 12 * (references.len() - kept.len()) as u64;
+        let position = placements.iter().position(|p| p.index > index).unwrap_or(placements.len());
+        placements.insert(position, BoxPlacement {
+            index,
+            offset: info.offset,
+            size: info.size,
+            new_offset: 0,
+            new_size,
+            kept_references: Some(kept),
+        });
+    }
+
+    // A `ssix` box describes the subsegments of the `sidx` box preceding it,
+    // and is only kept if that `sidx` is kept whole
+    let complete_sidx_indexes: Vec<usize> = placements.iter()
+        .filter(|p| {
+            let reference_count = results[p.index].1.as_deref()
+                .and_then(get_sidx_references)
+                .map(|references| references.len());
+            reference_count.is_some() &&
+                p.kept_references.as_ref().map(|kept| kept.len()) == reference_count
+        })
+        .map(|p| p.index)
+        .collect();
+    placements.retain(|p| {
+        let info = &results[p.index].0;
+        if info.short_name != "ssix" ||
+            p.index.checked_sub(1).is_some_and(|i| complete_sidx_indexes.contains(&i))
+        {
+            return true;
+        }
+        warnings.push(format!(
+            "ssix at offset {}: removed as the sidx it describes is not extracted whole",
+            info.offset));
+        false
+    });
+
+    let mut new_offset = 0;
+    for placement in placements.iter_mut() {
+        placement.new_offset = new_offset;
+        new_offset += placement.new_size;
+    }
+    if placements.is_empty() {
+        warnings.push("no box to extract".to_owned());
+    }
+
+    // Bytes to overwrite in the input file's boxes, by offset in that file
+    let mut patches: Vec<(u64, Vec<u8>)> = vec![];
+    let mut unmapped_count = 0;
+    for field in find_offset_fields(results) {
+        let position = field.get_position();
+        if map_offset(&placements, position).is_none() {
+            continue;
+        }
+        match get_updated_field(&field, |offset| map_offset(&placements, offset)) {
+            Ok(bytes) => patches.push((position, bytes)),
+            Err(UpdateError::Unmapped) => unmapped_count += 1,
+            Err(UpdateError::Overflow(new_value)) => warnings.push(format!(
+                "the offset at {} cannot hold its new value {}",
+                position, new_value)),
+        }
+    }
+    if unmapped_count > 0 {
+        warnings.push(format!(
+            "{} offsets point to data which is not extracted and were left unchanged",
+            unmapped_count));
+    }
+
+    let last_index = placements.last().map(|p| p.index);
+    for placement in placements.iter() {
+        let (info, entry) = &results[placement.index];
+        let mut box_patches: Vec<(u64, &[u8])> = patches.iter()
+            .filter(|(position, _)| {
+                placement.offset <= *position && *position < placement.offset + placement.size
+            })
+            .map(|(position, bytes)| (*position, bytes.as_slice()))
+            .collect();
+        reader.seek(SeekFrom::Start(placement.offset))?;
+
+        if let (Some(kept), Some(entry)) = (&placement.kept_references, entry) {
+            // A `sidx` box has no offset field and is small enough to be
+            // rebuilt in memory
+            let mut data = vec![0; placement.size as usize];
+            reader.read_exact(&mut data)?;
+            let references = get_sidx_references(entry.as_ref()).unwrap_or_default();
+            data = rebuild_sidx(&data, info, &references, kept, |start, end| {
+                map_offset(&placements, start).zip(map_end_offset(&placements, end))
+            }, placement.new_offset + placement.new_size, &mut warnings);
+            output.write_all(&data)?;
+            continue;
+        }
+
+        let size_bytes = (placement.size as u32).to_be_bytes();
+        if info.size == 0 && Some(placement.index) != last_index {
+            // The box does not end the file anymore and needs an actual size
+            if placement.size > u32::MAX as u64 {
+                warnings.push(format!(
+                    "{} at offset {}: extends to the end of the file and cannot be \
+                    followed by other boxes",
+                    info.short_name, info.offset));
+            } else {
+                box_patches.push((placement.offset, &size_bytes));
+            }
+        }
+        // The box is copied as is, apart from the patched bytes
+        box_patches.sort_by_key(|(position, _)| *position);
+        let mut position = placement.offset;
+        for (patch_position, bytes) in box_patches {
+            io::copy(&mut reader.by_ref().take(patch_position - position), output)?;
+            output.write_all(bytes)?;
+            reader.seek(SeekFrom::Current(bytes.len() as i64))?;
+            position = patch_position + bytes.len() as u64;
+        }
+        io::copy(&mut reader.by_ref().take(placement.offset + placement.size - position), output)?;
+    }
+    Ok(warnings)
+}
+
+/// Build the content of a `sidx` box from the given one, only keeping the
+/// references with the given indexes.
+///
+/// `map_range` gives the new start and end offsets of a referenced byte
+/// range, and `anchor_point` is the new offset of the first byte following
+/// the `sidx`.
+/// Fields which cannot hold their new value are left unchanged, with a
+/// warning.
+fn rebuild_sidx(
+    data: &[u8],
+    info: &IsoBoxInfo,
+    references: &[ResolvedSidxReference],
+    kept: &[usize],
+    map_range: impl Fn(u64, u64) -> Option<(u64, u64)>,
+    anchor_point: u64,
+    warnings: &mut Vec<String>
+) -> Vec<u8> {
+    let header_size = info.header_size as usize;
+    let version = data[header_size];
+    // version, flags, reference_ID and timescale
+    let fields_start = header_size + 12;
+    let times_size = if version == 0 { 8 } else { 16 };
+    let entries_start = fields_start + times_size + 4;
+    let mut rebuilt = data[..fields_start].to_vec();
+
+    let first = kept.first().and_then(|i| references.get(*i));
+    let earliest_presentation_time = first.map_or(0, |r| r.start_time);
+    let first_offset = first
+        .and_then(|r| map_range(r.start_offset, r.end_offset))
+        .map_or(0, |(start, _)| start.saturating_sub(anchor_point));
+    let field_size = times_size as u64 / 2;
+    let time_end = fields_start + times_size / 2;
+    let time_field = get_sidx_field(
+        info, "earliest_presentation_time", field_size, earliest_presentation_time, warnings);
+    match time_field {
+        Some(bytes) => rebuilt.extend_from_slice(&bytes),
+        None => rebuilt.extend_from_slice(&data[fields_start..time_end]),
+    }
+    match get_sidx_field(info, "first_offset", field_size, first_offset, warnings) {
+        Some(bytes) => rebuilt.extend_from_slice(&bytes),
+        None => rebuilt.extend_from_slice(&data[time_end..fields_start + times_size]),
+    }
+    // reserved
+    rebuilt.extend_from_slice(&data[fields_start + times_size..fields_start + times_size + 2]);
+    rebuilt.extend_from_slice(&(kept.len() as u16).to_be_bytes());
+    for index in kept {
+        let entry_start = entries_start + index * 12;
+        let mut entry = data[entry_start..entry_start + 12].to_vec();
+        let new_field = references.get(*index).and_then(|r| {
+            let (start, end) = map_range(r.start_offset, r.end_offset)?;
+            get_sidx_referenced_size_field(info, *index, r.is_sidx_reference, end - start, warnings)
+        });
+        if let Some(new_field) = new_field {
+            entry[..4].copy_from_slice(&new_field);
+        }
+        rebuilt.extend_from_slice(&entry);
+    }
+
+    let size = rebuilt.len() as u64;
+    if header_size >= 16 && data[..4] == [0, 0, 0, 1] {
+        rebuilt[8..16].copy_from_slice(&size.to_be_bytes());
+    } else {
+        rebuilt[..4].copy_from_slice(&(size as u32).to_be_bytes());
+    }
+    rebuilt
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use super::{extract, Selection};
+    use super::super::super::boxes::{get_sidx_references, get_track_samples, parse_isobmff};
+
+    fn read_fixture(file_name: &str) -> Vec<u8> {
+        let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), file_name);
+        std::fs::read(&path).unwrap()
+    }
+
+    /// Returns the data of each sample of each track of the given file, only
+    /// keeping the samples found in the given byte range.
+    fn read_sample_data(file: &[u8], range: std::ops::Range<u64>) -> Vec<Vec<&[u8]>> {
+        let data = parse_isobmff(Cursor::new(file)).unwrap();
+        get_track_samples(&data).into_iter()
+            .map(|track| track.samples.iter()
+                .filter(|s| range.contains(&s.offset))
+                .map(|s| &file[s.offset as usize..(s.offset + s.size as u64) as usize])
+                .collect())
+            .collect()
+    }
+
+    /// start_offset, end_offset, start_time and end_time of a resolved `sidx`
+    /// reference.
+    type ReferenceSummary = (u64, u64, u64, u64);
+
+    /// Returns the name of each top-level box of the given file, and the
+    /// resolved references of its `sidx` boxes.
+    fn read_structure(file: &[u8]) -> (Vec<String>, Vec<Vec<ReferenceSummary>>) {
+        let data = parse_isobmff(Cursor::new(file)).unwrap();
+        let names = data.iter().map(|(info, _)| info.short_name.clone()).collect();
+        let references = data.iter()
+            .filter_map(|(_, entry)| entry.as_deref().and_then(get_sidx_references))
+            .map(|references| references.iter()
+                .map(|r| (r.start_offset, r.end_offset, r.start_time, r.end_time))
+                .collect())
+            .collect();
+        (names, references)
+    }
+
+    fn extract_fixture(file_name: &str, selection: Selection) -> (Vec<u8>, Vec<u8>, Vec<String>) {
+        let input = read_fixture(file_name);
+        let data = parse_isobmff(Cursor::new(&input)).unwrap();
+        let mut output = vec![];
+        let warnings = extract(&mut Cursor::new(&input), &data, &selection, &mut output).unwrap();
+        (input, output, warnings)
+    }
+
+    #[test]
+    fn extract_boxes() {
+        let (input, output, warnings) = extract_fixture(
+            "progressive.mp4",
+            Selection::Boxes(vec!["moov".to_owned(), "mdat".to_owned()]));
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        assert_eq!(read_structure(&output).0, vec!["moov", "mdat"]);
+        // Chunk offsets now point 16 bytes earlier, where the samples moved
+        assert_eq!(output.len(), input.len() - 16);
+        assert_eq!(read_sample_data(&output, 0..u64::MAX), read_sample_data(&input, 0..u64::MAX));
+    }
+
+    #[test]
+    fn extract_fragment() {
+        let (input, output, warnings) = extract_fixture(
+            "frags.mp4",
+            Selection::Fragments { first: 2, last: Some(2) });
+        assert_eq!(warnings, vec![
+            "sidx at offset 320: 2 of its 3 references point to data which is not extracted and were removed",
+        ]);
+        assert_eq!(read_structure(&output), (
+            vec!["ftyp", "moov", "sidx", "moof", "mdat"].into_iter().map(String::from).collect(),
+            vec![vec![(364, 484, 2000, 4000)]],
+        ));
+        // The second fragment of the input is made of its bytes 500 to 620
+        assert_eq!(read_sample_data(&output, 0..u64::MAX), read_sample_data(&input, 500..620));
+    }
+
+    #[test]
+    fn extract_time_range() {
+        let (input, output, _) = extract_fixture(
+            "frags.mp4",
+            Selection::TimeRange { start: 2.0, end: None });
+        assert_eq!(read_structure(&output), (
+            vec!["ftyp", "moov", "sidx", "moof", "mdat", "moof", "mdat"]
+                .into_iter().map(String::from).collect(),
+            vec![vec![(376, 496, 2000, 4000), (496, 608, 4000, 6000)]],
+        ));
+        assert_eq!(read_sample_data(&output, 0..u64::MAX), read_sample_data(&input, 500..732));
+    }
+}
@@ -0,0 +1,620 @@
+use std::convert::TryFrom;
+use std::io::{self, Read, Seek, Write};
+use super::extract::SEGMENT_BOXES;
+use super::super::boxes::{
+    BoxWriter,
+    Co64,
+    Ctts,
+    IsoBoxData,
+    Mdhd,
+    Mfhd,
+    Moof,
+    Mvex,
+    Mvhd,
+    SampleFlags,
+    SampleLocation,
+    Sidx,
+    SidxReference,
+    Stco,
+    Stsc,
+    Stss,
+    Stsz,
+    Stts,
+    Tfdt,
+    Tfhd,
+    Tkhd,
+    Traf,
+    Trex,
+    Trun,
+    TrunSample,
+    create_box_data,
+    get_track_samples,
+};
+
+/// Boxes of a `stbl` describing the timing, size and location of samples,
+/// which are built again when (de)fragmenting a file.
+const SAMPLE_TABLE_BOXES: [&str; 8] = ["stts", "ctts", "stss", "stsz", "stz2", "stsc", "stco", "co64"];
+
+/// Boxes of a `trak` leading to its sample tables.
+const STBL_PATH: [&str; 3] = ["mdia", "minf", "stbl"];
+
+/// Sample flags of a sync sample, which depends on no other sample.
+const SYNC_SAMPLE_FLAGS: u32 = 0x02000000;
+
+/// Sample flags of a non-sync sample, which depends on other samples.
+const NON_SYNC_SAMPLE_FLAGS: u32 = 0x01010000;
+
+/// Samples of a track, in decoding order.
+struct Track {
+    track_id: u32,
+
+    /// `None` for tracks only found in fragments, without a `trak` box.
+    timescale: Option<u32>,
+    is_protected: bool,
+    samples: Vec<SampleLocation>,
+}
+
+fn get_tracks(results: &[IsoBoxData]) -> Vec<Track> {
+    get_track_samples(results).into_iter()
+        .map(|track| Track {
+            track_id: track.track_id,
+            timescale: track.timescale,
+            is_protected: track.get_protection().is_some(),
+            samples: track.samples,
+        })
+        .collect()
+}
+
+/// Returns the first box parsed as `B` among the given ones.
+fn find_box<B: 'static>(boxes: &[IsoBoxData]) -> Option<&B> {
+    boxes.iter().find_map(|(_, entry)| entry.as_ref()?.as_any().downcast_ref::<B>())
+}
+
+fn find_box_mut<B: 'static>(boxes: &mut [IsoBoxData]) -> Option<&mut B> {
+    boxes.iter_mut().find_map(|(_, entry)| entry.as_mut()?.as_any_mut().downcast_mut::<B>())
+}
+
+/// Returns the boxes contained in the box reached by following the given
+/// box names from `boxes`, each time taking the first box with that name.
+fn get_children_mut<'a>(
+    mut boxes: &'a mut Vec<IsoBoxData>,
+    path: &[&str]
+) -> Option<&'a mut Vec<IsoBoxData>> {
+    for name in path {
+        boxes = boxes.iter_mut()
+            .find(|(info, _)| info.short_name == *name)?
+            .1.as_mut()?
+            .get_inner_boxes_mut()?;
+    }
+    Some(boxes)
+}
+
+fn get_moov_children(results: &mut [IsoBoxData]) -> io::Result<&mut Vec<IsoBoxData>> {
+    results.iter_mut()
+        .find(|(info, _)| info.short_name == "moov")
+        .and_then(|(_, entry)| entry.as_mut()?.get_inner_boxes_mut())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "no moov box was found"))
+}
+
+/// Returns the track ID and the contained boxes of each `trak` box among the
+/// children of a `moov` box.
+fn get_traks_mut(moov: &mut [IsoBoxData]) -> Vec<(u32, &mut Vec<IsoBoxData>)> {
+    moov.iter_mut()
+        .filter(|(info, _)| info.short_name == "trak")
+        .filter_map(|(_, entry)| {
+            let children = entry.as_mut()?.get_inner_boxes_mut()?;
+            let track_id = find_box::<Tkhd>(children)?.get_track_id();
+            Some((track_id, children))
+        })
+        .collect()
+}
+
+/// Replace the sample tables of a `stbl` box by `tables`, placed right after
+/// its `stsd` box. Its other boxes are kept only if `keep` returns `true` for
+/// their name.
+/// Returns the names of the boxes removed which were not sample tables.
+fn replace_sample_tables(
+    stbl: &mut Vec<IsoBoxData>,
+    tables: Vec<IsoBoxData>,
+    keep: impl Fn(&str) -> bool
+) -> Vec<String> {
+    let (mut content, others): (Vec<IsoBoxData>, Vec<IsoBoxData>) = std::mem::take(stbl)
+        .into_iter()
+        .partition(|(info, _)| info.short_name == "stsd");
+    content.extend(tables);
+    let mut removed = vec![];
+    for data in others {
+        let name = data.0.short_name.as_str();
+        if keep(name) {
+            content.push(data);
+        } else if !SAMPLE_TABLE_BOXES.contains(&name) {
+            removed.push(name.to_owned());
+        }
+    }
+    *stbl = content;
+    removed
+}
+
+/// Write the header of a `mdat` box whose content has the given size, which
+/// is then expected to be written.
+fn write_mdat_header(writer: &mut BoxWriter, content_size: u64) -> io::Result<()> {
+    if content_size + 8 > u32::MAX as u64 {
+        writer.write_u32(1)?;
+        writer.write_4cc("mdat")?;
+        writer.write_u64(content_size + 16)
+    } else {
+        writer.write_u32(content_size as u32 + 8)?;
+        writer.write_4cc("mdat")
+    }
+}
+
+fn get_mdat_header_size(content_size: u64) -> u64 {
+    if content_size + 8 > u32::MAX as u64 { 16 } else { 8 }
+}
+
+/// Add the given byte range of the input file to the ones to copy, merging it
+/// with the previous one when they are contiguous.
+fn add_copy(copies: &mut Vec<(u64, u64)>, offset: u64, size: u64) {
+    match copies.last_mut() {
+        Some((last_offset, last_size)) if *last_offset + *last_size == offset =>
+            *last_size += size,
+        _ => copies.push((offset, size)),
+    }
+}
+
+/// Replace the chunk offsets of each track's `stco` or `co64` box by the
+/// given ones, which are relative to `base_offset`.
+/// A `co64` box is used when `use_co64` is set.
+fn set_chunk_offsets(
+    moov: &mut [IsoBoxData],
+    chunk_offsets: &[(u32, Vec<u64>)],
+    base_offset: u64,
+    use_co64: bool
+) {
+    for (track_id, children) in get_traks_mut(moov) {
+        let offsets = match chunk_offsets.iter().find(|(id, _)| *id == track_id) {
+            Some((_, offsets)) => offsets.iter().map(|offset| base_offset + offset),
+            None => continue,
+        };
+        let stbl = match get_children_mut(children, &STBL_PATH) {
+            Some(stbl) => stbl,
+            None => continue,
+        };
+        let table = if use_co64 {
+            create_box_data(Co64::new(offsets.collect()))
+        } else {
+            create_box_data(Stco::new(offsets.map(|offset| offset as u32).collect()))
+        };
+        match stbl.iter().position(|(info, _)| info.short_name == "stco" || info.short_name == "co64") {
+            Some(pos) => stbl[pos] = table,
+            None => stbl.push(table),
+        }
+    }
+}
+
+/// Write to `output` a progressive version of a fragmented file: the samples
+/// of every fragment are described by the sample tables of their track and
+/// stored in a single `mdat` box.
+/// Returns a description of each issue encountered.
+pub fn defragment(
+    reader: &mut (impl Read + Seek),
+    mut results: Vec<IsoBoxData>,
+    output: &mut impl Write
+) -> io::Result<Vec<String>> {
+    let mut warnings = vec![];
+    let tracks = get_tracks(&results);
+    for track in tracks.iter().filter(|t| t.timescale.is_none()) {
+        warnings.push(format!(
+            "track {} has no trak box, its {} samples are not kept",
+            track.track_id, track.samples.len()));
+    }
+
+    // Boxes of track fragments other than those describing the samples are
+    // not carried over to the sample tables
+    let mut dropped_traf_boxes: Vec<String> = vec![];
+    for (_, entry) in results.iter().filter(|(info, _)| info.short_name == "moof") {
+        let moof_children = entry.as_ref().and_then(|e| e.get_inner_boxes_ref()).unwrap_or_default();
+        for (_, traf) in moof_children.into_iter().filter(|(info, _)| info.short_name == "traf") {
+            let traf_children = traf.and_then(|t| t.get_inner_boxes_ref()).unwrap_or_default();
+            for (info, _) in traf_children {
+                if !["tfhd", "tfdt", "trun"].contains(&info.short_name.as_str()) &&
+                    !dropped_traf_boxes.contains(&info.short_name)
+                {
+                    dropped_traf_boxes.push(info.short_name.clone());
+                }
+            }
+        }
+    }
+    for name in dropped_traf_boxes {
+        warnings.push(format!("the \"{}\" boxes of track fragments are not kept", name));
+    }
+
+    // Samples are stored in the order they are found in the input file, each
+    // track keeping its decoding order. Consecutive samples of a track
+    // described by the same sample entry form a chunk.
+    let mut chunks: Vec<Vec<(u32, u32)>> = vec![vec![]; tracks.len()];
+    let mut chunk_offsets: Vec<(u32, Vec<u64>)> = tracks.iter()
+        .map(|track| (track.track_id, vec![]))
+        .collect();
+    let mut copies = vec![];
+    let mut data_size = 0;
+    let mut next_samples = vec![0; tracks.len()];
+    let mut previous = None;
+    loop {
+        let next_track = (0..tracks.len())
+            .filter(|t| tracks[*t].timescale.is_some() && next_samples[*t] < tracks[*t].samples.len())
+            .min_by_key(|t| tracks[*t].samples[next_samples[*t]].offset);
+        let track_index = match next_track {
+            Some(track_index) => track_index,
+            None => break,
+        };
+        let sample = &tracks[track_index].samples[next_samples[track_index]];
+        next_samples[track_index] += 1;
+        let description_index = sample.sample_description_index;
+        if previous == Some((track_index, description_index)) {
+            chunks[track_index].last_mut().unwrap().0 += 1;
+        } else {
+            chunks[track_index].push((1, description_index));
+            chunk_offsets[track_index].1.push(data_size);
+        }
+        previous = Some((track_index, description_index));
+        add_copy(&mut copies, sample.offset, sample.size as u64);
+        data_size += sample.size as u64;
+    }
+
+    results.retain(|(info, _)| !SEGMENT_BOXES.contains(&info.short_name.as_str()));
+    let moov = get_moov_children(&mut results)?;
+    moov.retain(|(info, _)| info.short_name != "mvex");
+    let movie_timescale = find_box::<Mvhd>(moov).map(|mvhd| mvhd.get_timescale());
+    let mut movie_duration = 0;
+    for (track_id, children) in get_traks_mut(moov) {
+        let (track_index, track) = match tracks.iter().enumerate().find(|(_, t)| t.track_id == track_id) {
+            Some(track) => track,
+            None => continue,
+        };
+        let samples = &track.samples;
+        let durations: Vec<u32> = samples.iter().map(|s| s.duration).collect();
+        let composition_offsets: Vec<i64> = samples.iter().map(|s| s.composition_offset).collect();
+        let sync_samples: Vec<u32> = samples.iter()
+            .enumerate()
+            .filter(|(_, s)| s.is_sync)
+            .map(|(i, _)| i as u32 + 1)
+            .collect();
+        let mut tables = vec![create_box_data(Stts::new(&durations))];
+        if composition_offsets.iter().any(|offset| *offset != 0) {
+            tables.push(create_box_data(Ctts::new(&composition_offsets)));
+        }
+        if sync_samples.len() < samples.len() {
+            tables.push(create_box_data(Stss::new(sync_samples)));
+        }
+        tables.push(create_box_data(Stsc::new(&chunks[track_index])));
+        tables.push(create_box_data(Stsz::new(samples.iter().map(|s| s.size).collect())));
+        // Chunk offsets are set once the size of the boxes preceding the
+        // `mdat` is known
+        tables.push(create_box_data(Stco::new(vec![])));
+        // Auxiliary information is located through offsets which are no
+        // longer valid
+        let aux_info_boxes = ["saiz", "saio", "senc"];
+        if let Some(stbl) = get_children_mut(children, &STBL_PATH) {
+            let removed = replace_sample_tables(stbl, tables, |name| !aux_info_boxes.contains(&name));
+            for name in removed {
+                warnings.push(format!("the \"{}\" box of track {} is not kept", name, track_id));
+            }
+        } else if !samples.is_empty() {
+            warnings.push(format!(
+                "track {} has no stbl box, its {} samples are not described",
+                track_id, samples.len()));
+        }
+        if track.is_protected {
+            warnings.push(format!(
+                "track {} is protected, the encryption parameters of its samples are not kept",
+                track_id));
+        }
+
+        let media_duration: u64 = durations.iter().map(|d| *d as u64).sum();
+        let media_timescale = track.timescale.unwrap_or(1);
+        if let Some(mdhd) = get_children_mut(children, &["mdia"]).and_then(|c| find_box_mut::<Mdhd>(c)) {
+            mdhd.set_duration(media_duration);
+        }
+        if let (Some(tkhd), Some(movie_timescale)) = (find_box_mut::<Tkhd>(children), movie_timescale) {
+            let duration = (media_duration as u128 * movie_timescale as u128 /
+                media_timescale.max(1) as u128) as u64;
+            tkhd.set_duration(duration);
+            movie_duration = movie_duration.max(duration);
+        }
+    }
+    if let Some(mvhd) = find_box_mut::<Mvhd>(moov) {
+        mvhd.set_duration(movie_duration);
+    }
+
+    // The size of the boxes preceding the `mdat` only depends on the size of
+    // the chunk offsets, not on their values
+    let mdat_header_size = get_mdat_header_size(data_size);
+    let get_data_start = |results: &[IsoBoxData]| -> io::Result<u64> {
+        let mut size = mdat_header_size;
+        for (info, entry) in results {
+            size += BoxWriter::get_box_size(info, entry.as_deref())?;
+        }
+        Ok(size)
+    };
+    set_chunk_offsets(get_moov_children(&mut results)?, &chunk_offsets, 0, false);
+    let mut data_start = get_data_start(&results)?;
+    let use_co64 = data_start + data_size > u32::MAX as u64;
+    if use_co64 {
+        set_chunk_offsets(get_moov_children(&mut results)?, &chunk_offsets, 0, true);
+        data_start = get_data_start(&results)?;
+    }
+    set_chunk_offsets(get_moov_children(&mut results)?, &chunk_offsets, data_start, use_co64);
+
+    let mut writer = BoxWriter::create(output, Some(reader));
+    writer.write_boxes(&results)?;
+    write_mdat_header(&mut writer, data_size)?;
+    for (offset, size) in copies {
+        writer.copy_from_source(offset, Some(size))?;
+    }
+    Ok(warnings)
+}
+
+/// A `moof` box and the data of the `mdat` box following it.
+struct Fragment {
+    moof: IsoBoxData,
+    moof_size: u64,
+
+    /// Byte ranges of the input file making the content of the `mdat` box.
+    copies: Vec<(u64, u64)>,
+    data_size: u64,
+}
+
+/// Create a `moof` box describing the given samples of each track, whose
+/// `trun` box points to the corresponding data offset relative to the start
+/// of the `moof`.
+fn create_moof(
+    sequence_number: u32,
+    track_samples: &[(u32, &[SampleLocation])],
+    data_offsets: &[i32]
+) -> IsoBoxData {
+    let mut content = vec![create_box_data(Mfhd::new(sequence_number))];
+    for ((track_id, samples), data_offset) in track_samples.iter().zip(data_offsets) {
+        // the default sample description index is set to 1 in the `trex` box
+        let description_index = samples[0].sample_description_index;
+        let tfhd = Tfhd::new(*track_id, Some(description_index).filter(|index| *index != 1));
+        let trun_samples = samples.iter()
+            .map(|s| {
+                let flags = if s.is_sync { SYNC_SAMPLE_FLAGS } else { NON_SYNC_SAMPLE_FLAGS };
+                TrunSample::new(s.duration, s.size, SampleFlags::new(flags), s.composition_offset)
+            })
+            .collect();
+        content.push(create_box_data(Traf::new(vec![
+            create_box_data(tfhd),
+            create_box_data(Tfdt::new(samples[0].decode_time)),
+            create_box_data(Trun::new(*data_offset, trun_samples)),
+        ])));
+    }
+    create_box_data(Moof::new(content))
+}
+
+/// Write to `output` a fragmented version of a progressive file: its samples
+/// are moved to pairs of `moof` and `mdat` boxes, a new fragment starting at
+/// each sync sample of the first track which does not only have sync samples.
+/// A `sidx` box indexing those fragments is added if `with_sidx` is set.
+/// Returns a description of each issue encountered.
+pub fn fragment(
+    reader: &mut (impl Read + Seek),
+    mut results: Vec<IsoBoxData>,
+    with_sidx: bool,
+    output: &mut impl Write
+) -> io::Result<Vec<String>> {
+    if results.iter().any(|(info, _)| info.short_name == "moof") {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "the file is already fragmented"));
+    }
+    let mut warnings = vec![];
+    let tracks = get_tracks(&results);
+    let reference_index = tracks.iter()
+        .position(|t| t.samples.iter().any(|s| !s.is_sync))
+        .or_else(|| tracks.iter().position(|t| !t.samples.is_empty()))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "no sample was found"))?;
+    let reference = &tracks[reference_index];
+    let reference_timescale = reference.timescale.unwrap_or(1) as u128;
+    let mut fragment_starts: Vec<u64> = reference.samples.iter()
+        .enumerate()
+        .filter(|(i, s)| *i == 0 || s.is_sync)
+        .map(|(_, s)| s.decode_time)
+        .collect();
+    // Samples without a duration cannot start a fragment of their own
+    fragment_starts.dedup();
+
+    // For each track, the range of its samples in each fragment
+    let sample_ranges: Vec<Vec<std::ops::Range<usize>>> = tracks.iter()
+        .map(|track| {
+            let timescale = track.timescale.unwrap_or(1) as u128;
+            let mut ranges = vec![];
+            let mut first_sample = 0;
+            for next_start in fragment_starts.iter().skip(1) {
+                let end = first_sample + track.samples[first_sample..].iter()
+                    .take_while(|s| {
+                        s.decode_time as u128 * reference_timescale <
+                            *next_start as u128 * timescale
+                    })
+                    .count();
+                ranges.push(first_sample..end);
+                first_sample = end;
+            }
+            ranges.push(first_sample..track.samples.len());
+            ranges
+        })
+        .collect();
+
+    let mut fragments = vec![];
+    for fragment_index in 0..fragment_starts.len() {
+        let track_samples: Vec<(u32, &[SampleLocation])> = tracks.iter()
+            .zip(sample_ranges.iter())
+            .map(|(track, ranges)| (track.track_id, &track.samples[ranges[fragment_index].clone()]))
+            .filter(|(_, samples)| !samples.is_empty())
+            .collect();
+        let mut copies = vec![];
+        let mut track_data_offsets = vec![];
+        let mut data_size = 0;
+        for (track_id, samples) in track_samples.iter() {
+            let description_index = samples[0].sample_description_index;
+            if samples.iter().any(|s| s.sample_description_index != description_index) {
+                warnings.push(format!(
+                    "fragment {} mixes sample entries of track {}, only the first one is kept",
+                    fragment_index + 1, track_id));
+            }
+            track_data_offsets.push(data_size);
+            for sample in samples.iter() {
+                add_copy(&mut copies, sample.offset, sample.size as u64);
+                data_size += sample.size as u64;
+            }
+        }
+
+        // The size of a `moof` does not depend on the value of its data
+        // offsets
+        let sequence_number = fragment_index as u32 + 1;
+        let (info, entry) = create_moof(sequence_number, &track_samples, &vec![0; track_samples.len()]);
+        let moof_size = BoxWriter::get_box_size(&info, entry.as_deref())?;
+        let data_start = moof_size + get_mdat_header_size(data_size);
+        let data_offsets = track_data_offsets.into_iter()
+            .map(|offset| i32::try_from(data_start + offset).map_err(|_| io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("fragment {} is too large", fragment_index + 1))))
+            .collect::<io::Result<Vec<i32>>>()?;
+        fragments.push(Fragment {
+            moof: create_moof(sequence_number, &track_samples, &data_offsets),
+            moof_size,
+            copies,
+            data_size,
+        });
+    }
+
+    let sidx = if with_sidx {
+        let mut references = vec![];
+        for (fragment, ranges) in fragments.iter().zip(sample_ranges[reference_index].iter()) {
+            let samples = &reference.samples[ranges.clone()];
+            let size = fragment.moof_size + get_mdat_header_size(fragment.data_size) + fragment.data_size;
+            let duration: u64 = samples.iter().map(|s| s.duration as u64).sum();
+            let (referenced_size, subsegment_duration) =
+                match (u32::try_from(size), u32::try_from(duration)) {
+                    (Ok(size), Ok(duration)) => (size, duration),
+                    _ => return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "a fragment is too large to be indexed by a sidx box")),
+                };
+            let starts_with_sap = samples[0].is_sync;
+            references.push(SidxReference::new(
+                referenced_size,
+                subsegment_duration,
+                starts_with_sap,
+                if starts_with_sap { 1 } else { 0 }));
+        }
+        let earliest_presentation_time = reference.samples[sample_ranges[reference_index][0].clone()]
+            .iter()
+            .map(|s| (s.decode_time as i64 + s.composition_offset).max(0) as u64)
+            .min()
+            .unwrap_or(0);
+        Some(create_box_data(Sidx::new(
+            reference.track_id,
+            reference_timescale as u32,
+            earliest_presentation_time,
+            references)))
+    } else {
+        None
+    };
+
+    // Sample tables are left empty, every sample being in a fragment
+    results.retain(|(info, _)| info.short_name != "mdat");
+    let moov = get_moov_children(&mut results)?;
+    let mut trexs = vec![];
+    for (track_id, children) in get_traks_mut(moov) {
+        let tables = vec![
+            create_box_data(Stts::new(&[])),
+            create_box_data(Stsc::new(&[])),
+            create_box_data(Stsz::new(vec![])),
+            create_box_data(Stco::new(vec![])),
+        ];
+        if let Some(stbl) = get_children_mut(children, &STBL_PATH) {
+            let removed = replace_sample_tables(stbl, tables, |name| name == "sgpd");
+            for name in removed {
+                warnings.push(format!("the \"{}\" box of track {} is not kept", name, track_id));
+            }
+        }
+        if tracks.iter().any(|t| t.track_id == track_id && t.is_protected) {
+            warnings.push(format!(
+                "track {} is protected, the encryption parameters of its samples are not kept",
+                track_id));
+        }
+        trexs.push(create_box_data(Trex::new(track_id)));
+    }
+    moov.retain(|(info, _)| info.short_name != "mvex");
+    let mvex_pos = moov.iter()
+        .rposition(|(info, _)| info.short_name == "trak")
+        .map_or(moov.len(), |pos| pos + 1);
+    moov.insert(mvex_pos, create_box_data(Mvex::new(trexs)));
+
+    let mut writer = BoxWriter::create(output, Some(reader));
+    writer.write_boxes(&results)?;
+    if let Some((info, entry)) = sidx.as_ref() {
+        writer.write_box(info, entry.as_deref())?;
+    }
+    for fragment in fragments {
+        writer.write_box(&fragment.moof.0, fragment.moof.1.as_deref())?;
+        write_mdat_header(&mut writer, fragment.data_size)?;
+        for (offset, size) in fragment.copies {
+            writer.copy_from_source(offset, Some(size))?;
+        }
+    }
+    Ok(warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use super::{defragment, fragment};
+    use super::super::super::boxes::{get_track_samples, parse_isobmff};
+
+    /// Data, duration, composition offset and sync status of a sample.
+    type SampleSummary<'a> = (&'a [u8], u32, i64, bool);
+
+    /// Returns a summary of each sample of each track of the given file.
+    fn read_samples(file: &[u8]) -> Vec<Vec<SampleSummary<'_>>> {
+        let data = parse_isobmff(Cursor::new(file)).unwrap();
+        get_track_samples(&data).into_iter()
+            .map(|track| track.samples.iter()
+                .map(|s| {
+                    let start = s.offset as usize;
+                    (&file[start..start + s.size as usize], s.duration, s.composition_offset, s.is_sync)
+                })
+                .collect())
+            .collect()
+    }
+
+    /// Fragment then defragment the given fixture, checking that the samples
+    /// are unchanged and that the expected number of fragments is created.
+    fn check_fragment_then_defragment(file_name: &str, expected_moof_count: usize) {
+        let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), file_name);
+        let input = std::fs::read(&path).unwrap();
+
+        let mut fragmented = vec![];
+        let data = parse_isobmff(Cursor::new(&input)).unwrap();
+        let warnings = fragment(&mut Cursor::new(&input), data, true, &mut fragmented).unwrap();
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        let data = parse_isobmff(Cursor::new(&fragmented)).unwrap();
+        let moof_count = data.iter().filter(|(info, _)| info.short_name == "moof").count();
+        assert_eq!(moof_count, expected_moof_count, "{}", file_name);
+        assert_eq!(read_samples(&fragmented), read_samples(&input));
+
+        let mut defragmented = vec![];
+        let warnings = defragment(&mut Cursor::new(&fragmented), data, &mut defragmented).unwrap();
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        assert_eq!(read_samples(&defragmented), read_samples(&input));
+    }
+
+    #[test]
+    fn fragment_then_defragment() {
+        // Video with sync samples and composition offsets, and audio
+        check_fragment_then_defragment("progressive.mp4", 3);
+        // Video without `stss`, thus cut at each sample, with negative
+        // composition offsets and 64-bit chunk offsets
+        check_fragment_then_defragment("progressive_all_sync.mp4", 6);
+    }
+}
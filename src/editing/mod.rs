@@ -0,0 +1,8 @@
+mod edit;
+mod extract;
+mod fragmentation;
+mod offsets;
+
+pub use edit::{edit, BoxPath, EditOperation};
+pub use extract::{extract, Selection};
+pub use fragmentation::{defragment, fragment};
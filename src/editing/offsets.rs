@@ -0,0 +1,95 @@
+use std::convert::TryFrom;
+use super::super::boxes::{IsoBoxInfo, OffsetField};
+
+/// Why an offset field could not be updated.
+pub enum UpdateError {
+    /// The data it points to does not have a new offset.
+    Unmapped,
+
+    /// The new value does not fit in the field.
+    Overflow(i128),
+}
+
+/// Returns the bytes an offset field has to be overwritten with once the
+/// data of the file moved, `map_offset` giving the new offset of any offset
+/// of the original file.
+pub fn get_updated_field(
+    field: &OffsetField,
+    map_offset: impl Fn(u64) -> Option<u64>
+) -> Result<Vec<u8>, UpdateError> {
+    let (size, new_value) = match *field {
+        OffsetField::Absolute { size, value, .. } => {
+            let new_value = map_offset(value).ok_or(UpdateError::Unmapped)?;
+            (size, new_value as i128)
+        },
+        OffsetField::Relative { size, base_offset, value, .. } => {
+            // A target before the start of the file or past the largest
+            // offset does not point to any data
+            let target = i64::try_from(base_offset).ok()
+                .and_then(|base_offset| base_offset.checked_add(value))
+                .and_then(|target| u64::try_from(target).ok())
+                .ok_or(UpdateError::Unmapped)?;
+            match (map_offset(base_offset), map_offset(target)) {
+                (Some(new_base), Some(new_target)) =>
+                    (size, new_target as i128 - new_base as i128),
+                _ => return Err(UpdateError::Unmapped),
+            }
+        },
+    };
+    // Relative offsets are signed in `trun` boxes but not in `saio` ones,
+    // both ranges are accepted
+    let bytes = if size == 4 {
+        if new_value < i32::MIN as i128 || new_value > u32::MAX as i128 {
+            return Err(UpdateError::Overflow(new_value));
+        }
+        (new_value as u32).to_be_bytes().to_vec()
+    } else {
+        if new_value < i64::MIN as i128 || new_value > u64::MAX as i128 {
+            return Err(UpdateError::Overflow(new_value));
+        }
+        (new_value as u64).to_be_bytes().to_vec()
+    };
+    Ok(bytes)
+}
+
+/// Returns the bytes of a `sidx` field of `size` bytes, either 4 or 8, which
+/// now holds `value`.
+/// `None`, with a warning, if the value does not fit in the field.
+pub fn get_sidx_field(
+    info: &IsoBoxInfo,
+    name: &str,
+    size: u64,
+    value: u64,
+    warnings: &mut Vec<String>
+) -> Option<Vec<u8>> {
+    if size == 8 {
+        return Some(value.to_be_bytes().to_vec());
+    }
+    if value > u32::MAX as u64 {
+        warnings.push(format!(
+            "sidx at offset {}: {} cannot hold its new value {}",
+            info.offset, name, value));
+        return None;
+    }
+    Some((value as u32).to_be_bytes().to_vec())
+}
+
+/// Returns the first 4 bytes of the reference of a `sidx` box with the given
+/// 0-based index: its reference type followed by its new referenced size.
+/// `None`, with a warning, if the size does not fit in its 31 bits.
+pub fn get_sidx_referenced_size_field(
+    info: &IsoBoxInfo,
+    index: usize,
+    is_sidx_reference: bool,
+    referenced_size: u64,
+    warnings: &mut Vec<String>
+) -> Option<[u8; 4]> {
+    if referenced_size > 0x7FFF_FFFF {
+        warnings.push(format!(
+            "sidx at offset {}: referenced_size of reference {} cannot hold its new value {}",
+            info.offset, index + 1, referenced_size));
+        return None;
+    }
+    let reference_type = if is_sidx_reference { 1 << 31 } else { 0 };
+    Some((reference_type | referenced_size as u32).to_be_bytes())
+}
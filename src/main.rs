@@ -1,20 +1,40 @@
 extern crate clap;
 
 mod boxes;
+mod editing;
+mod payloads;
 mod rendering;
 
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::Path;
 
-use clap::{Arg, App};
-use boxes::parse_isobmff;
-use rendering::{DisplayOptions, render_result};
+use clap::{App, AppSettings, Arg, ArgGroup, ArgMatches, SubCommand};
+use boxes::{
+    IsoBoxData,
+    check_auxiliary_information,
+    check_isobmff,
+    get_track_samples,
+    has_auxiliary_information,
+    parse_isobmff,
+};
+use payloads::{
+    ContentKeys,
+    PayloadKind,
+    SampleDecoder,
+    SampleDecrypter,
+    parse_content_key,
+    read_sample,
+};
+use editing::{BoxPath, EditOperation, Selection, defragment, edit, extract, fragment};
+use rendering::{DisplayOptions, render_result, render_sample};
 
 fn main() {
     let matches = App::new("ISOBMFF-inspector")
         .version("0.1")
         .author("Paul Berberian <pea.berberian@gmail.com>")
         .about("Display metadata contained in an ISOBMFF file.")
+        .setting(AppSettings::SubcommandsNegateReqs)
         .arg(Arg::with_name("INPUT")
             .help("Sets the input file to use")
             .required(true)
@@ -51,8 +71,214 @@ fn main() {
                 "Values corresponding to a collection of multiple entries are \
                 hidden by default.\nThis option allows to display them."
             ))
+        .arg(Arg::with_name("decode-subtitles")
+            .short("t")
+            .long("decode-subtitles")
+            .help(
+                "Read the samples of the subtitle and timed text tracks (\"stpp\", \
+                \"wvtt\" and \"tx3g\") from the file and display their \
+                decoded content after the boxes."
+            ))
+        .arg(Arg::with_name("nal-units")
+            .short("n")
+            .long("nal-units")
+            .help(
+                "Read the samples of the AVC, HEVC and VVC tracks from the file \
+                and display the NAL units they contain after the boxes, \
+                indicating which samples contain random access slices, \
+                parameter sets, SEI messages or access unit delimiters.\n\
+                SEI messages are decoded, including CEA-608/708 captions, HDR \
+                metadata and time codes."
+            ))
+        .arg(Arg::with_name("obus")
+            .short("o")
+            .long("obus")
+            .help(
+                "Read the samples of the AV1 tracks from the file and display \
+                the OBUs they contain after the boxes, indicating which samples \
+                contain key frames or sequence headers."
+            ))
+        .arg(Arg::with_name("audio-frames")
+            .short("f")
+            .long("audio-frames")
+            .help(
+                "Read the samples of the AAC, AC-3, E-AC-3 and Opus tracks from \
+                the file and display their frame headers after the boxes, \
+                reporting where they disagree with the sample entry."
+            ))
+        .arg(Arg::with_name("key")
+            .short("k")
+            .long("key")
+            .value_name("KID:KEY")
+            .help(
+                "Content key with which the samples of the protected tracks \
+                (\"cenc\", \"cens\", \"cbc1\" and \"cbcs\" schemes) are \
+                decrypted in memory before being decoded by the \"-t\", \"-n\", \
+                \"-o\" and \"-f\" options.\n\
+                Both the key ID and the key are written in hexadecimal. This \
+                option can be repeated to give multiple keys."
+            )
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1))
+        .subcommand(SubCommand::with_name("extract")
+            .about(
+                "Write a new ISOBMFF file made of a part of the input file, \
+                updating the offsets of the extracted boxes so that they still \
+                point to the same data.")
+            .arg(Arg::with_name("INPUT")
+                .help("Sets the input file to use")
+                .required(true)
+                .index(1))
+            .arg(Arg::with_name("OUTPUT")
+                .help("Sets the file to write")
+                .required(true)
+                .index(2))
+            .arg(Arg::with_name("boxes")
+                .short("b")
+                .long("boxes")
+                .value_name("BOX_NAME1,BOX_NAME2,...")
+                .help(
+                    "Only extract the top-level boxes with the given four letters \
+                    short names, e.g. \"ftyp,moov\".")
+                .takes_value(true))
+            .arg(Arg::with_name("init")
+                .short("i")
+                .long("init")
+                .help(
+                    "Only extract the initialization segment: the top-level boxes \
+                    preceding the first \"moof\" box, media data excluded."))
+            .arg(Arg::with_name("fragments")
+                .short("f")
+                .long("fragments")
+                .value_name("FIRST[-LAST]")
+                .help(
+                    "Extract the initialization segment followed by the fragments \
+                    whose 1-based indexes are in the given range, e.g. \"3\", \
+                    \"3-5\" or \"3-\" to go until the last one.")
+                .takes_value(true))
+            .arg(Arg::with_name("time")
+                .long("time")
+                .value_name("START[-END]")
+                .help(
+                    "Extract the initialization segment followed by the fragments \
+                    whose decode time starts in the given range, in seconds, e.g. \
+                    \"10-20\" or \"10-\" to go until the last one.")
+                .takes_value(true))
+            .group(ArgGroup::with_name("selection")
+                .args(&["boxes", "init", "fragments", "time"])
+                .required(true)))
+        .subcommand(SubCommand::with_name("edit")
+            .about(
+                "Write a new ISOBMFF file with some boxes removed, replaced or \
+                inserted, updating the sizes and offsets depending on them. \
+                Boxes are designated by a path such as \"moov/trak[2]/mdia/hdlr\", \
+                in which \"*\" designates any box, \"**\" any number of levels \
+                of nesting and the optional bracketed index is 1-based. \
+                Operations are performed in the order they are given.")
+            .arg(Arg::with_name("INPUT")
+                .help("Sets the input file to use")
+                .required(true)
+                .index(1))
+            .arg(Arg::with_name("OUTPUT")
+                .help("Sets the file to write")
+                .required(true)
+                .index(2))
+            .arg(Arg::with_name("remove")
+                .short("r")
+                .long("remove")
+                .value_name("PATH")
+                .help("Remove the designated boxes, e.g. \"**/free\" or \"moov/udta\".")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1))
+            .arg(Arg::with_name("replace")
+                .long("replace")
+                .value_name("PATH=FILE")
+                .help(
+                    "Replace the designated boxes by the boxes contained in the \
+                    given file, e.g. \"moov/pssh[1]=pssh.bin\".")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1))
+            .arg(Arg::with_name("insert-before")
+                .long("insert-before")
+                .value_name("PATH=FILE")
+                .help(
+                    "Insert the boxes contained in the given file before the \
+                    designated boxes, e.g. \"moof=emsg.bin\".")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1))
+            .arg(Arg::with_name("insert-after")
+                .long("insert-after")
+                .value_name("PATH=FILE")
+                .help("Insert the boxes contained in the given file after the designated boxes.")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1))
+            .arg(Arg::with_name("insert-into")
+                .long("insert-into")
+                .value_name("PATH=FILE")
+                .help(
+                    "Insert the boxes contained in the given file at the end of \
+                    the designated container boxes, e.g. \"moov=pssh.bin\".")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1))
+            .group(ArgGroup::with_name("operations")
+                .args(&["remove", "replace", "insert-before", "insert-after", "insert-into"])
+                .multiple(true)
+                .required(true)))
+        .subcommand(SubCommand::with_name("defragment")
+            .about(
+                "Write a progressive version of a fragmented ISOBMFF file, whose \
+                samples are described by the sample tables of the \"moov\" box \
+                and stored in a single \"mdat\" box.")
+            .arg(Arg::with_name("INPUT")
+                .help("Sets the input file to use")
+                .required(true)
+                .index(1))
+            .arg(Arg::with_name("OUTPUT")
+                .help("Sets the file to write")
+                .required(true)
+                .index(2)))
+        .subcommand(SubCommand::with_name("fragment")
+            .about(
+                "Write a fragmented version of a progressive ISOBMFF file, made \
+                of \"moof\" and \"mdat\" boxes. A new fragment starts at each \
+                sync sample of the video track, or of the first track if there \
+                is none.")
+            .arg(Arg::with_name("INPUT")
+                .help("Sets the input file to use")
+                .required(true)
+                .index(1))
+            .arg(Arg::with_name("OUTPUT")
+                .help("Sets the file to write")
+                .required(true)
+                .index(2))
+            .arg(Arg::with_name("sidx")
+                .long("sidx")
+                .help("Add a \"sidx\" box indexing the fragments.")))
         .get_matches();
 
+    if let Some(extract_matches) = matches.subcommand_matches("extract") {
+        run_extract(extract_matches);
+        return;
+    }
+    if let Some(edit_matches) = matches.subcommand_matches("edit") {
+        run_edit(edit_matches);
+        return;
+    }
+    if let Some(defragment_matches) = matches.subcommand_matches("defragment") {
+        run_fragmentation(defragment_matches, false);
+        return;
+    }
+    if let Some(fragment_matches) = matches.subcommand_matches("fragment") {
+        run_fragmentation(fragment_matches, true);
+        return;
+    }
+
     let file_name = matches.value_of("INPUT").unwrap();
     let only_boxes = if let Some(box_filter) = matches.value_of("only-boxes") {
         Some(box_filter
@@ -64,6 +290,31 @@ fn main() {
     };
     let only_size = matches.is_present("only-size");
     let show_all = matches.is_present("show-all");
+    let mut payload_kinds = vec![];
+    if matches.is_present("decode-subtitles") {
+        payload_kinds.push(PayloadKind::Subtitles);
+    }
+    if matches.is_present("nal-units") {
+        payload_kinds.push(PayloadKind::NalUnits);
+    }
+    if matches.is_present("obus") {
+        payload_kinds.push(PayloadKind::Obus);
+    }
+    if matches.is_present("audio-frames") {
+        payload_kinds.push(PayloadKind::AudioFrames);
+    }
+    let mut keys = ContentKeys::new();
+    for arg in matches.values_of("key").into_iter().flatten() {
+        match parse_content_key(arg) {
+            Some((kid, key)) => {
+                keys.insert(kid, key);
+            },
+            None => {
+                eprintln!("Error: invalid key \"{}\", expected KID:KEY in hexadecimal", arg);
+                std::process::exit(1);
+            },
+        }
+    }
 
     let f = File::open(&file_name).unwrap_or_else(|err| {
         eprintln!("Error: Error while opening \"{}\": {}", &file_name, err);
@@ -77,11 +328,300 @@ fn main() {
             eprintln!("Error: something went wrong when parsing the file: {}", e);
         },
         Ok(data) => {
-            render_result(data, DisplayOptions {
+            let mut warnings = check_isobmff(&data);
+            let tracks = get_track_samples(&data);
+            warnings.extend(tracks.iter().flat_map(|t| t.warnings.iter().cloned()));
+            if has_auxiliary_information(&data) {
+                if let Ok(mut file) = File::open(file_name) {
+                    warnings.extend(check_auxiliary_information(&mut file, &tracks));
+                }
+            }
+            let opts = DisplayOptions {
                 hide_collections: !show_all,
                 filter_boxes: only_boxes,
                 display_only_size: only_size,
-            });
+            };
+            render_result(&data, &opts);
+            if !payload_kinds.is_empty() && !only_size {
+                render_samples(file_name, &data, &payload_kinds, &keys, &opts);
+            }
+            for warning in warnings.iter() {
+                eprintln!("Warning: {}", warning);
+            }
+        }
+    }
+}
+
+/// Read from the file the samples of each track whose content is of one of
+/// the wanted kinds, and display their decoded content.
+/// Samples of protected tracks are first decrypted if their key is known.
+fn render_samples(
+    file_name: &str,
+    data: &[IsoBoxData],
+    payload_kinds: &[PayloadKind],
+    keys: &ContentKeys,
+    opts: &DisplayOptions
+) {
+    let mut file = File::open(file_name).unwrap_or_else(|err| {
+        eprintln!("Error: Error while opening \"{}\": {}", file_name, err);
+        std::process::exit(1);
+    });
+    for track in get_track_samples(data) {
+        let mut decoder = match payload_kinds
+            .iter()
+            .find_map(|kind| SampleDecoder::for_track(*kind, &track))
+        {
+            Some(decoder) => decoder,
+            None => continue,
+        };
+        let decrypter = if keys.is_empty() {
+            None
+        } else {
+            SampleDecrypter::for_track(&track, keys).unwrap_or_else(|e| {
+                eprintln!("Error: cannot decrypt track {}: {}", track.track_id, e);
+                None
+            })
+        };
+        let codec = track.codec.as_deref().unwrap_or_default();
+        for sample in track.samples.iter() {
+            let title = format!("track {} sample {} ({}, offset: {}, size: {}{})",
+                track.track_id, sample.sample_number, codec, sample.offset, sample.size,
+                if decrypter.is_some() { ", decrypted" } else { "" });
+            println!();
+            match read_sample(&mut file, sample) {
+                Ok(mut sample_data) => {
+                    if let Some(decrypter) = &decrypter {
+                        let decrypted = decrypter.get_sample_encryption(&mut file, sample)
+                            .and_then(|encryption| {
+                                decrypter.decrypt(encryption.as_ref(), &mut sample_data)
+                            });
+                        if let Err(e) = decrypted {
+                            eprintln!("Error: could not decrypt {}: {}", title, e);
+                            continue;
+                        }
+                    }
+                    if let Some(decoded) = decoder.decode(&sample_data) {
+                        render_sample(&title, &decoded.get_values(), opts);
+                    }
+                },
+                Err(e) => {
+                    eprintln!("Error: could not read {}: {}", title, e);
+                },
+            }
+        }
+    }
+}
+
+/// Parse a "FIRST[-LAST]" range, in which LAST defaults to FIRST if the dash
+/// is omitted and is `None` if nothing follows the dash.
+fn parse_range<T: std::str::FromStr + Copy>(arg: &str) -> Option<(T, Option<T>)> {
+    match arg.split_once('-') {
+        None => {
+            let value = arg.trim().parse().ok()?;
+            Some((value, Some(value)))
+        },
+        Some((first, "")) => Some((first.trim().parse().ok()?, None)),
+        Some((first, last)) => Some((first.trim().parse().ok()?, Some(last.trim().parse().ok()?))),
+    }
+}
+
+/// Parse the input file of a subcommand writing a new file, then let `write`
+/// write that new file from the parsed boxes and a reader of the input file.
+///
+/// The new file is first written next to the output file then renamed, so
+/// the input file is only replaced once it has been fully read when both are
+/// the same.
+/// Warnings are displayed and the process exits on error.
+fn write_new_file(
+    input_name: &str,
+    output_name: &str,
+    write: impl FnOnce(
+        Vec<IsoBoxData>,
+        &mut BufReader<File>,
+        &mut BufWriter<File>
+    ) -> io::Result<Vec<String>>
+) {
+    let exit_with_error = |message: String| -> ! {
+        eprintln!("Error: {}", message);
+        std::process::exit(1);
+    };
+    let open_input = || File::open(input_name).unwrap_or_else(|err| {
+        exit_with_error(format!("could not open \"{}\": {}", input_name, err))
+    });
+    let data = parse_isobmff(BufReader::new(open_input())).unwrap_or_else(|e| {
+        exit_with_error(format!("something went wrong when parsing the file: {}", e))
+    });
+
+    let output_path = Path::new(output_name);
+    let temporary_name = format!(
+        ".{}.{}.tmp",
+        output_path.file_name().map_or("output".into(), |n| n.to_string_lossy()),
+        std::process::id());
+    let temporary_path = output_path.with_file_name(temporary_name);
+    let output = File::create(&temporary_path).unwrap_or_else(|err| {
+        exit_with_error(format!("could not create \"{}\": {}", output_name, err))
+    });
+    let mut reader = BufReader::new(open_input());
+    let mut writer = BufWriter::new(output);
+    let result = write(data, &mut reader, &mut writer)
+        .and_then(|warnings| writer.flush().map(|_| warnings));
+    drop(writer);
+    drop(reader);
+    let result = result.and_then(|warnings| {
+        std::fs::rename(&temporary_path, output_path).map(|_| warnings)
+    });
+    match result {
+        Ok(warnings) => {
+            for warning in warnings.iter() {
+                eprintln!("Warning: {}", warning);
+            }
+        },
+        Err(e) => {
+            let _ = std::fs::remove_file(&temporary_path);
+            exit_with_error(format!("could not write \"{}\": {}", output_name, e))
+        },
+    }
+}
+
+/// Run the "extract" subcommand.
+fn run_extract(matches: &ArgMatches) {
+    let input_name = matches.value_of("INPUT").unwrap();
+    let output_name = matches.value_of("OUTPUT").unwrap();
+    let exit_with_error = |message: String| -> ! {
+        eprintln!("Error: {}", message);
+        std::process::exit(1);
+    };
+    let selection = if let Some(box_filter) = matches.value_of("boxes") {
+        Selection::Boxes(box_filter.split(',').map(|s| s.to_string()).collect())
+    } else if matches.is_present("init") {
+        Selection::InitSegment
+    } else if let Some(arg) = matches.value_of("fragments") {
+        match parse_range::<usize>(arg) {
+            Some((first, last)) if first > 0 => Selection::Fragments { first, last },
+            _ => exit_with_error(format!("invalid fragment range \"{}\"", arg)),
+        }
+    } else {
+        let arg = matches.value_of("time").unwrap();
+        match parse_range::<f64>(arg) {
+            Some((start, end)) => Selection::TimeRange { start, end },
+            None => exit_with_error(format!("invalid time range \"{}\"", arg)),
+        }
+    };
+
+    write_new_file(input_name, output_name, |data, reader, writer| {
+        extract(reader, &data, &selection, writer)
+    });
+}
+
+/// Run the "edit" subcommand.
+fn run_edit(matches: &ArgMatches) {
+    let input_name = matches.value_of("INPUT").unwrap();
+    let output_name = matches.value_of("OUTPUT").unwrap();
+    let exit_with_error = |message: String| -> ! {
+        eprintln!("Error: {}", message);
+        std::process::exit(1);
+    };
+    let parse_path = |path: &str| -> BoxPath {
+        path.parse().unwrap_or_else(|err| exit_with_error(format!("invalid path: {}", err)))
+    };
+    let parse_insertion = |arg: &str| -> (BoxPath, Vec<u8>) {
+        let (path, file_name) = arg.rsplit_once('=').unwrap_or_else(|| {
+            exit_with_error(format!("expected PATH=FILE, got \"{}\"", arg))
+        });
+        let data = std::fs::read(file_name).unwrap_or_else(|err| {
+            exit_with_error(format!("could not read \"{}\": {}", file_name, err))
+        });
+        (parse_path(path), data)
+    };
+
+    // Operations are performed in the order of their arguments
+    let mut operations: Vec<(usize, EditOperation)> = vec![];
+    for name in ["remove", "replace", "insert-before", "insert-after", "insert-into"] {
+        let (values, indices) = match (matches.values_of(name), matches.indices_of(name)) {
+            (Some(values), Some(indices)) => (values, indices),
+            _ => continue,
+        };
+        for (arg, index) in values.zip(indices) {
+            let operation = match name {
+                "remove" => EditOperation::Remove(parse_path(arg)),
+                "replace" => {
+                    let (path, data) = parse_insertion(arg);
+                    EditOperation::Replace(path, data)
+                },
+                "insert-before" => {
+                    let (path, data) = parse_insertion(arg);
+                    EditOperation::InsertBefore(path, data)
+                },
+                "insert-after" => {
+                    let (path, data) = parse_insertion(arg);
+                    EditOperation::InsertAfter(path, data)
+                },
+                _ => {
+                    let (path, data) = parse_insertion(arg);
+                    EditOperation::InsertInto(path, data)
+                },
+            };
+            operations.push((index, operation));
         }
     }
+    operations.sort_by_key(|(index, _)| *index);
+    let operations: Vec<EditOperation> = operations.into_iter().map(|(_, op)| op).collect();
+
+    write_new_file(input_name, output_name, |data, reader, writer| {
+        edit(reader, data, &operations, writer)
+    });
+}
+
+/// Run the "fragment" subcommand if `fragmenting` is set, the "defragment"
+/// one otherwise.
+fn run_fragmentation(matches: &ArgMatches, fragmenting: bool) {
+    let input_name = matches.value_of("INPUT").unwrap();
+    let output_name = matches.value_of("OUTPUT").unwrap();
+    let with_sidx = matches.is_present("sidx");
+    write_new_file(input_name, output_name, |data, reader, writer| {
+        if fragmenting {
+            fragment(reader, data, with_sidx, writer)
+        } else {
+            defragment(reader, data, writer)
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use super::{EditOperation, edit, parse_isobmff, write_new_file};
+
+    #[test]
+    fn edit_file_in_place() {
+        let path = format!("{}/tests/fixtures/progressive.mp4", env!("CARGO_MANIFEST_DIR"));
+        let input = std::fs::read(&path).unwrap();
+        let directory = std::env::temp_dir()
+            .join(format!("isobmff-inspector-test-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let file_path = directory.join("progressive.mp4");
+        std::fs::write(&file_path, &input).unwrap();
+
+        let file_name = file_path.to_str().unwrap();
+        write_new_file(file_name, file_name, |data, reader, writer| {
+            edit(reader, data, &[EditOperation::Remove("moov".parse().unwrap())], writer)
+        });
+
+        // The input was fully read before being replaced
+        let output = std::fs::read(&file_path).unwrap();
+        let names: Vec<_> = parse_isobmff(Cursor::new(&output)).unwrap()
+            .iter()
+            .map(|(info, _)| info.short_name.clone())
+            .collect();
+        assert_eq!(names, vec!["ftyp", "mdat"]);
+        assert_eq!(output[..16], input[..16]);
+        assert_eq!(output[16..], input[1175..]);
+
+        // The temporary file was renamed
+        let remaining: Vec<_> = std::fs::read_dir(&directory).unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(remaining, vec!["progressive.mp4"]);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
 }
@@ -0,0 +1,466 @@
+use super::super::boxes::{Ac3StreamInfo, BoxValue};
+use super::bits::BitReader;
+
+/// Audio coding whose frame headers can be decoded.
+pub enum AudioFormat {
+    /// Raw AAC, with the `channelConfiguration` of the sample entry if known.
+    Aac(Option<u8>),
+
+    /// AC-3 or E-AC-3, with the stream parameters of the sample entry if
+    /// known.
+    Ac3(Option<Ac3StreamInfo>),
+
+    /// Opus, with the output channel count of the sample entry if known.
+    Opus(Option<u8>),
+}
+
+/// Size in 16-bit words of AC-3 syncframes, by `frmsizecod` then by `fscod`.
+const AC3_FRAME_SIZES: [[u16; 3]; 38] = [
+    [64, 69, 96], [64, 70, 96], [80, 87, 120], [80, 88, 120],
+    [96, 104, 144], [96, 105, 144], [112, 121, 168], [112, 122, 168],
+    [128, 139, 192], [128, 140, 192], [160, 174, 240], [160, 175, 240],
+    [192, 208, 288], [192, 209, 288], [224, 243, 336], [224, 244, 336],
+    [256, 278, 384], [256, 279, 384], [320, 348, 480], [320, 349, 480],
+    [384, 417, 576], [384, 418, 576], [448, 487, 672], [448, 488, 672],
+    [512, 557, 768], [512, 558, 768], [640, 696, 960], [640, 697, 960],
+    [768, 835, 1152], [768, 836, 1152], [896, 975, 1344], [896, 976, 1344],
+    [1024, 1114, 1536], [1024, 1115, 1536], [1152, 1253, 1728], [1152, 1254, 1728],
+    [1280, 1393, 1920], [1280, 1394, 1920],
+];
+
+/// Header of an AC-3 or E-AC-3 syncframe.
+pub struct Ac3Frame {
+    /// `true` for an E-AC-3 frame (`bsid` 11 to 16).
+    is_enhanced: bool,
+    frame_size: u32,
+
+    /// E-AC-3 stream type and substream ID.
+    strmtyp: Option<u8>,
+    substreamid: Option<u8>,
+    fscod: u8,
+    bsid: u8,
+    acmod: u8,
+    lfeon: bool,
+    dialnorm: u8,
+}
+
+impl Ac3Frame {
+    /// Parse the syncframe header at the start of `data`.
+    fn parse(data: &[u8]) -> Option<Self> {
+        if data.get(..2)? != [0x0B, 0x77] {
+            return None;
+        }
+        let bsid = data.get(5)? >> 3;
+        if bsid > 10 {
+            let mut reader = BitReader::new(&data[2..]);
+            let strmtyp = reader.read_bits(2)? as u8;
+            let substreamid = reader.read_bits(3)? as u8;
+            let frame_size = (reader.read_bits(11)? as u32 + 1) * 2;
+            let fscod = reader.read_bits(2)? as u8;
+            // fscod2 or numblkscod
+            reader.skip_bits(2)?;
+            let acmod = reader.read_bits(3)? as u8;
+            let lfeon = reader.read_bit()?;
+            let bsid = reader.read_bits(5)? as u8;
+            let dialnorm = reader.read_bits(5)? as u8;
+            Some(Self {
+                is_enhanced: true,
+                frame_size,
+                strmtyp: Some(strmtyp),
+                substreamid: Some(substreamid),
+                fscod,
+                bsid,
+                acmod,
+                lfeon,
+                dialnorm,
+            })
+        } else {
+            // skip crc1
+            let mut reader = BitReader::new(&data[4..]);
+            let fscod = reader.read_bits(2)? as u8;
+            let frmsizecod = reader.read_bits(6)? as usize;
+            let frame_size = 2 * *AC3_FRAME_SIZES.get(frmsizecod)?.get(fscod as usize)? as u32;
+            let bsid = reader.read_bits(5)? as u8;
+            // bsmod
+            reader.skip_bits(3)?;
+            let acmod = reader.read_bits(3)? as u8;
+            if acmod & 0x01 != 0 && acmod != 1 {
+                // cmixlev
+                reader.skip_bits(2)?;
+            }
+            if acmod & 0x04 != 0 {
+                // surmixlev
+                reader.skip_bits(2)?;
+            }
+            if acmod == 2 {
+                // dsurmod
+                reader.skip_bits(2)?;
+            }
+            let lfeon = reader.read_bit()?;
+            let dialnorm = reader.read_bits(5)? as u8;
+            Some(Self {
+                is_enhanced: false,
+                frame_size,
+                strmtyp: None,
+                substreamid: None,
+                fscod,
+                bsid,
+                acmod,
+                lfeon,
+                dialnorm,
+            })
+        }
+    }
+
+    fn get_values(&self) -> Vec<(&'static str, BoxValue)> {
+        let mut values = vec![
+            ("format", BoxValue::from(if self.is_enhanced { "E-AC-3" } else { "AC-3" })),
+            ("frame_size", BoxValue::from(self.frame_size)),
+        ];
+        if let Some(strmtyp) = self.strmtyp {
+            values.push(("strmtyp", BoxValue::from(strmtyp)));
+        }
+        if let Some(substreamid) = self.substreamid {
+            values.push(("substreamid", BoxValue::from(substreamid)));
+        }
+        values.push(("fscod", BoxValue::from(self.fscod)));
+        values.push(("bsid", BoxValue::from(self.bsid)));
+        values.push(("acmod", BoxValue::from(self.acmod)));
+        values.push(("lfeon", BoxValue::from(self.lfeon)));
+        values.push(("dialnorm", BoxValue::from(self.dialnorm)));
+        values
+    }
+}
+
+/// Returns the name of an AAC syntactic element (`id_syn_ele`).
+fn get_aac_element_name(id_syn_ele: u8) -> &'static str {
+    match id_syn_ele {
+        0 => "SCE",
+        1 => "CPE",
+        2 => "CCE",
+        3 => "LFE",
+        4 => "DSE",
+        5 => "PCE",
+        6 => "FIL",
+        _ => "END",
+    }
+}
+
+/// Returns the first audio element an AAC `raw_data_block` should contain
+/// for a given `channelConfiguration`.
+fn get_expected_aac_element(channel_configuration: u8) -> Option<u8> {
+    match channel_configuration {
+        1 | 3..=7 => Some(0),
+        2 => Some(1),
+        _ => None,
+    }
+}
+
+/// List the elements at the start of an AAC `raw_data_block`, skipping over
+/// the data stream and fill elements. Reading stops at the first element
+/// which cannot be skipped without decoding it.
+fn read_aac_elements(data: &[u8]) -> Vec<(u8, u8)> {
+    let mut elements = vec![];
+    let mut reader = BitReader::new(data);
+    while let (Some(id_syn_ele), Some(instance_tag)) = (reader.read_bits(3), reader.read_bits(4)) {
+        let id_syn_ele = id_syn_ele as u8;
+        let instance_tag = instance_tag as u8;
+        match id_syn_ele {
+            4 => {
+                elements.push((id_syn_ele, instance_tag));
+                let byte_align = reader.read_bit();
+                let mut count = reader.read_bits(8).unwrap_or(0) as usize;
+                if count == 255 {
+                    count += reader.read_bits(8).unwrap_or(0) as usize;
+                }
+                if byte_align == Some(true) {
+                    let position = reader.get_position();
+                    if reader.skip_bits((8 - position % 8) % 8).is_none() {
+                        break;
+                    }
+                }
+                if reader.skip_bits(count * 8).is_none() {
+                    break;
+                }
+            },
+            6 => {
+                // FIL has no instance tag: those 4 bits are its count
+                elements.push((id_syn_ele, 0));
+                let mut count = instance_tag as usize;
+                if count == 15 {
+                    count += reader.read_bits(8).unwrap_or(0) as usize;
+                    count -= 1;
+                }
+                if reader.skip_bits(count * 8).is_none() {
+                    break;
+                }
+            },
+            _ => {
+                elements.push((id_syn_ele, instance_tag));
+                break;
+            },
+        }
+    }
+    elements
+}
+
+/// Frame headers decoded from an audio sample.
+pub enum AudioSample {
+    Aac {
+        /// `id_syn_ele` and `element_instance_tag` of the elements at the
+        /// start of the `raw_data_block`.
+        elements: Vec<(u8, u8)>,
+        mismatches: Vec<String>,
+    },
+    Ac3 {
+        frames: Vec<Ac3Frame>,
+        trailing_bytes: usize,
+        mismatches: Vec<String>,
+    },
+    Opus {
+        config: u8,
+        stereo: bool,
+        frame_count: Option<u8>,
+        mismatches: Vec<String>,
+    },
+}
+
+impl AudioSample {
+    pub fn decode(format: &AudioFormat, data: &[u8]) -> Self {
+        match format {
+            AudioFormat::Aac(channel_configuration) => {
+                let mut mismatches = vec![];
+                let mut raw_data_block = data;
+                if data.len() >= 2 && data[0] == 0xFF && data[1] & 0xF0 == 0xF0 {
+                    mismatches.push("sample starts with an ADTS header".to_owned());
+                    // the header is followed by a CRC unless protection_absent is set
+                    let header_size = if data[1] & 0x01 != 0 { 7 } else { 9 };
+                    raw_data_block = &data[header_size.min(data.len())..];
+                }
+                let elements = read_aac_elements(raw_data_block);
+                let first_audio_element = elements.iter().find(|e| e.0 != 4 && e.0 != 6);
+                let expected = channel_configuration.and_then(get_expected_aac_element);
+                if let (Some(expected), Some(element)) = (expected, first_audio_element) {
+                    if element.0 != expected {
+                        mismatches.push(format!(
+                            "first element is {} while channelConfiguration {} announces {}",
+                            get_aac_element_name(element.0),
+                            channel_configuration.unwrap_or(0),
+                            get_aac_element_name(expected)));
+                    }
+                }
+                AudioSample::Aac { elements, mismatches }
+            },
+            AudioFormat::Ac3(stream_info) => {
+                let mut frames = vec![];
+                let mut remaining = data;
+                while let Some(frame) = Ac3Frame::parse(remaining) {
+                    let frame_size = (frame.frame_size as usize).min(remaining.len());
+                    remaining = &remaining[frame_size..];
+                    frames.push(frame);
+                }
+                let mut mismatches = vec![];
+                // Only the first independent substream is described by the
+                // sample entry
+                if let (Some(info), Some(frame)) = (stream_info, frames.first()) {
+                    let mut compare = |field: &str, in_frame: u8, in_entry: u8| {
+                        if in_frame != in_entry {
+                            mismatches.push(format!(
+                                "{} {} differs from the sample entry's {}",
+                                field, in_frame, in_entry));
+                        }
+                    };
+                    compare("fscod", frame.fscod, info.fscod);
+                    compare("bsid", frame.bsid, info.bsid);
+                    compare("acmod", frame.acmod, info.acmod);
+                    compare("lfeon", frame.lfeon as u8, info.lfeon as u8);
+                }
+                if frames.is_empty() {
+                    mismatches.push("no syncframe found".to_owned());
+                }
+                AudioSample::Ac3 { frames, trailing_bytes: remaining.len(), mismatches }
+            },
+            AudioFormat::Opus(channel_count) => {
+                let toc = data.first().copied().unwrap_or(0);
+                let stereo = toc & 0x04 != 0;
+                let frame_count = match toc & 0x03 {
+                    0 => Some(1),
+                    1 | 2 => Some(2),
+                    _ => data.get(1).map(|b| b & 0x3F),
+                };
+                let mut mismatches = vec![];
+                if data.is_empty() {
+                    mismatches.push("empty packet".to_owned());
+                } else if stereo && *channel_count == Some(1) {
+                    mismatches.push("stereo packet in a mono stream".to_owned());
+                }
+                AudioSample::Opus { config: toc >> 3, stereo, frame_count, mismatches }
+            },
+        }
+    }
+
+    pub fn get_values(&self) -> Vec<(&'static str, BoxValue)> {
+        match self {
+            AudioSample::Aac { elements, mismatches } => vec![
+                ("elements", BoxValue::Collection(
+                        elements.iter().map(|(id_syn_ele, instance_tag)| vec![
+                            ("id_syn_ele", BoxValue::from(*id_syn_ele)),
+                            ("element_name", BoxValue::from(get_aac_element_name(*id_syn_ele))),
+                            ("element_instance_tag", BoxValue::from(*instance_tag)),
+                        ]).collect()
+                )),
+                ("config_mismatches", BoxValue::from(mismatches.as_slice())),
+            ],
+            AudioSample::Ac3 { frames, trailing_bytes, mismatches } => {
+                let mut values = vec![
+                    ("frame_count", BoxValue::from(frames.len() as u32)),
+                    ("frames", BoxValue::Collection(
+                            frames.iter().map(|frame| frame.get_values()).collect())),
+                ];
+                if *trailing_bytes > 0 {
+                    values.push(("trailing_bytes", BoxValue::from(*trailing_bytes as u64)));
+                }
+                values.push(("config_mismatches", BoxValue::from(mismatches.as_slice())));
+                values
+            },
+            AudioSample::Opus { config, stereo, frame_count, mismatches } => {
+                let (mode, bandwidth, frame_duration) = describe_opus_config(*config);
+                let mut values = vec![
+                    ("config", BoxValue::from(*config)),
+                    ("mode", BoxValue::from(mode)),
+                    ("bandwidth", BoxValue::from(bandwidth)),
+                    ("frame_duration", BoxValue::from(frame_duration)),
+                    ("stereo", BoxValue::from(*stereo)),
+                ];
+                if let Some(frame_count) = frame_count {
+                    values.push(("frame_count", BoxValue::from(*frame_count)));
+                }
+                values.push(("config_mismatches", BoxValue::from(mismatches.as_slice())));
+                values
+            },
+        }
+    }
+}
+
+/// Returns the mode, the audio bandwidth and the frame duration of an Opus
+/// TOC `config` value.
+fn describe_opus_config(config: u8) -> (&'static str, &'static str, &'static str) {
+    match config {
+        0..=11 => (
+            "SILK-only",
+            ["NB", "MB", "WB"][config as usize / 4],
+            ["10ms", "20ms", "40ms", "60ms"][config as usize % 4],
+        ),
+        12..=15 => (
+            "Hybrid",
+            if config < 14 { "SWB" } else { "FB" },
+            ["10ms", "20ms"][config as usize % 2],
+        ),
+        _ => (
+            "CELT-only",
+            ["NB", "WB", "SWB", "FB"][(config as usize - 16) / 4],
+            ["2.5ms", "5ms", "10ms", "20ms"][config as usize % 4],
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AudioFormat, AudioSample};
+    use super::super::{DecodedSample, PayloadKind};
+    use super::super::test_utils::decode_fixture_samples;
+
+    fn read_audio_samples() -> Vec<Vec<DecodedSample>> {
+        decode_fixture_samples("audio.mp4", PayloadKind::AudioFrames)
+    }
+
+    fn get_audio_sample(decoded: &DecodedSample) -> &AudioSample {
+        match decoded {
+            DecodedSample::AudioFrames(sample) => sample,
+            _ => panic!("unexpected decoded sample"),
+        }
+    }
+
+    #[test]
+    fn aac_elements() {
+        let tracks = read_audio_samples();
+        let samples: Vec<_> = tracks[0].iter()
+            .map(|decoded| match get_audio_sample(decoded) {
+                AudioSample::Aac { elements, mismatches } => (elements.clone(), mismatches.clone()),
+                _ => panic!("not an AAC sample"),
+            })
+            .collect();
+        assert_eq!(samples, vec![
+            (vec![(1, 0)], vec![]),
+            (vec![(6, 0), (0, 0)],
+             vec!["first element is SCE while channelConfiguration 2 announces CPE".to_owned()]),
+            (vec![(1, 0)], vec!["sample starts with an ADTS header".to_owned()]),
+        ]);
+    }
+
+    #[test]
+    fn ac3_and_eac3_frames() {
+        let tracks = read_audio_samples();
+        for (track, expected_mismatches) in [
+            (&tracks[1], vec!["acmod 2 differs from the sample entry's 7".to_owned()]),
+            (&tracks[2], vec![]),
+        ] {
+            assert_eq!(track.len(), 1);
+            match get_audio_sample(&track[0]) {
+                AudioSample::Ac3 { mismatches, .. } => assert_eq!(*mismatches, expected_mismatches),
+                _ => panic!("not an AC-3 sample"),
+            }
+        }
+
+        let (frames, trailing_bytes) = match get_audio_sample(&tracks[1][0]) {
+            AudioSample::Ac3 { frames, trailing_bytes, .. } => (frames, *trailing_bytes),
+            _ => panic!("not an AC-3 sample"),
+        };
+        assert_eq!(frames.len(), 1);
+        let frame = &frames[0];
+        assert!(!frame.is_enhanced);
+        assert_eq!((frame.frame_size, frame.fscod, frame.bsid, frame.acmod, frame.lfeon, frame.dialnorm),
+            (128, 0, 8, 2, false, 31));
+        assert_eq!(trailing_bytes, 0);
+
+        let (frames, trailing_bytes) = match get_audio_sample(&tracks[2][0]) {
+            AudioSample::Ac3 { frames, trailing_bytes, .. } => (frames, *trailing_bytes),
+            _ => panic!("not an AC-3 sample"),
+        };
+        assert_eq!(frames.len(), 1);
+        let frame = &frames[0];
+        assert!(frame.is_enhanced);
+        assert_eq!((frame.strmtyp, frame.substreamid), (Some(0), Some(0)));
+        assert_eq!((frame.frame_size, frame.fscod, frame.bsid, frame.acmod, frame.lfeon, frame.dialnorm),
+            (128, 0, 16, 7, true, 27));
+        assert_eq!(trailing_bytes, 5);
+    }
+
+    #[test]
+    fn opus_packets() {
+        let tracks = read_audio_samples();
+        let samples: Vec<_> = tracks[3].iter()
+            .map(|decoded| match get_audio_sample(decoded) {
+                AudioSample::Opus { config, stereo, frame_count, mismatches } =>
+                    (*config, *stereo, *frame_count, mismatches.clone()),
+                _ => panic!("not an Opus sample"),
+            })
+            .collect();
+        assert_eq!(samples, vec![
+            (31, true, Some(2), vec!["stereo packet in a mono stream".to_owned()]),
+            (1, false, Some(1), vec![]),
+        ]);
+    }
+
+    #[test]
+    fn ac3_without_syncframe() {
+        match AudioSample::decode(&AudioFormat::Ac3(None), &[0x00, 0x01, 0x02]) {
+            AudioSample::Ac3 { frames, trailing_bytes, mismatches } => {
+                assert!(frames.is_empty());
+                assert_eq!(trailing_bytes, 3);
+                assert_eq!(mismatches, vec!["no syncframe found".to_owned()]);
+            },
+            _ => panic!("not an AC-3 sample"),
+        }
+    }
+}
@@ -0,0 +1,82 @@
+/// Reads a bitstream, most significant bit first, as done for the syntax
+/// elements of video codings.
+/// Every read returns `None` once the end of the data is reached.
+pub struct BitReader<'a> {
+    data: &'a [u8],
+
+    /// Position of the next bit to read, from the start of `data`.
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    /// Read `nb_bits` bits, up to 64, as an unsigned integer.
+    pub fn read_bits(&mut self, nb_bits: u8) -> Option<u64> {
+        if self.position + nb_bits as usize > self.data.len() * 8 {
+            return None;
+        }
+        let mut value = 0u64;
+        for _ in 0..nb_bits {
+            let byte = self.data[self.position / 8];
+            let bit = (byte >> (7 - self.position % 8)) & 0x01;
+            value = (value << 1) | bit as u64;
+            self.position += 1;
+        }
+        Some(value)
+    }
+
+    pub fn read_bit(&mut self) -> Option<bool> {
+        self.read_bits(1).map(|bit| bit == 1)
+    }
+
+    /// Read an unsigned Exp-Golomb-coded value (`ue(v)`).
+    pub fn read_ue(&mut self) -> Option<u32> {
+        let mut leading_zeros = 0;
+        while !self.read_bit()? {
+            leading_zeros += 1;
+            if leading_zeros > 31 {
+                return None;
+            }
+        }
+        let suffix = self.read_bits(leading_zeros)?;
+        Some(((1u64 << leading_zeros) - 1 + suffix) as u32)
+    }
+
+    /// Read a signed Exp-Golomb-coded value (`se(v)`).
+    pub fn read_se(&mut self) -> Option<i32> {
+        let value = self.read_ue()? as i64;
+        Some(if value % 2 == 0 { -(value / 2) } else { (value + 1) / 2 } as i32)
+    }
+
+    /// Returns the position of the next bit to read.
+    pub fn get_position(&self) -> usize {
+        self.position
+    }
+
+    pub fn skip_bits(&mut self, nb_bits: usize) -> Option<()> {
+        if self.position + nb_bits > self.data.len() * 8 {
+            return None;
+        }
+        self.position += nb_bits;
+        Some(())
+    }
+}
+
+/// Convert the payload of a NAL unit to its raw byte sequence payload, by
+/// removing its emulation prevention bytes (the `0x03` of each `0x000003`).
+pub fn to_rbsp(data: &[u8]) -> Vec<u8> {
+    let mut rbsp = Vec::with_capacity(data.len());
+    let mut zeros = 0;
+    for byte in data {
+        if zeros >= 2 && *byte == 0x03 {
+            zeros = 0;
+            continue;
+        }
+        zeros = if *byte == 0 { zeros + 1 } else { 0 };
+        rbsp.push(*byte);
+    }
+    rbsp
+}
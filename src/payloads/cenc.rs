@@ -0,0 +1,447 @@
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+use aes::Aes128;
+use aes::cipher::{BlockDecrypt, BlockEncrypt, KeyInit};
+use thiserror::Error;
+use super::super::boxes::{SampleEncryption, SampleLocation, TrackEncryption, TrackSamples};
+
+/// Content keys, by key ID.
+pub type ContentKeys = HashMap<[u8; 16], [u8; 16]>;
+
+#[derive(Error, Debug)]
+pub enum DecryptionError {
+    /// The track is protected with a scheme other than the Common Encryption
+    /// ones.
+    #[error("unsupported protection scheme \"{0}\"")]
+    UnsupportedScheme(String),
+
+    /// The track's protection information has no `tenc` box.
+    #[error("no tenc box found in the protection scheme information")]
+    MissingTrackEncryption,
+
+    /// No key was supplied for the key ID of the track.
+    #[error("no key supplied for KID {0}")]
+    MissingKey(String),
+
+    /// The sample has no IV, neither from a `senc` box nor as a constant IV.
+    #[error("no initialization vector found for the sample")]
+    MissingIv,
+
+    /// The auxiliary information of the sample could not be read.
+    #[error("cannot read the auxiliary information of the sample: {0}")]
+    AuxiliaryInformationRead(#[from] std::io::Error),
+
+    /// The auxiliary information of the sample does not match the IV size of
+    /// the track.
+    #[error("invalid auxiliary information for the sample")]
+    InvalidAuxiliaryInformation,
+
+    /// The subsamples of the sample do not add up to its size.
+    #[error("subsamples cover {covered} bytes of a {size}-byte sample")]
+    SubsampleSizeMismatch {
+        covered: u64,
+        size: usize,
+    },
+}
+
+/// Parse a "KID:KEY" pair of 16-byte hexadecimal values, as given on the
+/// command line. Dashes in the key ID are ignored, so that UUIDs can be used.
+pub fn parse_content_key(arg: &str) -> Option<([u8; 16], [u8; 16])> {
+    let (kid, key) = arg.split_once(':')?;
+    Some((parse_hex_16(&kid.replace('-', ""))?, parse_hex_16(key)?))
+}
+
+fn parse_hex_16(hex: &str) -> Option<[u8; 16]> {
+    if hex.len() != 32 || !hex.is_ascii() {
+        return None;
+    }
+    let mut bytes = [0u8; 16];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(bytes)
+}
+
+fn to_hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Block cipher mode of a Common Encryption scheme.
+#[derive(Clone, Copy, PartialEq)]
+enum CipherMode {
+    /// AES-CTR, used by "cenc" and "cens".
+    Ctr,
+    /// AES-CBC, used by "cbc1" and "cbcs".
+    Cbc,
+}
+
+/// Decrypts the samples of a track protected with one of the Common
+/// Encryption schemes ("cenc", "cens", "cbc1" or "cbcs").
+pub struct SampleDecrypter {
+    cipher: Aes128,
+    mode: CipherMode,
+
+    /// Number of encrypted then clear 16-byte blocks of the encryption
+    /// pattern. `None` if every block of the protected data is encrypted.
+    pattern: Option<(usize, usize)>,
+
+    /// If `true`, the IV applies to each subsample separately instead of to
+    /// the whole sample, as in "cbcs".
+    reset_iv_per_subsample: bool,
+    per_sample_iv_size: u8,
+    constant_iv: Vec<u8>,
+}
+
+impl SampleDecrypter {
+    /// Create a decrypter for the samples of the given track with the
+    /// supplied keys.
+    /// `None` if the track is not protected, or is protected but with its
+    /// samples left in the clear.
+    pub fn for_track(
+        track: &TrackSamples,
+        keys: &ContentKeys
+    ) -> Result<Option<Self>, DecryptionError> {
+        match track.get_protection() {
+            Some(protection) => Self::new(protection.scheme_type, protection.encryption, keys),
+            None => Ok(None),
+        }
+    }
+
+    /// Create a decrypter for samples protected with the given scheme and
+    /// the track encryption parameters of its `tenc` box.
+    /// `None` if the samples are left in the clear.
+    pub fn new(
+        scheme_type: &str,
+        encryption: Option<&TrackEncryption>,
+        keys: &ContentKeys
+    ) -> Result<Option<Self>, DecryptionError> {
+        let (mode, uses_pattern, reset_iv_per_subsample) = match scheme_type {
+            "cenc" => (CipherMode::Ctr, false, false),
+            "cens" => (CipherMode::Ctr, true, false),
+            "cbc1" => (CipherMode::Cbc, false, false),
+            "cbcs" => (CipherMode::Cbc, true, true),
+            scheme => return Err(DecryptionError::UnsupportedScheme(scheme.to_owned())),
+        };
+        let encryption = encryption.ok_or(DecryptionError::MissingTrackEncryption)?;
+        if encryption.is_protected == 0 {
+            return Ok(None);
+        }
+        let key = keys.get(&encryption.kid)
+            .ok_or_else(|| DecryptionError::MissingKey(to_hex(&encryption.kid)))?;
+        let pattern = if uses_pattern && encryption.crypt_byte_block > 0 {
+            Some((encryption.crypt_byte_block as usize, encryption.skip_byte_block as usize))
+        } else {
+            None
+        };
+        Ok(Some(Self {
+            cipher: Aes128::new(key.into()),
+            mode,
+            pattern,
+            reset_iv_per_subsample,
+            per_sample_iv_size: encryption.per_sample_iv_size,
+            constant_iv: encryption.constant_iv.clone(),
+        }))
+    }
+
+    /// Returns the encryption parameters of a sample: the ones found for it
+    /// in the `senc` box or, without such a box, the ones read from its
+    /// auxiliary information, as located by the `saiz` and `saio` boxes.
+    /// `None` if the sample has neither.
+    pub fn get_sample_encryption<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        sample: &SampleLocation
+    ) -> Result<Option<SampleEncryption>, DecryptionError> {
+        if let Some(encryption) = &sample.encryption {
+            return Ok(Some(encryption.clone()));
+        }
+        let (offset, size) = match sample.auxiliary_information {
+            Some(aux_info) => aux_info,
+            None => return Ok(None),
+        };
+        let mut data = vec![0; size as usize];
+        reader.seek(SeekFrom::Start(offset))?;
+        reader.read_exact(&mut data)?;
+        SampleEncryption::parse_auxiliary_information(&data, self.per_sample_iv_size as usize)
+            .map(Some)
+            .ok_or(DecryptionError::InvalidAuxiliaryInformation)
+    }
+
+    /// Decrypt a sample in place, with the encryption parameters returned for
+    /// it by `get_sample_encryption`.
+    pub fn decrypt(
+        &self,
+        encryption: Option<&SampleEncryption>,
+        data: &mut [u8]
+    ) -> Result<(), DecryptionError> {
+        let iv = match encryption {
+            Some(encryption) if !encryption.iv.is_empty() => &encryption.iv,
+            _ if !self.constant_iv.is_empty() => &self.constant_iv,
+            _ => return Err(DecryptionError::MissingIv),
+        };
+        let mut iv_block = [0u8; 16];
+        let iv_len = iv.len().min(16);
+        iv_block[..iv_len].copy_from_slice(&iv[..iv_len]);
+
+        // Byte ranges of the protected data, one per subsample
+        let protected_ranges: Vec<(usize, usize)> = match encryption {
+            Some(encryption) if !encryption.subsamples.is_empty() => {
+                let mut ranges = vec![];
+                let mut offset = 0;
+                for (clear, protected) in encryption.subsamples.iter() {
+                    offset += *clear as usize;
+                    ranges.push((offset, *protected as usize));
+                    offset += *protected as usize;
+                }
+                if offset != data.len() {
+                    return Err(DecryptionError::SubsampleSizeMismatch {
+                        covered: offset as u64,
+                        size: data.len(),
+                    });
+                }
+                ranges
+            },
+            _ => vec![(0, data.len())],
+        };
+
+        // Data encrypted with a single IV, as lists of encrypted byte ranges
+        let mut chains: Vec<Vec<(usize, usize)>> = vec![];
+        for (start, size) in protected_ranges {
+            let ranges = self.get_encrypted_ranges(start, size);
+            match chains.last_mut() {
+                Some(chain) if !self.reset_iv_per_subsample => chain.extend(ranges),
+                _ => chains.push(ranges),
+            }
+        }
+        for chain in chains {
+            let mut encrypted: Vec<u8> = chain
+                .iter()
+                .flat_map(|(start, size)| data[*start..*start + *size].iter().copied())
+                .collect();
+            match self.mode {
+                CipherMode::Ctr => self.decrypt_ctr(&iv_block, &mut encrypted),
+                CipherMode::Cbc => self.decrypt_cbc(&iv_block, &mut encrypted),
+            }
+            let mut decrypted = encrypted.into_iter();
+            for (start, size) in chain {
+                for byte in data[start..start + size].iter_mut() {
+                    *byte = decrypted.next().unwrap_or(*byte);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the encrypted byte ranges of a protected range, according to
+    /// the encryption pattern. In CBC mode, or when a pattern is used, a
+    /// trailing partial block is left in the clear.
+    fn get_encrypted_ranges(&self, start: usize, size: usize) -> Vec<(usize, usize)> {
+        let full_blocks_only = self.mode == CipherMode::Cbc || self.pattern.is_some();
+        let (crypt, skip) = match self.pattern {
+            Some(pattern) => pattern,
+            None if full_blocks_only => return vec![(start, size - size % 16)],
+            None => return vec![(start, size)],
+        };
+        let mut ranges = vec![];
+        let mut offset = 0;
+        while size - offset >= 16 {
+            let encrypted_size = (crypt * 16).min((size - offset) / 16 * 16);
+            ranges.push((start + offset, encrypted_size));
+            offset += encrypted_size;
+            offset += (skip * 16).min(size - offset);
+        }
+        ranges
+    }
+
+    fn decrypt_ctr(&self, iv: &[u8; 16], data: &mut [u8]) {
+        let mut counter = *iv;
+        for chunk in data.chunks_mut(16) {
+            let mut keystream = aes::Block::from(counter);
+            self.cipher.encrypt_block(&mut keystream);
+            for (byte, key_byte) in chunk.iter_mut().zip(keystream.iter()) {
+                *byte ^= key_byte;
+            }
+            // Only the last 8 bytes of the counter block are incremented
+            let mut block_counter = [0u8; 8];
+            block_counter.copy_from_slice(&counter[8..]);
+            let block_counter = u64::from_be_bytes(block_counter).wrapping_add(1);
+            counter[8..].copy_from_slice(&block_counter.to_be_bytes());
+        }
+    }
+
+    fn decrypt_cbc(&self, iv: &[u8; 16], data: &mut [u8]) {
+        let mut previous = *iv;
+        for chunk in data.chunks_exact_mut(16) {
+            let mut ciphertext = [0u8; 16];
+            ciphertext.copy_from_slice(chunk);
+            let mut block = aes::Block::from(ciphertext);
+            self.cipher.decrypt_block(&mut block);
+            for ((byte, plain_byte), previous_byte) in
+                chunk.iter_mut().zip(block.iter()).zip(previous.iter())
+            {
+                *byte = plain_byte ^ previous_byte;
+            }
+            previous = ciphertext;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use super::*;
+
+    const KID: [u8; 16] = [0x11; 16];
+    const KEY: [u8; 16] = [
+        0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6,
+        0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c,
+    ];
+    const IV_8: [u8; 8] = [0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7];
+    const IV_16: [u8; 16] = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+        0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+    ];
+
+    // Expected results were computed with another AES implementation
+    const CENC_SAMPLE: &str = "030a11181f2a028f8d1890377d479b9146e172c38d39323859b2b9c02d17b8ef\
+                               034f302eb687bdda";
+    const CBC1_SAMPLE: &str = "0fa02a8340a0687ca4413328a063ed248ae61fb0dfdb689e3ef022124fd852c8\
+                               7fcae9ca1c7c5df09bb0d9ecfc0b65bb535a";
+    const CENS_SAMPLE: &str = "030a11181f2a028f8d1890377d479b9146e172c38d969da4abb2b9c0c7ced5dc\
+                               e3eaf1f8ff060d141b222930373e454c535a61686f767d848b9299a0a7aeb5bc\
+                               c3cad1d8dfe6edf4fb020910171e252c333a41484f565d646b727980878e959c\
+                               a3aab1b8bfc6cdd4dbe2e9f0f7fe050c131a21282f363d444b525960676e757c\
+                               838a91989fa6adb4bbc2c9d0d7dee5ecf3fa01080f161d242b323940474e555c\
+                               636a71787f292208694870dd845e600d0593605821f6fd040b121920272e353c\
+                               434a51585f666d747b828990979ea5ac909a61c45bdd9cbd1d913dd6886753fe\
+                               232a31383f464d545b626970777e858c939aa1a8afb6bdc4";
+    const CBCS_SAMPLE: &str = "030a11181f52bffc3cf93688118e734e8f5864e0a0969da4abb2b9c0c7ced5dc\
+                               e3eaf1f8ff060d141b222930373e454c535a61686f767d848b9299a0a7aeb5bc\
+                               c3cad1d8dfe6edf4fb020910171e252c333a41484f565d646b727980878e959c\
+                               a3aab1b8bfc6cdd4dbe2e9f0f7fe050c131a21282f363d444b525960676e757c\
+                               838a91989fa6adb4bbc2c9d0d7dee5ecf3fa01080f161d242b323940474e555c\
+                               636a71787f7c78370f041a42a26620a2dde25e3d8bf6fd040b121920272e353c\
+                               434a51585f666d747b828990979ea5acedb624d155ae949f5b3181db275005c9\
+                               232a31383f464d545b626970777e858c939aa1a8afb6bdc4";
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    /// The clear sample whose encrypted versions are tested.
+    fn get_clear_sample(size: usize) -> Vec<u8> {
+        (0..size).map(|i| (i * 7 + 3) as u8).collect()
+    }
+
+    fn create_decrypter(scheme_type: &str, encryption: TrackEncryption) -> SampleDecrypter {
+        let keys = ContentKeys::from([(KID, KEY)]);
+        SampleDecrypter::new(scheme_type, Some(&encryption), &keys).unwrap().unwrap()
+    }
+
+    fn create_encryption(iv: &[u8], subsamples: &[(u16, u32)]) -> SampleEncryption {
+        SampleEncryption { iv: iv.to_vec(), subsamples: subsamples.to_vec(), data: vec![] }
+    }
+
+    fn check_decryption(
+        decrypter: &SampleDecrypter,
+        encryption: Option<&SampleEncryption>,
+        encrypted_sample: &str
+    ) {
+        let mut data = from_hex(encrypted_sample);
+        decrypter.decrypt(encryption, &mut data).unwrap();
+        assert_eq!(data, get_clear_sample(data.len()));
+    }
+
+    #[test]
+    fn decrypt_cenc() {
+        let decrypter = create_decrypter("cenc", TrackEncryption {
+            crypt_byte_block: 0,
+            skip_byte_block: 0,
+            is_protected: 1,
+            per_sample_iv_size: 8,
+            kid: KID,
+            constant_iv: vec![],
+        });
+        let encryption = create_encryption(&IV_8, &[(5, 20), (3, 12)]);
+        check_decryption(&decrypter, Some(&encryption), CENC_SAMPLE);
+    }
+
+    #[test]
+    fn decrypt_cbc1() {
+        let decrypter = create_decrypter("cbc1", TrackEncryption {
+            crypt_byte_block: 0,
+            skip_byte_block: 0,
+            is_protected: 1,
+            per_sample_iv_size: 16,
+            kid: KID,
+            constant_iv: vec![],
+        });
+        let encryption = create_encryption(&IV_16, &[]);
+        check_decryption(&decrypter, Some(&encryption), CBC1_SAMPLE);
+    }
+
+    #[test]
+    fn decrypt_cens() {
+        let decrypter = create_decrypter("cens", TrackEncryption {
+            crypt_byte_block: 1,
+            skip_byte_block: 9,
+            is_protected: 1,
+            per_sample_iv_size: 8,
+            kid: KID,
+            constant_iv: vec![],
+        });
+        let encryption = create_encryption(&IV_8, &[(5, 200), (3, 40)]);
+        check_decryption(&decrypter, Some(&encryption), CENS_SAMPLE);
+    }
+
+    #[test]
+    fn decrypt_cbcs() {
+        let decrypter = create_decrypter("cbcs", TrackEncryption {
+            crypt_byte_block: 1,
+            skip_byte_block: 9,
+            is_protected: 1,
+            per_sample_iv_size: 0,
+            kid: KID,
+            constant_iv: IV_16.to_vec(),
+        });
+        let encryption = create_encryption(&[], &[(5, 200), (3, 40)]);
+        check_decryption(&decrypter, Some(&encryption), CBCS_SAMPLE);
+    }
+
+    #[test]
+    fn decrypt_with_auxiliary_information() {
+        let decrypter = create_decrypter("cenc", TrackEncryption {
+            crypt_byte_block: 0,
+            skip_byte_block: 0,
+            is_protected: 1,
+            per_sample_iv_size: 8,
+            kid: KID,
+            constant_iv: vec![],
+        });
+        // IV, then 2 subsamples, stored after 4 unrelated bytes
+        let mut file = vec![0xff; 4];
+        file.extend_from_slice(&IV_8);
+        file.extend_from_slice(&[0, 2, 0, 5, 0, 0, 0, 20, 0, 3, 0, 0, 0, 12]);
+        let sample = SampleLocation {
+            sample_number: 1,
+            offset: 0,
+            size: 40,
+            decode_time: 0,
+            duration: 0,
+            composition_offset: 0,
+            is_sync: true,
+            sample_description_index: 1,
+            encryption: None,
+            auxiliary_information: Some((4, 22)),
+        };
+        let encryption = decrypter.get_sample_encryption(&mut Cursor::new(file), &sample)
+            .unwrap()
+            .unwrap();
+        assert_eq!(encryption.iv, IV_8);
+        assert_eq!(encryption.subsamples, [(5, 20), (3, 12)]);
+        check_decryption(&decrypter, Some(&encryption), CENC_SAMPLE);
+    }
+}
@@ -0,0 +1,218 @@
+mod audio;
+mod bits;
+mod cenc;
+mod nal;
+mod obu;
+mod sei;
+mod sps;
+mod subtitles;
+
+use std::io::{Read, Seek, SeekFrom};
+use super::boxes::{BoxValue, SampleLocation, TrackSamples};
+
+pub use audio::{AudioFormat, AudioSample};
+pub use cenc::{parse_content_key, ContentKeys, SampleDecrypter};
+pub use nal::{NalDecodingState, NalFormat, NalSample};
+pub use obu::{ObuDecodingState, ObuSample};
+pub use subtitles::SubtitleSample;
+
+/// Kind of content which can be decoded from the samples of a track.
+#[derive(Clone, Copy)]
+pub enum PayloadKind {
+    /// Subtitles and timed text (`stpp`, `wvtt`, `tx3g`).
+    Subtitles,
+
+    /// NAL units of AVC, HEVC and VVC video, with their SEI messages.
+    NalUnits,
+
+    /// OBUs of AV1 video.
+    Obus,
+
+    /// Frame headers of AAC, AC-3, E-AC-3 and Opus audio.
+    AudioFrames,
+}
+
+/// How the samples of a given track are decoded.
+pub enum SampleDecoder {
+    Subtitles(String),
+    NalUnits {
+        format: NalFormat,
+        nal_length_size: u8,
+        state: NalDecodingState,
+    },
+    Obus(ObuDecodingState),
+    AudioFrames(AudioFormat),
+}
+
+impl SampleDecoder {
+    /// Returns the decoder of the wanted kind for the samples of the given
+    /// track. `None` if that kind of content cannot be decoded from them.
+    pub fn for_track(kind: PayloadKind, track: &TrackSamples) -> Option<Self> {
+        let codec = track.get_coding_name()?;
+        match kind {
+            PayloadKind::Subtitles => match codec {
+                "stpp" | "wvtt" | "tx3g" => Some(SampleDecoder::Subtitles(codec.to_owned())),
+                _ => None,
+            },
+            PayloadKind::NalUnits => {
+                let format = NalFormat::from_codec(codec)?;
+                let mut state = NalDecodingState::default();
+                for nal_unit in track.get_configuration_nal_units() {
+                    state.process_nal_unit(format, nal_unit);
+                }
+                Some(SampleDecoder::NalUnits {
+                    format,
+                    nal_length_size: track.get_nal_length_size()?,
+                    state,
+                })
+            },
+            PayloadKind::Obus => {
+                if codec != "av01" {
+                    return None;
+                }
+                let mut state = ObuDecodingState::default();
+                if let Some(config_obus) = track.get_av1_config_obus() {
+                    ObuSample::decode(config_obus, &mut state);
+                }
+                Some(SampleDecoder::Obus(state))
+            },
+            PayloadKind::AudioFrames => match codec {
+                "mp4a" => Some(SampleDecoder::AudioFrames(
+                        AudioFormat::Aac(track.get_aac_channel_configuration()))),
+                "ac-3" | "ec-3" => Some(SampleDecoder::AudioFrames(
+                        AudioFormat::Ac3(track.get_ac3_stream_info()))),
+                "Opus" => Some(SampleDecoder::AudioFrames(
+                        AudioFormat::Opus(track.get_opus_channel_count()))),
+                _ => None,
+            },
+        }
+    }
+
+    /// Decode a sample. Samples have to be decoded in order, as information
+    /// found in a sample might be needed to decode the following ones.
+    pub fn decode(&mut self, data: &[u8]) -> Option<DecodedSample> {
+        match self {
+            SampleDecoder::Subtitles(codec) =>
+                SubtitleSample::decode(codec, data).map(DecodedSample::Subtitles),
+            SampleDecoder::NalUnits { format, nal_length_size, state } =>
+                Some(DecodedSample::NalUnits(
+                        NalSample::decode(*format, *nal_length_size, data, state))),
+            SampleDecoder::Obus(state) =>
+                Some(DecodedSample::Obus(ObuSample::decode(data, state))),
+            SampleDecoder::AudioFrames(format) =>
+                Some(DecodedSample::AudioFrames(AudioSample::decode(format, data))),
+        }
+    }
+}
+
+/// Content decoded from a sample.
+pub enum DecodedSample {
+    Subtitles(SubtitleSample),
+    NalUnits(NalSample),
+    Obus(ObuSample),
+    AudioFrames(AudioSample),
+}
+
+impl DecodedSample {
+    pub fn get_values(&self) -> Vec<(&'static str, BoxValue)> {
+        match self {
+            DecodedSample::Subtitles(sample) => sample.get_values(),
+            DecodedSample::NalUnits(sample) => sample.get_values(),
+            DecodedSample::Obus(sample) => sample.get_values(),
+            DecodedSample::AudioFrames(sample) => sample.get_values(),
+        }
+    }
+}
+
+/// Read the data of a sample from the file it was located in.
+pub fn read_sample<R: Read + Seek>(
+    reader: &mut R,
+    location: &SampleLocation
+) -> Result<Vec<u8>, std::io::Error> {
+    reader.seek(SeekFrom::Start(location.offset))?;
+    // The size is not trusted enough to allocate it upfront
+    let mut data = vec![];
+    reader.take(location.size as u64).read_to_end(&mut data)?;
+    if data.len() < location.size as usize {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            "sample goes beyond the end of the file"
+        ));
+    }
+    Ok(data)
+}
+
+/// Split data made of consecutive ISOBMFF boxes into the type and the content
+/// of each of these boxes.
+/// A truncated last box is returned with its available content.
+fn split_boxes(data: &[u8]) -> Vec<(String, &[u8])> {
+    let mut boxes = vec![];
+    let mut remaining = data;
+    while remaining.len() >= 8 {
+        let size = u32::from_be_bytes([remaining[0], remaining[1], remaining[2], remaining[3]]);
+        let box_type = remaining[4..8].iter().map(|b| *b as char).collect();
+        let size = (size as usize).clamp(8, remaining.len());
+        boxes.push((box_type, &remaining[8..size]));
+        remaining = &remaining[size..];
+    }
+    boxes
+}
+
+#[cfg(test)]
+mod test_utils {
+    use std::io::Cursor;
+    use super::{DecodedSample, PayloadKind, SampleDecoder};
+    use super::super::boxes::{BoxValue, get_track_samples, parse_isobmff};
+
+    /// Decode the samples of each track of the given fixture whose content
+    /// can be decoded as the given kind.
+    pub fn decode_fixture_samples(file_name: &str, kind: PayloadKind) -> Vec<Vec<DecodedSample>> {
+        let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), file_name);
+        let input = std::fs::read(&path).unwrap();
+        let data = parse_isobmff(Cursor::new(&input)).unwrap();
+        get_track_samples(&data).into_iter()
+            .filter_map(|track| {
+                let mut decoder = SampleDecoder::for_track(kind, &track)?;
+                Some(track.samples.iter()
+                    .map(|s| {
+                        let start = s.offset as usize;
+                        decoder.decode(&input[start..start + s.size as usize]).unwrap()
+                    })
+                    .collect())
+            })
+            .collect()
+    }
+
+    /// Describe each of the given values as a `name=value` string, the values
+    /// of a collection being prefixed by its name and the index of their
+    /// entry, e.g. `nal_units[1].type=5`.
+    pub fn describe_values(values: &[(&str, BoxValue)]) -> Vec<String> {
+        values.iter().flat_map(|(name, value)| match value {
+            BoxValue::Collection(entries) => entries.iter()
+                .enumerate()
+                .flat_map(|(i, entry)| describe_values(entry)
+                    .into_iter()
+                    .map(move |description| format!("{}[{}].{}", name, i, description)))
+                .collect(),
+            _ => vec![format!("{}={}", name, describe_value(value))],
+        }).collect()
+    }
+
+    fn describe_value(value: &BoxValue) -> String {
+        match value {
+            BoxValue::UInt8(v) => v.to_string(),
+            BoxValue::UInt16(v) => v.to_string(),
+            BoxValue::UInt32(v) => v.to_string(),
+            BoxValue::UInt64(v) => v.to_string(),
+            BoxValue::Int16(v) => v.to_string(),
+            BoxValue::Int32(v) => v.to_string(),
+            BoxValue::Int64(v) => v.to_string(),
+            BoxValue::Bool(v) => v.to_string(),
+            BoxValue::Scaled(v, divider) => format!("{}/{}", v, divider),
+            BoxValue::Hex(data) => data.iter().map(|b| format!("{:02x}", b)).collect(),
+            BoxValue::Utf8(s) => s.to_string(),
+            BoxValue::Utf8Arr(strings) => strings.join(","),
+            _ => panic!("unexpected value type"),
+        }
+    }
+}
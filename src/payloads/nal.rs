@@ -0,0 +1,275 @@
+use super::super::boxes::BoxValue;
+use super::bits::to_rbsp;
+use super::sei::{PicTimingState, SeiMessage};
+use super::sps::{AvcTimingInfo, HevcTimingInfo};
+
+/// Video coding whose samples are made of length-prefixed NAL units.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum NalFormat {
+    Avc,
+    Hevc,
+    Vvc,
+}
+
+impl NalFormat {
+    /// Returns the format used by the samples of a track whose sample entry
+    /// is `codec`. `None` if they are not made of NAL units.
+    pub fn from_codec(codec: &str) -> Option<Self> {
+        match codec {
+            "avc1" | "avc2" | "avc3" | "avc4" | "dva1" | "dvav" => Some(NalFormat::Avc),
+            "hvc1" | "hev1" | "hvc2" | "hev2" | "dvh1" | "dvhe" => Some(NalFormat::Hevc),
+            "vvc1" | "vvi1" => Some(NalFormat::Vvc),
+            _ => None,
+        }
+    }
+
+    /// Size in bytes of the NAL unit header.
+    fn header_size(self) -> usize {
+        match self {
+            NalFormat::Avc => 1,
+            NalFormat::Hevc | NalFormat::Vvc => 2,
+        }
+    }
+}
+
+/// What a NAL unit type carries, as far as the sample summary is concerned.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum NalCategory {
+    /// Slice of a picture at which decoding can start (IDR, CRA, BLA, GDR).
+    RandomAccessSlice,
+    Slice,
+    ParameterSet,
+    Sei,
+    AccessUnitDelimiter,
+    Other,
+}
+
+/// Returns the name and category of a NAL unit type.
+fn describe_nal_type(format: NalFormat, nal_type: u8) -> (&'static str, NalCategory) {
+    use NalCategory::*;
+    match (format, nal_type) {
+        (NalFormat::Avc, 1) => ("non-IDR slice", Slice),
+        (NalFormat::Avc, 2) => ("slice data partition A", Slice),
+        (NalFormat::Avc, 3) => ("slice data partition B", Slice),
+        (NalFormat::Avc, 4) => ("slice data partition C", Slice),
+        (NalFormat::Avc, 5) => ("IDR slice", RandomAccessSlice),
+        (NalFormat::Avc, 6) => ("SEI", Sei),
+        (NalFormat::Avc, 7) => ("SPS", ParameterSet),
+        (NalFormat::Avc, 8) => ("PPS", ParameterSet),
+        (NalFormat::Avc, 9) => ("AUD", AccessUnitDelimiter),
+        (NalFormat::Avc, 10) => ("end of sequence", Other),
+        (NalFormat::Avc, 11) => ("end of stream", Other),
+        (NalFormat::Avc, 12) => ("filler data", Other),
+        (NalFormat::Avc, 13) => ("SPS extension", ParameterSet),
+        (NalFormat::Avc, 14) => ("prefix NAL unit", Other),
+        (NalFormat::Avc, 15) => ("subset SPS", ParameterSet),
+        (NalFormat::Avc, 19) => ("auxiliary slice", Slice),
+        (NalFormat::Avc, 20) => ("slice extension", Slice),
+
+        (NalFormat::Hevc, 0) => ("TRAIL_N", Slice),
+        (NalFormat::Hevc, 1) => ("TRAIL_R", Slice),
+        (NalFormat::Hevc, 2) => ("TSA_N", Slice),
+        (NalFormat::Hevc, 3) => ("TSA_R", Slice),
+        (NalFormat::Hevc, 4) => ("STSA_N", Slice),
+        (NalFormat::Hevc, 5) => ("STSA_R", Slice),
+        (NalFormat::Hevc, 6) => ("RADL_N", Slice),
+        (NalFormat::Hevc, 7) => ("RADL_R", Slice),
+        (NalFormat::Hevc, 8) => ("RASL_N", Slice),
+        (NalFormat::Hevc, 9) => ("RASL_R", Slice),
+        (NalFormat::Hevc, 16) => ("BLA_W_LP", RandomAccessSlice),
+        (NalFormat::Hevc, 17) => ("BLA_W_RADL", RandomAccessSlice),
+        (NalFormat::Hevc, 18) => ("BLA_N_LP", RandomAccessSlice),
+        (NalFormat::Hevc, 19) => ("IDR_W_RADL", RandomAccessSlice),
+        (NalFormat::Hevc, 20) => ("IDR_N_LP", RandomAccessSlice),
+        (NalFormat::Hevc, 21) => ("CRA", RandomAccessSlice),
+        (NalFormat::Hevc, 32) => ("VPS", ParameterSet),
+        (NalFormat::Hevc, 33) => ("SPS", ParameterSet),
+        (NalFormat::Hevc, 34) => ("PPS", ParameterSet),
+        (NalFormat::Hevc, 35) => ("AUD", AccessUnitDelimiter),
+        (NalFormat::Hevc, 36) => ("end of sequence", Other),
+        (NalFormat::Hevc, 37) => ("end of bitstream", Other),
+        (NalFormat::Hevc, 38) => ("filler data", Other),
+        (NalFormat::Hevc, 39) => ("prefix SEI", Sei),
+        (NalFormat::Hevc, 40) => ("suffix SEI", Sei),
+
+        (NalFormat::Vvc, 0) => ("TRAIL", Slice),
+        (NalFormat::Vvc, 1) => ("STSA", Slice),
+        (NalFormat::Vvc, 2) => ("RADL", Slice),
+        (NalFormat::Vvc, 3) => ("RASL", Slice),
+        (NalFormat::Vvc, 7) => ("IDR_W_RADL", RandomAccessSlice),
+        (NalFormat::Vvc, 8) => ("IDR_N_LP", RandomAccessSlice),
+        (NalFormat::Vvc, 9) => ("CRA", RandomAccessSlice),
+        (NalFormat::Vvc, 10) => ("GDR", RandomAccessSlice),
+        (NalFormat::Vvc, 12) => ("OPI", ParameterSet),
+        (NalFormat::Vvc, 13) => ("DCI", ParameterSet),
+        (NalFormat::Vvc, 14) => ("VPS", ParameterSet),
+        (NalFormat::Vvc, 15) => ("SPS", ParameterSet),
+        (NalFormat::Vvc, 16) => ("PPS", ParameterSet),
+        (NalFormat::Vvc, 17) => ("prefix APS", ParameterSet),
+        (NalFormat::Vvc, 18) => ("suffix APS", ParameterSet),
+        (NalFormat::Vvc, 19) => ("picture header", Other),
+        (NalFormat::Vvc, 20) => ("AUD", AccessUnitDelimiter),
+        (NalFormat::Vvc, 21) => ("end of sequence", Other),
+        (NalFormat::Vvc, 22) => ("end of bitstream", Other),
+        (NalFormat::Vvc, 23) => ("prefix SEI", Sei),
+        (NalFormat::Vvc, 24) => ("suffix SEI", Sei),
+        (NalFormat::Vvc, 25) => ("filler data", Other),
+
+        _ => ("reserved or unspecified", Other),
+    }
+}
+
+/// Information carried by parameter sets, which is needed to decode the
+/// NAL units of the following samples.
+#[derive(Default)]
+pub struct NalDecodingState {
+    pic_timing: PicTimingState,
+}
+
+impl NalDecodingState {
+    /// Update the state from a NAL unit, header included, found either in a
+    /// sample or in the decoder configuration.
+    pub fn process_nal_unit(&mut self, format: NalFormat, nal_data: &[u8]) {
+        if format == NalFormat::Avc && nal_data.first().map(|b| b & 0x1F) == Some(7) {
+            if let Some(timing) = AvcTimingInfo::parse(&to_rbsp(&nal_data[1..])) {
+                self.pic_timing.avc_timing = Some(timing);
+            }
+        }
+        if format == NalFormat::Hevc && nal_data.len() > 2 && (nal_data[0] >> 1) & 0x3F == 33 {
+            if let Some(timing) = HevcTimingInfo::parse(&to_rbsp(&nal_data[2..])) {
+                self.pic_timing.hevc_timing = Some(timing);
+            }
+        }
+    }
+}
+
+pub struct NalUnit {
+    /// Size of the NAL unit, header included but not its length field.
+    size: u32,
+    nal_type: u8,
+
+    /// `nal_ref_idc` of AVC NAL units.
+    nal_ref_idc: Option<u8>,
+
+    /// `nuh_layer_id` of HEVC and VVC NAL units.
+    layer_id: Option<u8>,
+
+    /// `TemporalId` of HEVC and VVC NAL units.
+    temporal_id: Option<u8>,
+
+    /// Messages of SEI NAL units.
+    sei_messages: Vec<SeiMessage>,
+}
+
+/// Breakdown of a sample made of length-prefixed NAL units.
+pub struct NalSample {
+    format: NalFormat,
+    nal_units: Vec<NalUnit>,
+
+    /// Number of bytes at the end of the sample which could not be read as
+    /// a NAL unit, because of an announced length going beyond the sample.
+    truncated_bytes: usize,
+}
+
+impl NalSample {
+    pub fn decode(
+        format: NalFormat,
+        nal_length_size: u8,
+        data: &[u8],
+        state: &mut NalDecodingState
+    ) -> Self {
+        let length_size = nal_length_size as usize;
+        let mut nal_units = vec![];
+        let mut remaining = data;
+        while remaining.len() >= length_size && !remaining.is_empty() {
+            let size = remaining[..length_size]
+                .iter()
+                .fold(0usize, |acc, b| (acc << 8) | *b as usize);
+            let nal_data = match remaining.get(length_size..length_size + size) {
+                Some(nal_data) if nal_data.len() >= format.header_size() => nal_data,
+                _ => break,
+            };
+            let (nal_type, nal_ref_idc, layer_id, temporal_id) = match format {
+                NalFormat::Avc => (nal_data[0] & 0x1F, Some((nal_data[0] >> 5) & 0x03), None, None),
+                NalFormat::Hevc => (
+                    (nal_data[0] >> 1) & 0x3F,
+                    None,
+                    Some(((nal_data[0] & 0x01) << 5) | (nal_data[1] >> 3)),
+                    Some((nal_data[1] & 0x07).saturating_sub(1)),
+                ),
+                NalFormat::Vvc => (
+                    nal_data[1] >> 3,
+                    None,
+                    Some(nal_data[0] & 0x3F),
+                    Some((nal_data[1] & 0x07).saturating_sub(1)),
+                ),
+            };
+            state.process_nal_unit(format, nal_data);
+            let sei_messages = if describe_nal_type(format, nal_type).1 == NalCategory::Sei {
+                SeiMessage::parse_all(
+                    &to_rbsp(&nal_data[format.header_size()..]),
+                    format,
+                    temporal_id.unwrap_or(0),
+                    &mut state.pic_timing)
+            } else {
+                vec![]
+            };
+            nal_units.push(NalUnit {
+                size: size as u32,
+                nal_type,
+                nal_ref_idc,
+                layer_id,
+                temporal_id,
+                sei_messages,
+            });
+            remaining = &remaining[length_size + size..];
+        }
+        Self { format, nal_units, truncated_bytes: remaining.len() }
+    }
+
+    fn contains(&self, category: NalCategory) -> bool {
+        self.nal_units
+            .iter()
+            .any(|nal| describe_nal_type(self.format, nal.nal_type).1 == category)
+    }
+
+    pub fn get_values(&self) -> Vec<(&'static str, BoxValue)> {
+        let mut values = vec![
+            ("nal_unit_count", BoxValue::from(self.nal_units.len() as u32)),
+            ("random_access", BoxValue::from(self.contains(NalCategory::RandomAccessSlice))),
+            ("parameter_sets", BoxValue::from(self.contains(NalCategory::ParameterSet))),
+            ("sei", BoxValue::from(self.contains(NalCategory::Sei))),
+            ("aud", BoxValue::from(self.contains(NalCategory::AccessUnitDelimiter))),
+            ("captions", BoxValue::from(self.nal_units
+                    .iter()
+                    .any(|nal| nal.sei_messages.iter().any(|sei| sei.has_captions())))),
+        ];
+        if self.truncated_bytes > 0 {
+            values.push(("truncated_bytes", BoxValue::from(self.truncated_bytes as u64)));
+        }
+        values.push(("nal_units", BoxValue::Collection(
+                    self.nal_units.iter().map(|nal| {
+                        let mut nal_values = vec![
+                            ("type", BoxValue::from(nal.nal_type)),
+                            ("type_name", BoxValue::from(describe_nal_type(self.format, nal.nal_type).0)),
+                            ("size", BoxValue::from(nal.size)),
+                        ];
+                        if let Some(nal_ref_idc) = nal.nal_ref_idc {
+                            nal_values.push(("nal_ref_idc", BoxValue::from(nal_ref_idc)));
+                        }
+                        if let Some(layer_id) = nal.layer_id {
+                            nal_values.push(("layer_id", BoxValue::from(layer_id)));
+                        }
+                        if let Some(temporal_id) = nal.temporal_id {
+                            nal_values.push(("temporal_id", BoxValue::from(temporal_id)));
+                        }
+                        if !nal.sei_messages.is_empty() {
+                            nal_values.push(("sei_messages", BoxValue::Collection(
+                                        nal.sei_messages.iter().map(|sei| sei.get_values()).collect())));
+                        }
+                        nal_values
+                    }).collect()
+        )));
+        values
+    }
+}
@@ -0,0 +1,346 @@
+use super::super::boxes::BoxValue;
+use super::bits::BitReader;
+
+/// Returns the name of an OBU type.
+fn get_obu_type_name(obu_type: u8) -> &'static str {
+    match obu_type {
+        1 => "OBU_SEQUENCE_HEADER",
+        2 => "OBU_TEMPORAL_DELIMITER",
+        3 => "OBU_FRAME_HEADER",
+        4 => "OBU_TILE_GROUP",
+        5 => "OBU_METADATA",
+        6 => "OBU_FRAME",
+        7 => "OBU_REDUNDANT_FRAME_HEADER",
+        8 => "OBU_TILE_LIST",
+        15 => "OBU_PADDING",
+        _ => "reserved",
+    }
+}
+
+/// Returns the name of a `frame_type`.
+fn get_frame_type_name(frame_type: u8) -> &'static str {
+    match frame_type {
+        0 => "KEY_FRAME",
+        1 => "INTER_FRAME",
+        2 => "INTRA_ONLY_FRAME",
+        _ => "SWITCH_FRAME",
+    }
+}
+
+/// Read an unsigned LEB128-coded value, returning it with the number of
+/// bytes it is coded on.
+fn read_leb128(data: &[u8]) -> Option<(u64, usize)> {
+    let mut value = 0u64;
+    for (i, byte) in data.iter().take(8).enumerate() {
+        value |= ((byte & 0x7F) as u64) << (i * 7);
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+/// Information carried by sequence headers, which is needed to decode the
+/// frame headers of the following samples.
+#[derive(Default)]
+pub struct ObuDecodingState {
+    reduced_still_picture_header: bool,
+}
+
+pub struct SequenceHeader {
+    seq_profile: u8,
+    still_picture: bool,
+    reduced_still_picture_header: bool,
+    operating_points_cnt: u8,
+    max_frame_width: u32,
+    max_frame_height: u32,
+}
+
+impl SequenceHeader {
+    /// Parse the start of a sequence header OBU payload, up to the maximum
+    /// frame dimensions.
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut reader = BitReader::new(data);
+        let seq_profile = reader.read_bits(3)? as u8;
+        let still_picture = reader.read_bit()?;
+        let reduced_still_picture_header = reader.read_bit()?;
+        let mut operating_points_cnt = 1;
+        if reduced_still_picture_header {
+            // seq_level_idx[0]
+            reader.skip_bits(5)?;
+        } else {
+            let mut buffer_delay_length = 0;
+            let timing_info_present = reader.read_bit()?;
+            let mut decoder_model_info_present = false;
+            if timing_info_present {
+                reader.skip_bits(64)?;
+                if reader.read_bit()? {
+                    // num_ticks_per_picture_minus_1
+                    let mut leading_zeros = 0;
+                    while !reader.read_bit()? {
+                        leading_zeros += 1;
+                        if leading_zeros >= 32 {
+                            break;
+                        }
+                    }
+                    if leading_zeros < 32 {
+                        reader.skip_bits(leading_zeros)?;
+                    }
+                }
+                decoder_model_info_present = reader.read_bit()?;
+                if decoder_model_info_present {
+                    buffer_delay_length = reader.read_bits(5)? as usize + 1;
+                    reader.skip_bits(42)?;
+                }
+            }
+            let initial_display_delay_present = reader.read_bit()?;
+            operating_points_cnt = reader.read_bits(5)? as u8 + 1;
+            for _ in 0..operating_points_cnt {
+                reader.skip_bits(12)?;
+                if reader.read_bits(5)? > 7 {
+                    reader.skip_bits(1)?;
+                }
+                if decoder_model_info_present && reader.read_bit()? {
+                    reader.skip_bits(buffer_delay_length * 2 + 1)?;
+                }
+                if initial_display_delay_present && reader.read_bit()? {
+                    reader.skip_bits(4)?;
+                }
+            }
+        }
+        let frame_width_bits = reader.read_bits(4)? as u8 + 1;
+        let frame_height_bits = reader.read_bits(4)? as u8 + 1;
+        let max_frame_width = reader.read_bits(frame_width_bits)? as u32 + 1;
+        let max_frame_height = reader.read_bits(frame_height_bits)? as u32 + 1;
+        Some(Self {
+            seq_profile,
+            still_picture,
+            reduced_still_picture_header,
+            operating_points_cnt,
+            max_frame_width,
+            max_frame_height,
+        })
+    }
+}
+
+/// Start of the uncompressed header of a frame.
+pub struct FrameHeader {
+    show_existing_frame: bool,
+
+    /// `None` for a `show_existing_frame`, whose type is the one of the
+    /// frame shown.
+    frame_type: Option<u8>,
+    show_frame: bool,
+}
+
+impl FrameHeader {
+    fn parse(data: &[u8], state: &ObuDecodingState) -> Option<Self> {
+        if state.reduced_still_picture_header {
+            return Some(Self { show_existing_frame: false, frame_type: Some(0), show_frame: true });
+        }
+        let mut reader = BitReader::new(data);
+        if reader.read_bit()? {
+            return Some(Self { show_existing_frame: true, frame_type: None, show_frame: true });
+        }
+        let frame_type = reader.read_bits(2)? as u8;
+        let show_frame = reader.read_bit()?;
+        Some(Self { show_existing_frame: false, frame_type: Some(frame_type), show_frame })
+    }
+
+    fn is_shown_key_frame(&self) -> bool {
+        self.frame_type == Some(0) && self.show_frame
+    }
+}
+
+pub struct Obu {
+    obu_type: u8,
+
+    /// Size of the OBU, header included.
+    size: u32,
+    temporal_id: Option<u8>,
+    spatial_id: Option<u8>,
+    sequence_header: Option<SequenceHeader>,
+    frame_header: Option<FrameHeader>,
+}
+
+/// Breakdown of a sample made of AV1 OBUs.
+pub struct ObuSample {
+    obus: Vec<Obu>,
+
+    /// Number of bytes at the end of the sample which could not be read as
+    /// an OBU, because of a malformed header or of an announced size going
+    /// beyond the sample.
+    truncated_bytes: usize,
+}
+
+impl ObuSample {
+    pub fn decode(data: &[u8], state: &mut ObuDecodingState) -> Self {
+        let mut obus = vec![];
+        let mut remaining = data;
+        while !remaining.is_empty() {
+            let header = remaining[0];
+            let obu_type = (header >> 3) & 0x0F;
+            let has_extension = header & 0x04 != 0;
+            let has_size_field = header & 0x02 != 0;
+            let mut header_size = 1;
+            let (temporal_id, spatial_id) = if has_extension {
+                match remaining.get(1) {
+                    Some(extension) => {
+                        header_size += 1;
+                        (Some(extension >> 5), Some((extension >> 3) & 0x03))
+                    },
+                    None => break,
+                }
+            } else {
+                (None, None)
+            };
+            let payload_size = if has_size_field {
+                match read_leb128(&remaining[header_size..]) {
+                    Some((size, leb128_size)) => {
+                        header_size += leb128_size;
+                        size as usize
+                    },
+                    None => break,
+                }
+            } else {
+                remaining.len() - header_size
+            };
+            let payload = match remaining.get(header_size..header_size + payload_size) {
+                Some(payload) => payload,
+                None => break,
+            };
+            let sequence_header = if obu_type == 1 {
+                SequenceHeader::parse(payload)
+            } else {
+                None
+            };
+            if let Some(sequence_header) = &sequence_header {
+                state.reduced_still_picture_header = sequence_header.reduced_still_picture_header;
+            }
+            let frame_header = if obu_type == 3 || obu_type == 6 {
+                FrameHeader::parse(payload, state)
+            } else {
+                None
+            };
+            obus.push(Obu {
+                obu_type,
+                size: (header_size + payload_size) as u32,
+                temporal_id,
+                spatial_id,
+                sequence_header,
+                frame_header,
+            });
+            remaining = &remaining[header_size + payload_size..];
+        }
+        Self { obus, truncated_bytes: remaining.len() }
+    }
+
+    pub fn get_values(&self) -> Vec<(&'static str, BoxValue)> {
+        let mut values = vec![
+            ("obu_count", BoxValue::from(self.obus.len() as u32)),
+            ("key_frame", BoxValue::from(self.obus
+                    .iter()
+                    .any(|obu| obu.frame_header.as_ref().is_some_and(|h| h.is_shown_key_frame())))),
+            ("sequence_header", BoxValue::from(self.obus.iter().any(|obu| obu.obu_type == 1))),
+        ];
+        if self.truncated_bytes > 0 {
+            values.push(("truncated_bytes", BoxValue::from(self.truncated_bytes as u64)));
+        }
+        values.push(("obus", BoxValue::Collection(
+                    self.obus.iter().map(|obu| {
+                        let mut obu_values = vec![
+                            ("type", BoxValue::from(obu.obu_type)),
+                            ("type_name", BoxValue::from(get_obu_type_name(obu.obu_type))),
+                            ("size", BoxValue::from(obu.size)),
+                        ];
+                        if let Some(temporal_id) = obu.temporal_id {
+                            obu_values.push(("temporal_id", BoxValue::from(temporal_id)));
+                        }
+                        if let Some(spatial_id) = obu.spatial_id {
+                            obu_values.push(("spatial_id", BoxValue::from(spatial_id)));
+                        }
+                        if let Some(header) = &obu.sequence_header {
+                            obu_values.push(("seq_profile", BoxValue::from(header.seq_profile)));
+                            obu_values.push(("still_picture", BoxValue::from(header.still_picture)));
+                            obu_values.push(("reduced_still_picture_header",
+                                BoxValue::from(header.reduced_still_picture_header)));
+                            obu_values.push(("operating_points_cnt",
+                                BoxValue::from(header.operating_points_cnt)));
+                            obu_values.push(("max_frame_width", BoxValue::from(header.max_frame_width)));
+                            obu_values.push(("max_frame_height", BoxValue::from(header.max_frame_height)));
+                        }
+                        if let Some(header) = &obu.frame_header {
+                            obu_values.push(("show_existing_frame",
+                                BoxValue::from(header.show_existing_frame)));
+                            if let Some(frame_type) = header.frame_type {
+                                obu_values.push(("frame_type", BoxValue::from(frame_type)));
+                                obu_values.push(("frame_type_name",
+                                    BoxValue::from(get_frame_type_name(frame_type))));
+                            }
+                            obu_values.push(("show_frame", BoxValue::from(header.show_frame)));
+                        }
+                        obu_values
+                    }).collect()
+        )));
+        values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ObuDecodingState, ObuSample};
+    use super::super::{DecodedSample, PayloadKind};
+    use super::super::test_utils::decode_fixture_samples;
+
+    /// Type, size, and temporal and spatial IDs of each OBU of the sample.
+    fn describe_obus(sample: &ObuSample) -> Vec<(u8, u32, Option<u8>, Option<u8>)> {
+        sample.obus.iter()
+            .map(|obu| (obu.obu_type, obu.size, obu.temporal_id, obu.spatial_id))
+            .collect()
+    }
+
+    #[test]
+    fn decode_av1_samples() {
+        let tracks = decode_fixture_samples("av1.mp4", PayloadKind::Obus);
+        let samples: Vec<&ObuSample> = tracks[0].iter()
+            .map(|decoded| match decoded {
+                DecodedSample::Obus(sample) => sample,
+                _ => panic!("unexpected decoded sample"),
+            })
+            .collect();
+        assert_eq!(samples.len(), 3);
+
+        assert_eq!(describe_obus(samples[0]), vec![(2, 2, None, None), (1, 10, None, None), (6, 5, None, None)]);
+        let sequence_header = samples[0].obus[1].sequence_header.as_ref().unwrap();
+        assert_eq!(sequence_header.seq_profile, 0);
+        assert!(!sequence_header.still_picture);
+        assert!(!sequence_header.reduced_still_picture_header);
+        assert_eq!(sequence_header.operating_points_cnt, 1);
+        assert_eq!((sequence_header.max_frame_width, sequence_header.max_frame_height), (1920, 540));
+        let frame_header = samples[0].obus[2].frame_header.as_ref().unwrap();
+        assert_eq!(frame_header.frame_type, Some(0));
+        assert!(frame_header.is_shown_key_frame());
+
+        assert_eq!(describe_obus(samples[1]), vec![(2, 2, None, None), (6, 5, Some(1), Some(0))]);
+        let frame_header = samples[1].obus[1].frame_header.as_ref().unwrap();
+        assert_eq!(frame_header.frame_type, Some(1));
+        assert!(!frame_header.is_shown_key_frame());
+
+        assert_eq!(describe_obus(samples[2]), vec![(2, 2, None, None), (3, 3, None, None)]);
+        let frame_header = samples[2].obus[1].frame_header.as_ref().unwrap();
+        assert!(frame_header.show_existing_frame);
+        assert_eq!(frame_header.frame_type, None);
+
+        assert!(samples.iter().all(|sample| sample.truncated_bytes == 0));
+    }
+
+    #[test]
+    fn stop_at_truncated_obu() {
+        // A temporal delimiter, then a padding OBU announcing 5 bytes of
+        // payload where only 2 remain
+        let data = [0x12, 0x00, 0x7A, 0x05, 0xAA, 0xBB];
+        let sample = ObuSample::decode(&data, &mut ObuDecodingState::default());
+        assert_eq!(describe_obus(&sample), vec![(2, 2, None, None)]);
+        assert_eq!(sample.truncated_bytes, 4);
+    }
+}
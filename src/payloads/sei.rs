@@ -0,0 +1,730 @@
+use super::super::boxes::BoxValue;
+use super::bits::BitReader;
+use super::nal::NalFormat;
+use super::sps::{AvcTimingInfo, HevcTimingInfo};
+
+/// Fields of a VVC `buffering_period` SEI message needed to decode the
+/// `pic_timing` messages following it.
+#[derive(Clone, Copy)]
+pub struct VvcBufferingPeriod {
+    cpb_removal_delay_length: u8,
+    dpb_output_delay_length: u8,
+    max_sublayers_minus1: u8,
+
+    /// Length of `pt_cpb_removal_delay_delta_idx`, when `pic_timing`
+    /// messages can signal removal delays as deltas.
+    cpb_removal_delay_delta_idx_length: Option<u8>,
+}
+
+impl VvcBufferingPeriod {
+    /// `None` if the message is truncated or malformed.
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut reader = BitReader::new(data);
+        // bp_nal_hrd_params_present_flag and bp_vcl_hrd_params_present_flag
+        reader.skip_bits(2)?;
+        let initial_cpb_removal_delay_length = reader.read_bits(5)? as usize + 1;
+        let cpb_removal_delay_length = reader.read_bits(5)? as u8 + 1;
+        let dpb_output_delay_length = reader.read_bits(5)? as u8 + 1;
+        if reader.read_bit()? {
+            // decoding unit delay lengths and flags
+            reader.skip_bits(12)?;
+        }
+        // bp_concatenation_flag
+        reader.read_bit()?;
+        if reader.read_bit()? {
+            reader.skip_bits(initial_cpb_removal_delay_length)?;
+        }
+        reader.skip_bits(cpb_removal_delay_length as usize)?;
+        let max_sublayers_minus1 = reader.read_bits(3)? as u8;
+        let mut cpb_removal_delay_delta_idx_length = None;
+        if max_sublayers_minus1 > 0 && reader.read_bit()? {
+            let num_deltas = reader.read_ue()?.checked_add(1)?;
+            // Ceil(Log2(num_deltas))
+            cpb_removal_delay_delta_idx_length = Some(32 - (num_deltas - 1).leading_zeros() as u8);
+        }
+        Some(Self {
+            cpb_removal_delay_length,
+            dpb_output_delay_length,
+            max_sublayers_minus1,
+            cpb_removal_delay_delta_idx_length,
+        })
+    }
+}
+
+/// Information from previous NAL units which is needed to decode
+/// `pic_timing` SEI messages.
+#[derive(Default)]
+pub struct PicTimingState {
+    /// Timing information of the last AVC sequence parameter set.
+    pub avc_timing: Option<AvcTimingInfo>,
+
+    /// Timing information of the last HEVC sequence parameter set.
+    pub hevc_timing: Option<HevcTimingInfo>,
+
+    /// Last VVC `buffering_period` SEI message.
+    pub vvc_buffering_period: Option<VvcBufferingPeriod>,
+}
+
+/// Clock timestamp of a `pic_timing` or `time_code` SEI message.
+pub struct ClockTimestamp {
+    counting_type: u8,
+    discontinuity: bool,
+    cnt_dropped: bool,
+
+    /// Time code, formatted as "HH:MM:SS:FF" (or "HH:MM:SS;FF" for
+    /// drop-frame counting). Components absent from the message are
+    /// replaced by "--".
+    timecode: String,
+    time_offset: i64,
+}
+
+impl ClockTimestamp {
+    /// Read a clock timestamp from its `counting_type` field.
+    /// `n_frames_length` is 8 bits for AVC and 9 bits for HEVC and VVC.
+    fn read(
+        reader: &mut BitReader,
+        n_frames_length: u8,
+        time_offset_length: Option<u8>
+    ) -> Option<Self> {
+        let counting_type = reader.read_bits(5)? as u8;
+        let full_timestamp = reader.read_bit()?;
+        let discontinuity = reader.read_bit()?;
+        let cnt_dropped = reader.read_bit()?;
+        let n_frames = reader.read_bits(n_frames_length)?;
+        let (mut seconds, mut minutes, mut hours) = (None, None, None);
+        if full_timestamp {
+            seconds = Some(reader.read_bits(6)?);
+            minutes = Some(reader.read_bits(6)?);
+            hours = Some(reader.read_bits(5)?);
+        } else if reader.read_bit()? {
+            seconds = Some(reader.read_bits(6)?);
+            if reader.read_bit()? {
+                minutes = Some(reader.read_bits(6)?);
+                if reader.read_bit()? {
+                    hours = Some(reader.read_bits(5)?);
+                }
+            }
+        }
+        // HEVC and VVC time codes carry the length of the offset themselves
+        let time_offset_length = match time_offset_length {
+            Some(length) => length,
+            None => reader.read_bits(5)? as u8,
+        };
+        let time_offset = if time_offset_length > 0 {
+            let value = reader.read_bits(time_offset_length)?;
+            let shift = 64 - time_offset_length as u32;
+            ((value << shift) as i64) >> shift
+        } else {
+            0
+        };
+        let format_component = |val: Option<u64>| val.map_or("--".to_owned(), |v| format!("{:02}", v));
+        let timecode = format!("{}:{}:{}{}{:02}",
+            format_component(hours),
+            format_component(minutes),
+            format_component(seconds),
+            if cnt_dropped { ";" } else { ":" },
+            n_frames);
+        Some(Self { counting_type, discontinuity, cnt_dropped, timecode, time_offset })
+    }
+
+    fn get_values(&self) -> Vec<(&'static str, BoxValue)> {
+        vec![
+            ("timecode", BoxValue::from(self.timecode.as_str())),
+            ("counting_type", BoxValue::from(self.counting_type)),
+            ("discontinuity", BoxValue::from(self.discontinuity)),
+            ("cnt_dropped", BoxValue::from(self.cnt_dropped)),
+            ("time_offset", BoxValue::from(self.time_offset)),
+        ]
+    }
+}
+
+/// Closed captions carried through ATSC A/53 `cc_data`.
+pub struct CaptionData {
+    cc_count: u8,
+
+    /// Valid CEA-608 byte pairs, for field 1 then field 2.
+    cea608_pairs: [u32; 2],
+
+    /// Printable characters of the valid CEA-608 byte pairs, for field 1
+    /// then field 2. Control codes are ignored.
+    cea608_text: [String; 2],
+
+    /// Number of valid CEA-708 (DTVCC) byte pairs.
+    cea708_pairs: u32,
+}
+
+impl CaptionData {
+    /// Read the `cc_data` structure, starting at its `process_em_data_flag`.
+    fn read(data: &[u8]) -> Option<Self> {
+        let cc_count = *data.first()? & 0x1F;
+        let mut cea608_pairs = [0, 0];
+        let mut cea608_text = [String::new(), String::new()];
+        let mut cea708_pairs = 0;
+        // skip em_data
+        for triplet in data.get(2..)?.chunks_exact(3).take(cc_count as usize) {
+            let cc_valid = triplet[0] & 0x04 != 0;
+            let cc_type = triplet[0] & 0x03;
+            if !cc_valid {
+                continue;
+            }
+            match cc_type {
+                0 | 1 => {
+                    let field = cc_type as usize;
+                    cea608_pairs[field] += 1;
+                    // strip the odd parity bit, then only keep basic characters
+                    for byte in [triplet[1] & 0x7F, triplet[2] & 0x7F] {
+                        if (0x20..0x7F).contains(&byte) {
+                            cea608_text[field].push(byte as char);
+                        }
+                    }
+                },
+                _ => cea708_pairs += 1,
+            }
+        }
+        Some(Self { cc_count, cea608_pairs, cea608_text, cea708_pairs })
+    }
+}
+
+/// A decoded SEI message.
+pub enum SeiPayload {
+    PicTiming {
+        /// For HEVC and VVC, the coded `cpb_removal_delay_minus1` plus 1.
+        cpb_removal_delay: Option<u64>,
+        dpb_output_delay: Option<u64>,
+        pic_struct: Option<u8>,
+
+        /// `source_scan_type` and `duplicate_flag`, for HEVC only.
+        source_scan_type: Option<u8>,
+        duplicate_flag: Option<bool>,
+
+        /// Clock timestamps, for AVC only. HEVC and VVC carry them in
+        /// `time_code` messages instead.
+        clock_timestamps: Vec<ClockTimestamp>,
+    },
+
+    /// `pic_timing` whose sequence parameter set (for AVC and HEVC) or
+    /// buffering period (for VVC) is not known, which cannot be decoded.
+    /// Contains a note indicating what is missing.
+    UndecodablePicTiming(&'static str),
+
+    /// `user_data_registered_itu_t_t35` carrying ATSC A/53 closed captions.
+    Captions {
+        country_code: u8,
+        provider_code: u16,
+        captions: CaptionData,
+    },
+
+    /// Any other `user_data_registered_itu_t_t35` message.
+    RegisteredUserData {
+        country_code: u8,
+        data: Vec<u8>,
+    },
+
+    UnregisteredUserData {
+        uuid: [u8; 16],
+        data: Vec<u8>,
+
+        /// The data, when it is made of printable characters (like the
+        /// settings written by many encoders).
+        text: Option<String>,
+    },
+
+    MasteringDisplayColourVolume {
+        /// x and y chromaticity coordinates of each primary, in increments
+        /// of 0.00002.
+        display_primaries: [(u16, u16); 3],
+        white_point: (u16, u16),
+
+        /// Luminances, in units of 0.0001 cd/m².
+        max_display_mastering_luminance: u32,
+        min_display_mastering_luminance: u32,
+    },
+
+    ContentLightLevel {
+        max_content_light_level: u16,
+        max_pic_average_light_level: u16,
+    },
+
+    TimeCode(Vec<ClockTimestamp>),
+
+    Other(Vec<u8>),
+}
+
+/// Returns the name of a SEI payload type.
+fn get_sei_payload_type_name(payload_type: u32) -> &'static str {
+    match payload_type {
+        0 => "buffering_period",
+        1 => "pic_timing",
+        2 => "pan_scan_rect",
+        3 => "filler_payload",
+        4 => "user_data_registered_itu_t_t35",
+        5 => "user_data_unregistered",
+        6 => "recovery_point",
+        45 => "frame_packing_arrangement",
+        47 => "display_orientation",
+        128 => "structure_of_pictures_info",
+        129 => "active_parameter_sets",
+        130 => "decoding_unit_info",
+        132 => "decoded_picture_hash",
+        136 => "time_code",
+        137 => "mastering_display_colour_volume",
+        144 => "content_light_level_info",
+        147 => "alternative_transfer_characteristics",
+        148 => "ambient_viewing_environment",
+        _ => "unknown",
+    }
+}
+
+/// Number of clock timestamps in an AVC `pic_timing` message, by `pic_struct`.
+const AVC_NUM_CLOCK_TS: [u8; 9] = [1, 1, 1, 2, 2, 3, 3, 2, 3];
+
+pub struct SeiMessage {
+    payload_type: u32,
+    payload_size: u32,
+    payload: SeiPayload,
+}
+
+impl SeiMessage {
+    /// Parse every SEI message of the raw byte sequence payload of a SEI NAL
+    /// unit, NAL unit header excluded.
+    /// `temporal_id` is the `TemporalId` of the NAL unit, for VVC.
+    /// VVC `buffering_period` messages update `timing` for the following
+    /// `pic_timing` messages.
+    pub fn parse_all(
+        rbsp: &[u8],
+        format: NalFormat,
+        temporal_id: u8,
+        timing: &mut PicTimingState
+    ) -> Vec<Self> {
+        let mut messages = vec![];
+        let mut remaining = rbsp;
+
+        // Stop at the `rbsp_trailing_bits`
+        while remaining.len() > 1 || remaining.first().is_some_and(|b| *b != 0x80) {
+            let mut read_value = || {
+                let mut value = 0u32;
+                loop {
+                    let (byte, rest) = remaining.split_first()?;
+                    remaining = rest;
+                    // a value not fitting in 32 bits ends the parsing
+                    value = value.checked_add(*byte as u32)?;
+                    if *byte != 0xFF {
+                        return Some(value);
+                    }
+                }
+            };
+            let (payload_type, payload_size) = match (read_value(), read_value()) {
+                (Some(payload_type), Some(payload_size)) => (payload_type, payload_size),
+                _ => break,
+            };
+            let size = (payload_size as usize).min(remaining.len());
+            let data = &remaining[..size];
+            remaining = &remaining[size..];
+            if payload_type == 0 && format == NalFormat::Vvc {
+                if let Some(buffering_period) = VvcBufferingPeriod::parse(data) {
+                    timing.vvc_buffering_period = Some(buffering_period);
+                }
+            }
+            let payload = decode_payload(payload_type, data, format, temporal_id, timing)
+                .unwrap_or_else(|| SeiPayload::Other(data.to_vec()));
+            messages.push(SeiMessage { payload_type, payload_size, payload });
+        }
+        messages
+    }
+
+    /// Returns `true` if this message carries CEA-608 or CEA-708 captions.
+    pub fn has_captions(&self) -> bool {
+        matches!(self.payload, SeiPayload::Captions { .. })
+    }
+
+    pub fn get_values(&self) -> Vec<(&'static str, BoxValue)> {
+        let mut values = vec![
+            ("payload_type", BoxValue::from(self.payload_type)),
+            ("payload_type_name", BoxValue::from(get_sei_payload_type_name(self.payload_type))),
+            ("payload_size", BoxValue::from(self.payload_size)),
+        ];
+        match &self.payload {
+            SeiPayload::PicTiming {
+                cpb_removal_delay,
+                dpb_output_delay,
+                pic_struct,
+                source_scan_type,
+                duplicate_flag,
+                clock_timestamps,
+            } => {
+                if let Some(delay) = cpb_removal_delay {
+                    values.push(("cpb_removal_delay", BoxValue::from(*delay)));
+                }
+                if let Some(delay) = dpb_output_delay {
+                    values.push(("dpb_output_delay", BoxValue::from(*delay)));
+                }
+                if let Some(pic_struct) = pic_struct {
+                    values.push(("pic_struct", BoxValue::from(*pic_struct)));
+                }
+                if let Some(source_scan_type) = source_scan_type {
+                    values.push(("source_scan_type", BoxValue::from(*source_scan_type)));
+                }
+                if let Some(duplicate_flag) = duplicate_flag {
+                    values.push(("duplicate_flag", BoxValue::from(*duplicate_flag)));
+                }
+                if !clock_timestamps.is_empty() {
+                    values.push(("clock_timestamps", BoxValue::Collection(
+                                clock_timestamps.iter().map(|ts| ts.get_values()).collect())));
+                }
+            },
+            SeiPayload::UndecodablePicTiming(note) => {
+                values.push(("note", BoxValue::from(*note)));
+            },
+            SeiPayload::Captions { country_code, provider_code, captions } => {
+                values.push(("itu_t_t35_country_code", BoxValue::from(*country_code)));
+                values.push(("itu_t_t35_provider_code", BoxValue::from(*provider_code)));
+                values.push(("cc_count", BoxValue::from(captions.cc_count)));
+                values.push(("cea608_field1_pairs", BoxValue::from(captions.cea608_pairs[0])));
+                values.push(("cea608_field1_text", BoxValue::from(captions.cea608_text[0].as_str())));
+                values.push(("cea608_field2_pairs", BoxValue::from(captions.cea608_pairs[1])));
+                values.push(("cea608_field2_text", BoxValue::from(captions.cea608_text[1].as_str())));
+                values.push(("cea708_pairs", BoxValue::from(captions.cea708_pairs)));
+            },
+            SeiPayload::RegisteredUserData { country_code, data } => {
+                values.push(("itu_t_t35_country_code", BoxValue::from(*country_code)));
+                values.push(("data", BoxValue::Hex(data)));
+            },
+            SeiPayload::UnregisteredUserData { uuid, data, text } => {
+                values.push(("uuid", BoxValue::Hex(uuid)));
+                match text {
+                    Some(text) => values.push(("text", BoxValue::from(text.as_str()))),
+                    None => values.push(("data", BoxValue::Hex(data))),
+                }
+            },
+            SeiPayload::MasteringDisplayColourVolume {
+                display_primaries,
+                white_point,
+                max_display_mastering_luminance,
+                min_display_mastering_luminance,
+            } => {
+                values.push(("display_primaries", BoxValue::Collection(
+                            display_primaries.iter().map(|(x, y)| vec![
+                                ("x", BoxValue::Scaled(*x as u32, 50000)),
+                                ("y", BoxValue::Scaled(*y as u32, 50000)),
+                            ]).collect())));
+                values.push(("white_point_x", BoxValue::Scaled(white_point.0 as u32, 50000)));
+                values.push(("white_point_y", BoxValue::Scaled(white_point.1 as u32, 50000)));
+                values.push(("max_display_mastering_luminance",
+                    BoxValue::Scaled(*max_display_mastering_luminance, 10000)));
+                values.push(("min_display_mastering_luminance",
+                    BoxValue::Scaled(*min_display_mastering_luminance, 10000)));
+            },
+            SeiPayload::ContentLightLevel { max_content_light_level, max_pic_average_light_level } => {
+                values.push(("max_content_light_level", BoxValue::from(*max_content_light_level)));
+                values.push(("max_pic_average_light_level",
+                    BoxValue::from(*max_pic_average_light_level)));
+            },
+            SeiPayload::TimeCode(clock_timestamps) => {
+                values.push(("clock_timestamps", BoxValue::Collection(
+                            clock_timestamps.iter().map(|ts| ts.get_values()).collect())));
+            },
+            SeiPayload::Other(data) => {
+                values.push(("data", BoxValue::Hex(data)));
+            },
+        }
+        values
+    }
+}
+
+/// Decode a SEI payload. `None` if its type is not supported or if it is
+/// malformed.
+fn decode_payload(
+    payload_type: u32,
+    data: &[u8],
+    format: NalFormat,
+    temporal_id: u8,
+    timing: &PicTimingState
+) -> Option<SeiPayload> {
+    let read_u16 = |offset: usize| data.get(offset..offset + 2)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]));
+    let read_u32 = |offset: usize| data.get(offset..offset + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]));
+    match payload_type {
+        1 if format == NalFormat::Avc => {
+            let timing = match timing.avc_timing.as_ref() {
+                Some(timing) => timing,
+                None => return Some(SeiPayload::UndecodablePicTiming(
+                        "cannot be decoded without its sequence parameter set")),
+            };
+            let mut reader = BitReader::new(data);
+            let (cpb_removal_delay, dpb_output_delay) = if timing.cpb_dpb_delays_present {
+                (Some(reader.read_bits(timing.cpb_removal_delay_length)?),
+                 Some(reader.read_bits(timing.dpb_output_delay_length)?))
+            } else {
+                (None, None)
+            };
+            let mut pic_struct = None;
+            let mut clock_timestamps = vec![];
+            if timing.pic_struct_present {
+                let value = reader.read_bits(4)? as u8;
+                pic_struct = Some(value);
+                let num_clock_ts = AVC_NUM_CLOCK_TS.get(value as usize).copied().unwrap_or(0);
+                for _ in 0..num_clock_ts {
+                    if reader.read_bit()? {
+                        // ct_type and nuit_field_based_flag
+                        reader.skip_bits(3)?;
+                        clock_timestamps.push(ClockTimestamp::read(
+                                &mut reader, 8, Some(timing.time_offset_length))?);
+                    }
+                }
+            }
+            Some(SeiPayload::PicTiming {
+                cpb_removal_delay,
+                dpb_output_delay,
+                pic_struct,
+                source_scan_type: None,
+                duplicate_flag: None,
+                clock_timestamps,
+            })
+        },
+        1 if format == NalFormat::Hevc => {
+            let timing = match timing.hevc_timing.as_ref() {
+                Some(timing) => timing,
+                None => return Some(SeiPayload::UndecodablePicTiming(
+                        "cannot be decoded without its sequence parameter set")),
+            };
+            let mut reader = BitReader::new(data);
+            let (mut pic_struct, mut source_scan_type, mut duplicate_flag) = (None, None, None);
+            if timing.frame_field_info_present {
+                pic_struct = Some(reader.read_bits(4)? as u8);
+                source_scan_type = Some(reader.read_bits(2)? as u8);
+                duplicate_flag = Some(reader.read_bit()?);
+            }
+            let (cpb_removal_delay, dpb_output_delay) = if timing.cpb_dpb_delays_present {
+                (Some(reader.read_bits(timing.cpb_removal_delay_length)? + 1),
+                 Some(reader.read_bits(timing.dpb_output_delay_length)?))
+            } else {
+                (None, None)
+            };
+            Some(SeiPayload::PicTiming {
+                cpb_removal_delay,
+                dpb_output_delay,
+                pic_struct,
+                source_scan_type,
+                duplicate_flag,
+                clock_timestamps: vec![],
+            })
+        },
+        1 => {
+            let buffering_period = match timing.vvc_buffering_period.as_ref() {
+                Some(buffering_period) => buffering_period,
+                None => return Some(SeiPayload::UndecodablePicTiming(
+                        "cannot be decoded without its buffering period")),
+            };
+            let mut reader = BitReader::new(data);
+            // removal delay of the highest sublayer
+            let cpb_removal_delay =
+                reader.read_bits(buffering_period.cpb_removal_delay_length)? + 1;
+            // skip the removal delays of the lower sublayers
+            for _ in temporal_id..buffering_period.max_sublayers_minus1 {
+                if !reader.read_bit()? {
+                    continue;
+                }
+                // either a delta index or the delay itself
+                let length = match buffering_period.cpb_removal_delay_delta_idx_length {
+                    Some(length) if reader.read_bit()? => length,
+                    _ => buffering_period.cpb_removal_delay_length,
+                };
+                reader.skip_bits(length as usize)?;
+            }
+            let dpb_output_delay = reader.read_bits(buffering_period.dpb_output_delay_length)?;
+            Some(SeiPayload::PicTiming {
+                cpb_removal_delay: Some(cpb_removal_delay),
+                dpb_output_delay: Some(dpb_output_delay),
+                pic_struct: None,
+                source_scan_type: None,
+                duplicate_flag: None,
+                clock_timestamps: vec![],
+            })
+        },
+        4 => {
+            let country_code = *data.first()?;
+            // an extension byte follows the 0xFF country code
+            let provider_offset = if country_code == 0xFF { 2 } else { 1 };
+            let provider_code = read_u16(provider_offset);
+            let user_identifier = data.get(provider_offset + 2..provider_offset + 6);
+            let user_data_type_code = data.get(provider_offset + 6);
+            match (provider_code, user_identifier, user_data_type_code) {
+                (Some(provider_code), Some(b"GA94"), Some(0x03)) => Some(SeiPayload::Captions {
+                    country_code,
+                    provider_code,
+                    captions: CaptionData::read(&data[provider_offset + 7..])?,
+                }),
+                _ => Some(SeiPayload::RegisteredUserData { country_code, data: data.to_vec() }),
+            }
+        },
+        5 => {
+            let mut uuid = [0u8; 16];
+            uuid.copy_from_slice(data.get(..16)?);
+            let user_data = &data[16..];
+            let text_data = match user_data.iter().position(|b| *b == 0) {
+                Some(end) => &user_data[..end],
+                None => user_data,
+            };
+            let text = if !text_data.is_empty() &&
+                text_data.iter().all(|b| b.is_ascii_graphic() || b.is_ascii_whitespace())
+            {
+                Some(String::from_utf8_lossy(text_data).into_owned())
+            } else {
+                None
+            };
+            Some(SeiPayload::UnregisteredUserData { uuid, data: user_data.to_vec(), text })
+        },
+        136 if format != NalFormat::Avc => {
+            let mut reader = BitReader::new(data);
+            let num_clock_ts = reader.read_bits(2)?;
+            let mut clock_timestamps = vec![];
+            for _ in 0..num_clock_ts {
+                if reader.read_bit()? {
+                    // units_field_based_flag
+                    reader.read_bit()?;
+                    clock_timestamps.push(ClockTimestamp::read(&mut reader, 9, None)?);
+                }
+            }
+            Some(SeiPayload::TimeCode(clock_timestamps))
+        },
+        137 => Some(SeiPayload::MasteringDisplayColourVolume {
+            display_primaries: [
+                (read_u16(0)?, read_u16(2)?),
+                (read_u16(4)?, read_u16(6)?),
+                (read_u16(8)?, read_u16(10)?),
+            ],
+            white_point: (read_u16(12)?, read_u16(14)?),
+            max_display_mastering_luminance: read_u32(16)?,
+            min_display_mastering_luminance: read_u32(20)?,
+        }),
+        144 => Some(SeiPayload::ContentLightLevel {
+            max_content_light_level: read_u16(0)?,
+            max_pic_average_light_level: read_u16(2)?,
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::PayloadKind;
+    use super::super::test_utils::{decode_fixture_samples, describe_values};
+
+    /// Returns, for each sample of each track of the "sei.mp4" fixture, the
+    /// described values of its SEI messages.
+    fn read_sei_values() -> Vec<Vec<Vec<String>>> {
+        decode_fixture_samples("sei.mp4", PayloadKind::NalUnits).iter()
+            .map(|samples| samples.iter()
+                .map(|sample| describe_values(&sample.get_values())
+                    .into_iter()
+                    .filter_map(|description| description
+                        .split_once("].sei_messages")
+                        .map(|(_, message)| format!("sei_messages{}", message)))
+                    .collect())
+                .collect())
+            .collect()
+    }
+
+    #[test]
+    fn avc_sei_messages() {
+        let sei = read_sei_values();
+        assert_eq!(sei[0], vec![vec![
+            "sei_messages[0].payload_type=1",
+            "sei_messages[0].payload_type_name=pic_timing",
+            "sei_messages[0].payload_size=12",
+            "sei_messages[0].cpb_removal_delay=2",
+            "sei_messages[0].dpb_output_delay=4",
+            "sei_messages[0].pic_struct=0",
+            "sei_messages[0].clock_timestamps[0].timecode=01:56:34:12",
+            "sei_messages[0].clock_timestamps[0].counting_type=0",
+            "sei_messages[0].clock_timestamps[0].discontinuity=false",
+            "sei_messages[0].clock_timestamps[0].cnt_dropped=false",
+            "sei_messages[0].clock_timestamps[0].time_offset=0",
+            "sei_messages[1].payload_type=4",
+            "sei_messages[1].payload_type_name=user_data_registered_itu_t_t35",
+            "sei_messages[1].payload_size=20",
+            "sei_messages[1].itu_t_t35_country_code=181",
+            "sei_messages[1].itu_t_t35_provider_code=49",
+            "sei_messages[1].cc_count=3",
+            "sei_messages[1].cea608_field1_pairs=2",
+            "sei_messages[1].cea608_field1_text=He",
+            "sei_messages[1].cea608_field2_pairs=0",
+            "sei_messages[1].cea608_field2_text=",
+            "sei_messages[1].cea708_pairs=1",
+            "sei_messages[2].payload_type=5",
+            "sei_messages[2].payload_type_name=user_data_unregistered",
+            "sei_messages[2].payload_size=32",
+            "sei_messages[2].uuid=11111111111111111111111111111111",
+            "sei_messages[2].text=x264 - core 164",
+            "sei_messages[3].payload_type=137",
+            "sei_messages[3].payload_type_name=mastering_display_colour_volume",
+            "sei_messages[3].payload_size=24",
+            "sei_messages[3].display_primaries[0].x=13250/50000",
+            "sei_messages[3].display_primaries[0].y=34500/50000",
+            "sei_messages[3].display_primaries[1].x=7500/50000",
+            "sei_messages[3].display_primaries[1].y=3000/50000",
+            "sei_messages[3].display_primaries[2].x=34000/50000",
+            "sei_messages[3].display_primaries[2].y=16000/50000",
+            "sei_messages[3].white_point_x=15635/50000",
+            "sei_messages[3].white_point_y=16450/50000",
+            "sei_messages[3].max_display_mastering_luminance=10000000/10000",
+            "sei_messages[3].min_display_mastering_luminance=50/10000",
+            "sei_messages[4].payload_type=144",
+            "sei_messages[4].payload_type_name=content_light_level_info",
+            "sei_messages[4].payload_size=4",
+            "sei_messages[4].max_content_light_level=1000",
+            "sei_messages[4].max_pic_average_light_level=400",
+        ]]);
+    }
+
+    #[test]
+    fn hevc_sei_messages() {
+        let sei = read_sei_values();
+        // `pic_timing` is decoded with the frame-field information and HRD
+        // parameters of the sequence parameter set
+        assert_eq!(sei[1], vec![vec![
+            "sei_messages[0].payload_type=1",
+            "sei_messages[0].payload_type_name=pic_timing",
+            "sei_messages[0].payload_size=4",
+            "sei_messages[0].cpb_removal_delay=8",
+            "sei_messages[0].dpb_output_delay=3",
+            "sei_messages[0].pic_struct=1",
+            "sei_messages[0].source_scan_type=1",
+            "sei_messages[0].duplicate_flag=false",
+            "sei_messages[1].payload_type=136",
+            "sei_messages[1].payload_type_name=time_code",
+            "sei_messages[1].payload_size=6",
+            "sei_messages[1].clock_timestamps[0].timecode=23:59:59;29",
+            "sei_messages[1].clock_timestamps[0].counting_type=0",
+            "sei_messages[1].clock_timestamps[0].discontinuity=false",
+            "sei_messages[1].clock_timestamps[0].cnt_dropped=true",
+            "sei_messages[1].clock_timestamps[0].time_offset=0",
+        ]]);
+    }
+
+    #[test]
+    fn vvc_sei_messages() {
+        let sei = read_sei_values();
+        assert_eq!(sei[2], vec![
+            // No buffering period is known yet
+            vec![
+                "sei_messages[0].payload_type=1",
+                "sei_messages[0].payload_type_name=pic_timing",
+                "sei_messages[0].payload_size=5",
+                "sei_messages[0].note=cannot be decoded without its buffering period",
+            ],
+            vec![
+                "sei_messages[0].payload_type=0",
+                "sei_messages[0].payload_type_name=buffering_period",
+                "sei_messages[0].payload_size=15",
+                "sei_messages[0].data=ae928001580201007091a2b32a190c",
+                "sei_messages[1].payload_type=1",
+                "sei_messages[1].payload_type_name=pic_timing",
+                "sei_messages[1].payload_size=5",
+                "sei_messages[1].cpb_removal_delay=20",
+                "sei_messages[1].dpb_output_delay=9",
+            ],
+        ]);
+    }
+}
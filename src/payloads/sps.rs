@@ -0,0 +1,425 @@
+use super::bits::BitReader;
+
+/// Fields of an AVC sequence parameter set needed to decode the `pic_timing`
+/// SEI messages of the pictures referring to it.
+#[derive(Clone, Copy, Default)]
+pub struct AvcTimingInfo {
+    /// Whether `pic_timing` messages start with `cpb_removal_delay` and
+    /// `dpb_output_delay`, which is the case when HRD parameters are present.
+    pub cpb_dpb_delays_present: bool,
+    pub cpb_removal_delay_length: u8,
+    pub dpb_output_delay_length: u8,
+    pub pic_struct_present: bool,
+    pub time_offset_length: u8,
+}
+
+/// Skip a `scaling_list` syntax structure of `size` coefficients.
+fn skip_scaling_list(reader: &mut BitReader, size: usize) -> Option<()> {
+    let mut last_scale = 8;
+    let mut next_scale = 8;
+    for _ in 0..size {
+        if next_scale != 0 {
+            let delta_scale = reader.read_se()?;
+            next_scale = (last_scale + delta_scale + 256) % 256;
+        }
+        if next_scale != 0 {
+            last_scale = next_scale;
+        }
+    }
+    Some(())
+}
+
+/// Read `hrd_parameters`, returning the lengths of `cpb_removal_delay`,
+/// `dpb_output_delay` and `time_offset`.
+fn read_hrd_parameters(reader: &mut BitReader) -> Option<(u8, u8, u8)> {
+    let cpb_cnt_minus1 = reader.read_ue()?;
+    // bit_rate_scale and cpb_size_scale
+    reader.skip_bits(8)?;
+    for _ in 0..=cpb_cnt_minus1 {
+        reader.read_ue()?;
+        reader.read_ue()?;
+        reader.read_bit()?;
+    }
+    // initial_cpb_removal_delay_length_minus1
+    reader.skip_bits(5)?;
+    let cpb_removal_delay_length = reader.read_bits(5)? as u8 + 1;
+    let dpb_output_delay_length = reader.read_bits(5)? as u8 + 1;
+    let time_offset_length = reader.read_bits(5)? as u8;
+    Some((cpb_removal_delay_length, dpb_output_delay_length, time_offset_length))
+}
+
+impl AvcTimingInfo {
+    /// Parse the raw byte sequence payload of an AVC sequence parameter set,
+    /// NAL unit header excluded.
+    /// `None` if it is truncated or malformed.
+    pub fn parse(rbsp: &[u8]) -> Option<Self> {
+        let mut reader = BitReader::new(rbsp);
+        let profile_idc = reader.read_bits(8)?;
+        // constraint flags and level_idc
+        reader.skip_bits(16)?;
+        reader.read_ue()?;
+        if [100, 110, 122, 244, 44, 83, 86, 118, 128, 138, 139, 134, 135].contains(&profile_idc) {
+            let chroma_format_idc = reader.read_ue()?;
+            if chroma_format_idc == 3 {
+                reader.read_bit()?;
+            }
+            reader.read_ue()?;
+            reader.read_ue()?;
+            reader.read_bit()?;
+            if reader.read_bit()? {
+                let list_count = if chroma_format_idc == 3 { 12 } else { 8 };
+                for i in 0..list_count {
+                    if reader.read_bit()? {
+                        skip_scaling_list(&mut reader, if i < 6 { 16 } else { 64 })?;
+                    }
+                }
+            }
+        }
+        reader.read_ue()?;
+        match reader.read_ue()? {
+            0 => {
+                reader.read_ue()?;
+            },
+            1 => {
+                reader.read_bit()?;
+                reader.read_se()?;
+                reader.read_se()?;
+                for _ in 0..reader.read_ue()? {
+                    reader.read_se()?;
+                }
+            },
+            _ => {},
+        }
+        reader.read_ue()?;
+        reader.read_bit()?;
+        reader.read_ue()?;
+        reader.read_ue()?;
+        if !reader.read_bit()? {
+            reader.read_bit()?;
+        }
+        reader.read_bit()?;
+        if reader.read_bit()? {
+            for _ in 0..4 {
+                reader.read_ue()?;
+            }
+        }
+
+        let mut timing_info = AvcTimingInfo::default();
+        if !reader.read_bit()? {
+            return Some(timing_info);
+        }
+
+        // VUI parameters
+        if reader.read_bit()? && reader.read_bits(8)? == 255 {
+            reader.skip_bits(32)?;
+        }
+        if reader.read_bit()? {
+            reader.read_bit()?;
+        }
+        if reader.read_bit()? {
+            reader.skip_bits(4)?;
+            if reader.read_bit()? {
+                reader.skip_bits(24)?;
+            }
+        }
+        if reader.read_bit()? {
+            reader.read_ue()?;
+            reader.read_ue()?;
+        }
+        if reader.read_bit()? {
+            reader.skip_bits(65)?;
+        }
+        let mut hrd_present = false;
+        for _ in 0..2 {
+            if reader.read_bit()? {
+                hrd_present = true;
+                let (cpb_removal_delay_length, dpb_output_delay_length, time_offset_length) =
+                    read_hrd_parameters(&mut reader)?;
+                timing_info.cpb_removal_delay_length = cpb_removal_delay_length;
+                timing_info.dpb_output_delay_length = dpb_output_delay_length;
+                timing_info.time_offset_length = time_offset_length;
+            }
+        }
+        if hrd_present {
+            // low_delay_hrd_flag
+            reader.read_bit()?;
+        }
+        timing_info.cpb_dpb_delays_present = hrd_present;
+        timing_info.pic_struct_present = reader.read_bit()?;
+        Some(timing_info)
+    }
+}
+
+/// Fields of an HEVC sequence parameter set needed to decode the
+/// `pic_timing` SEI messages of the pictures referring to it.
+#[derive(Clone, Copy, Default)]
+pub struct HevcTimingInfo {
+    /// Whether `pic_timing` messages start with `pic_struct`,
+    /// `source_scan_type` and `duplicate_flag`.
+    pub frame_field_info_present: bool,
+
+    /// Whether `pic_timing` messages carry `au_cpb_removal_delay_minus1` and
+    /// `pic_dpb_output_delay`, which is the case when HRD parameters are
+    /// present.
+    pub cpb_dpb_delays_present: bool,
+    pub cpb_removal_delay_length: u8,
+    pub dpb_output_delay_length: u8,
+}
+
+/// Skip a `profile_tier_level` syntax structure whose general profile is
+/// present.
+fn skip_profile_tier_level(reader: &mut BitReader, max_sub_layers_minus1: u8) -> Option<()> {
+    // general profile, tier and level
+    reader.skip_bits(96)?;
+    let mut sub_layer_flags = vec![];
+    for _ in 0..max_sub_layers_minus1 {
+        sub_layer_flags.push((reader.read_bit()?, reader.read_bit()?));
+    }
+    if max_sub_layers_minus1 > 0 {
+        reader.skip_bits(2 * (8 - max_sub_layers_minus1 as usize))?;
+    }
+    for (profile_present, level_present) in sub_layer_flags {
+        if profile_present {
+            reader.skip_bits(88)?;
+        }
+        if level_present {
+            reader.skip_bits(8)?;
+        }
+    }
+    Some(())
+}
+
+/// Skip an HEVC `scaling_list_data` syntax structure.
+fn skip_hevc_scaling_list_data(reader: &mut BitReader) -> Option<()> {
+    for size_id in 0..4 {
+        let matrix_step = if size_id == 3 { 3 } else { 1 };
+        for _ in (0..6).step_by(matrix_step) {
+            if !reader.read_bit()? {
+                // scaling_list_pred_matrix_id_delta
+                reader.read_ue()?;
+            } else {
+                if size_id > 1 {
+                    reader.read_se()?;
+                }
+                for _ in 0..64.min(1 << (4 + (size_id << 1))) {
+                    reader.read_se()?;
+                }
+            }
+        }
+    }
+    Some(())
+}
+
+/// Read a picture order count difference coded minus 1, which is at most
+/// 2^15.
+fn read_delta_poc(reader: &mut BitReader) -> Option<i32> {
+    match reader.read_ue()? {
+        value if value < 0x8000 => Some(value as i32 + 1),
+        _ => None,
+    }
+}
+
+/// Read the `st_ref_pic_set` of index `index`, returning its negative and
+/// positive picture order count deltas.
+/// The deltas of the previous sets are needed to resolve the ones predicted
+/// from them.
+fn read_short_term_ref_pic_set(
+    reader: &mut BitReader,
+    index: usize,
+    previous_sets: &[(Vec<i32>, Vec<i32>)]
+) -> Option<(Vec<i32>, Vec<i32>)> {
+    if index != 0 && reader.read_bit()? {
+        // inter_ref_pic_set_prediction_flag, always from the previous set in
+        // a sequence parameter set
+        let (ref_negative, ref_positive) = previous_sets.last()?;
+        let sign = reader.read_bit()?;
+        let abs_delta = read_delta_poc(reader)?;
+        let delta_rps = if sign { -abs_delta } else { abs_delta };
+        let num_deltas = ref_negative.len() + ref_positive.len();
+        let mut use_delta = Vec::with_capacity(num_deltas + 1);
+        for _ in 0..=num_deltas {
+            let used_by_curr_pic = reader.read_bit()?;
+            use_delta.push(used_by_curr_pic || reader.read_bit()?);
+        }
+        let (neg_count, pos_count) = (ref_negative.len(), ref_positive.len());
+        let mut negative = vec![];
+        for j in (0..pos_count).rev() {
+            let delta = ref_positive[j] + delta_rps;
+            if delta < 0 && use_delta[neg_count + j] {
+                negative.push(delta);
+            }
+        }
+        if delta_rps < 0 && use_delta[num_deltas] {
+            negative.push(delta_rps);
+        }
+        for j in 0..neg_count {
+            let delta = ref_negative[j] + delta_rps;
+            if delta < 0 && use_delta[j] {
+                negative.push(delta);
+            }
+        }
+        let mut positive = vec![];
+        for j in (0..neg_count).rev() {
+            let delta = ref_negative[j] + delta_rps;
+            if delta > 0 && use_delta[j] {
+                positive.push(delta);
+            }
+        }
+        if delta_rps > 0 && use_delta[num_deltas] {
+            positive.push(delta_rps);
+        }
+        for j in 0..pos_count {
+            let delta = ref_positive[j] + delta_rps;
+            if delta > 0 && use_delta[neg_count + j] {
+                positive.push(delta);
+            }
+        }
+        return Some((negative, positive));
+    }
+    let num_negative_pics = reader.read_ue()?;
+    let num_positive_pics = reader.read_ue()?;
+    if num_negative_pics > 16 || num_positive_pics > 16 {
+        return None;
+    }
+    let mut poc = 0;
+    let mut negative = vec![];
+    for _ in 0..num_negative_pics {
+        poc -= read_delta_poc(reader)?;
+        negative.push(poc);
+        reader.read_bit()?;
+    }
+    poc = 0;
+    let mut positive = vec![];
+    for _ in 0..num_positive_pics {
+        poc += read_delta_poc(reader)?;
+        positive.push(poc);
+        reader.read_bit()?;
+    }
+    Some((negative, positive))
+}
+
+impl HevcTimingInfo {
+    /// Parse the raw byte sequence payload of an HEVC sequence parameter
+    /// set, NAL unit header excluded.
+    /// `None` if it is truncated or malformed.
+    pub fn parse(rbsp: &[u8]) -> Option<Self> {
+        let mut reader = BitReader::new(rbsp);
+        // sps_video_parameter_set_id
+        reader.skip_bits(4)?;
+        let max_sub_layers_minus1 = reader.read_bits(3)? as u8;
+        reader.read_bit()?;
+        skip_profile_tier_level(&mut reader, max_sub_layers_minus1)?;
+        reader.read_ue()?;
+        if reader.read_ue()? == 3 {
+            reader.read_bit()?;
+        }
+        reader.read_ue()?;
+        reader.read_ue()?;
+        if reader.read_bit()? {
+            for _ in 0..4 {
+                reader.read_ue()?;
+            }
+        }
+        reader.read_ue()?;
+        reader.read_ue()?;
+        let log2_max_pic_order_cnt_lsb = reader.read_ue()?.checked_add(4)?;
+        if log2_max_pic_order_cnt_lsb > 16 {
+            return None;
+        }
+        let first_sub_layer = if reader.read_bit()? { 0 } else { max_sub_layers_minus1 };
+        for _ in first_sub_layer..=max_sub_layers_minus1 {
+            for _ in 0..3 {
+                reader.read_ue()?;
+            }
+        }
+        for _ in 0..6 {
+            reader.read_ue()?;
+        }
+        if reader.read_bit()? && reader.read_bit()? {
+            skip_hevc_scaling_list_data(&mut reader)?;
+        }
+        // amp_enabled_flag and sample_adaptive_offset_enabled_flag
+        reader.skip_bits(2)?;
+        if reader.read_bit()? {
+            reader.skip_bits(8)?;
+            reader.read_ue()?;
+            reader.read_ue()?;
+            reader.read_bit()?;
+        }
+        let num_short_term_ref_pic_sets = reader.read_ue()? as usize;
+        if num_short_term_ref_pic_sets > 64 {
+            return None;
+        }
+        let mut ref_pic_sets = vec![];
+        for index in 0..num_short_term_ref_pic_sets {
+            let set = read_short_term_ref_pic_set(&mut reader, index, &ref_pic_sets)?;
+            ref_pic_sets.push(set);
+        }
+        if reader.read_bit()? {
+            for _ in 0..reader.read_ue()? {
+                reader.skip_bits(log2_max_pic_order_cnt_lsb as usize + 1)?;
+            }
+        }
+        // sps_temporal_mvp_enabled_flag and strong_intra_smoothing_enabled_flag
+        reader.skip_bits(2)?;
+
+        let mut timing_info = HevcTimingInfo::default();
+        if !reader.read_bit()? {
+            return Some(timing_info);
+        }
+
+        // VUI parameters
+        if reader.read_bit()? && reader.read_bits(8)? == 255 {
+            reader.skip_bits(32)?;
+        }
+        if reader.read_bit()? {
+            reader.read_bit()?;
+        }
+        if reader.read_bit()? {
+            reader.skip_bits(4)?;
+            if reader.read_bit()? {
+                reader.skip_bits(24)?;
+            }
+        }
+        if reader.read_bit()? {
+            reader.read_ue()?;
+            reader.read_ue()?;
+        }
+        // neutral_chroma_indication_flag and field_seq_flag
+        reader.skip_bits(2)?;
+        timing_info.frame_field_info_present = reader.read_bit()?;
+        if reader.read_bit()? {
+            for _ in 0..4 {
+                reader.read_ue()?;
+            }
+        }
+        if !reader.read_bit()? {
+            return Some(timing_info);
+        }
+        reader.skip_bits(64)?;
+        if reader.read_bit()? {
+            reader.read_ue()?;
+        }
+        if !reader.read_bit()? {
+            return Some(timing_info);
+        }
+
+        // Common information of the HRD parameters, which is all that the
+        // `pic_timing` messages depend on
+        let nal_hrd_parameters_present = reader.read_bit()?;
+        let vcl_hrd_parameters_present = reader.read_bit()?;
+        if nal_hrd_parameters_present || vcl_hrd_parameters_present {
+            let sub_pic_hrd_params_present = reader.read_bit()?;
+            if sub_pic_hrd_params_present {
+                reader.skip_bits(19)?;
+            }
+            reader.skip_bits(if sub_pic_hrd_params_present { 12 } else { 8 })?;
+            // initial_cpb_removal_delay_length_minus1
+            reader.skip_bits(5)?;
+            timing_info.cpb_dpb_delays_present = true;
+            timing_info.cpb_removal_delay_length = reader.read_bits(5)? as u8 + 1;
+            timing_info.dpb_output_delay_length = reader.read_bits(5)? as u8 + 1;
+        }
+        Some(timing_info)
+    }
+}
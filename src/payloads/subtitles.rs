@@ -0,0 +1,102 @@
+use super::super::boxes::{BoxValue, decode_text};
+use super::split_boxes;
+
+/// A box of a WebVTT sample, either a cue (`vttc`), a cue-less interval
+/// (`vtte`) or a comment (`vtta`).
+pub struct VttBox {
+    box_type: String,
+
+    /// Text of each box contained in a `vttc` box, by box type, or the
+    /// comment of a `vtta` box.
+    content: Vec<(String, String)>,
+}
+
+/// Content of a sample of a subtitle or timed text track.
+pub enum SubtitleSample {
+    /// `stpp` sample: a TTML document.
+    Ttml(String),
+
+    /// `wvtt` sample: WebVTT cues active during the sample.
+    WebVtt(Vec<VttBox>),
+
+    /// `tx3g` sample: the displayed text, followed by the types of the
+    /// boxes modifying its display (styles, highlights, karaoke...).
+    Tx3g {
+        text: String,
+        modifiers: Vec<String>,
+    },
+}
+
+impl SubtitleSample {
+    /// Decode a sample of a track whose sample entry is `codec`.
+    /// `None` if the coding is not a supported subtitle format.
+    pub fn decode(codec: &str, data: &[u8]) -> Option<Self> {
+        match codec {
+            "stpp" => Some(SubtitleSample::Ttml(decode_text(data))),
+            "wvtt" => Some(SubtitleSample::WebVtt(
+                    split_boxes(data).into_iter().map(|(box_type, content)| {
+                        let content = match box_type.as_str() {
+                            "vttc" => split_boxes(content)
+                                .into_iter()
+                                .map(|(child_type, child)| (child_type, decode_text(child)))
+                                .collect(),
+                            "vtta" => vec![(box_type.clone(), decode_text(content))],
+                            _ => vec![],
+                        };
+                        VttBox { box_type, content }
+                    }).collect())),
+            "tx3g" => {
+                let text_length = if data.len() >= 2 {
+                    (u16::from_be_bytes([data[0], data[1]]) as usize).min(data.len() - 2)
+                } else {
+                    0
+                };
+                let text = decode_text(data.get(2..2 + text_length).unwrap_or_default());
+                let modifiers = split_boxes(data.get(2 + text_length..).unwrap_or_default())
+                    .into_iter()
+                    .map(|(box_type, _)| box_type)
+                    .collect();
+                Some(SubtitleSample::Tx3g { text, modifiers })
+            },
+            _ => None,
+        }
+    }
+
+    pub fn get_values(&self) -> Vec<(&'static str, BoxValue)> {
+        match self {
+            SubtitleSample::Ttml(document) => vec![
+                ("document", BoxValue::from(document.as_str())),
+            ],
+            SubtitleSample::WebVtt(boxes) => vec![
+                ("cues", BoxValue::Collection(
+                        boxes.iter().map(|vtt_box| {
+                            let mut values = vec![
+                                ("type", BoxValue::from(vtt_box.box_type.as_str())),
+                            ];
+                            for (content_type, text) in vtt_box.content.iter() {
+                                values.push((get_vtt_box_description(content_type),
+                                    BoxValue::from(text.as_str())));
+                            }
+                            values
+                        }).collect()
+                )),
+            ],
+            SubtitleSample::Tx3g { text, modifiers } => vec![
+                ("text", BoxValue::from(text.as_str())),
+                ("modifiers", BoxValue::from(modifiers.as_slice())),
+            ],
+        }
+    }
+}
+
+/// Returns what a box found in a WebVTT sample contains.
+fn get_vtt_box_description(box_type: &str) -> &'static str {
+    match box_type {
+        "iden" => "cue_id",
+        "sttg" => "settings",
+        "payl" => "payload",
+        "ctim" => "current_time",
+        "vtta" => "comment",
+        _ => "unknown",
+    }
+}
@@ -1,17 +1,19 @@
 mod filter;
 mod options;
 mod render;
+mod time;
 
 pub use options::DisplayOptions;
 
 use super::boxes::IsoBoxData;
 use filter::filter_results;
 use render::render_box_data;
+pub use render::render_sample;
 
-pub fn render_result(results: Vec<IsoBoxData>, opts: DisplayOptions) {
+pub fn render_result(results: &[IsoBoxData], opts: &DisplayOptions) {
     let mut is_initial_line = true;
     if let Some(boxes_to_display) = &opts.filter_boxes {
-        let filtered_results = filter_results(&results, boxes_to_display);
+        let filtered_results = filter_results(results, boxes_to_display);
         if opts.display_only_size {
             let combined_size = filtered_results
                 .iter()
@@ -26,7 +28,7 @@ pub fn render_result(results: Vec<IsoBoxData>, opts: DisplayOptions) {
                 println!(); // line break for subsequent boxes
             }
 
-            render_box_data(result.0, result.1, 0, &opts);
+            render_box_data(result.0, result.1, 0, opts);
         }
     } else {
         // TODO factorize size display code?
@@ -47,7 +49,7 @@ pub fn render_result(results: Vec<IsoBoxData>, opts: DisplayOptions) {
             let parsed_ref = box_data.1
                 .as_ref()
                 .map(|boxed| std::boxed::Box::as_ref(&boxed));
-            render_box_data(&box_data.0, parsed_ref, 0, &opts);
+            render_box_data(&box_data.0, parsed_ref, 0, opts);
         }
     };
 }
@@ -1,9 +1,11 @@
 use super::super::boxes::{
     BoxValue,
+    FlagNames,
     IsoBoxEntry,
     IsoBoxInfo,
 };
 use super::options::DisplayOptions;
+use super::time::{format_mp4_date, format_ntp_timestamp, format_ticks};
 
 pub fn render_box_data(
     box_info: &IsoBoxInfo,
@@ -19,7 +21,7 @@ pub fn render_box_data(
         },
         Some(val) => {
             for value in val.get_inner_values_ref().iter() {
-                display_inner_value(value, &padding, opts);
+                display_inner_value(value, &padding, val.get_flag_names(), opts);
             }
             if let Some(contained) = val.get_inner_boxes_ref() {
                 for parsed in contained.iter() {
@@ -35,6 +37,15 @@ pub fn render_box_data(
     };
 }
 
+/// Display values decoded from a sample, under the given title.
+pub fn render_sample(title: &str, values: &[(&str, BoxValue)], opts: &DisplayOptions) {
+    println!("\x1b[0;31m{}\x1b[0m", title);
+    println!("-------------------------------");
+    for value in values.iter() {
+        display_inner_value(value, "", &[], opts);
+    }
+}
+
 fn display_box_title(box_info: &IsoBoxInfo, padding: &str) {
     let IsoBoxInfo { short_name, size, offset, .. } = box_info;
     println!("{}\x1b[0;31m{}\x1b[0m (offset: {}, size: {})", padding, short_name, offset, size);
@@ -44,9 +55,16 @@ fn display_box_title(box_info: &IsoBoxInfo, padding: &str) {
 fn display_inner_value(
     inner_value: &(&str, BoxValue),
     padding: &str,
+    flag_names: FlagNames,
     opts: &DisplayOptions
 ) {
-    let value_to_string = stringify_box_value(&inner_value.1, &padding, opts);
+    let mut value_to_string = stringify_box_value(&inner_value.1, padding, opts);
+    if let BoxValue::Flags(flags) = inner_value.1 {
+        let set_flags = flags.get_set_flag_names(flag_names);
+        if !set_flags.is_empty() {
+            value_to_string = format!("{} ({})", value_to_string, set_flags.join(", "));
+        }
+    }
     println!("{}\x1b[0;32m{}:\x1b[0m {}", padding, inner_value.0, value_to_string);
 }
 
@@ -60,11 +78,34 @@ fn stringify_box_value(
         BoxValue::UInt16(x) => x.to_string(),
         BoxValue::UInt32(x) => x.to_string(),
         BoxValue::UInt64(x) => x.to_string(),
+        BoxValue::Int16(x) => x.to_string(),
         BoxValue::Int32(x) => x.to_string(),
         BoxValue::Int64(x) => x.to_string(),
         BoxValue::Flags(flags) => flags.to_hex_string(),
+        BoxValue::SampleFlags(flags) => {
+            use std::fmt::Write;
+            let mut s = flags.to_hex_string();
+            for item in flags.get_values().iter() {
+                let value_to_string = stringify_box_value(
+                    &item.1,
+                    multi_line_padding,
+                    opts);
+                write!(&mut s, "\n{}\t\x1b[0;32m{}:\x1b[0m {}", multi_line_padding, item.0, value_to_string)
+                    .expect("Issue formatting SampleFlags");
+            }
+            s
+        },
         BoxValue::Bool(val) => val.to_string(),
 
+        BoxValue::Date(secs) => format!("{} ({})", secs, format_mp4_date(*secs)),
+        BoxValue::Ticks(ticks, timescale) =>
+            format!("{} ({})", ticks, format_ticks(*ticks as i128, *timescale)),
+        BoxValue::SignedTicks(ticks, timescale) =>
+            format!("{} ({})", ticks, format_ticks(*ticks as i128, *timescale)),
+        BoxValue::NtpTimestamp(ts) => format!("{} ({})", ts, format_ntp_timestamp(*ts)),
+
+        BoxValue::Hex(arr) =>
+            arr.iter().map(|x| format!("{:02x}", x)).collect::<Vec<String>>().join(""),
         BoxValue::UInt8Arr(arr) =>
             arr.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(", "),
         BoxValue::UInt32Arr(arr) =>
@@ -76,22 +117,34 @@ fn stringify_box_value(
 
         BoxValue::Utf8Arr(stv) => stv.join(", "),
 
-        BoxValue::FixedPoint8(arr) =>
-            arr.iter().map(|val| val.to_string()).collect::<Vec<String>>().join("."),
-        BoxValue::FixedPoint16(arr) =>
-            arr.iter().map(|val| val.to_string()).collect::<Vec<String>>().join("."),
+        BoxValue::FixedPoint8_8(val) => format_decimal(*val as f64 / 256.),
+        BoxValue::FixedPoint16_16(val) => format_decimal(*val as f64 / 65536.),
+        BoxValue::UFixedPoint16_16(val) => format_decimal(*val as f64 / 65536.),
+        BoxValue::FixedPoint2_30(val) => format_decimal(*val as f64 / 1_073_741_824.),
+
+        BoxValue::Scaled(val, divider) =>
+            format!("{} ({})", val, format_decimal(*val as f64 / *divider as f64)),
+
+        BoxValue::Language(val) => decode_language(*val),
 
         BoxValue::Matrix3_3(m) => {
             use std::fmt::Write;
+            let values: Vec<f64> = m.iter().enumerate().map(|(i, val)| {
+                // u, v and w are 2.30 fixed-point values
+                let divider = if i % 3 == 2 { 1_073_741_824. } else { 65536. };
+                *val as i32 as f64 / divider
+            }).collect();
             let mut s = String::new();
-            write!(&mut s, "\n{}\t{}\t{}\t{}\n{}\t{}\t{}\t{}\n{}\t{}\t{}\t{}",
-                multi_line_padding,
-                m[0], m[1], m[2],
-                multi_line_padding,
-                m[3], m[4], m[5],
-                multi_line_padding,
-                m[6], m[7], m[8])
-                .expect("Unable to write hex string for flags.");
+            for row in values.chunks(3) {
+                write!(&mut s, "\n{}\t{}\t{}\t{}",
+                    multi_line_padding,
+                    format_decimal(row[0]),
+                    format_decimal(row[1]),
+                    format_decimal(row[2]))
+                    .expect("Unable to write matrix values.");
+            }
+            write!(&mut s, "\n{}\t({})", multi_line_padding, describe_matrix(&values))
+                .expect("Unable to write matrix values.");
             s
         },
 
@@ -106,7 +159,7 @@ fn stringify_box_value(
                     let mut s = String::new();
                     let value_to_string = stringify_box_value(
                         &item.1,
-                        &multi_line_padding,
+                        &(multi_line_padding.to_owned() + "\t"),
                         opts);
                     write!(&mut s, "\n{}\t\x1b[0;32m{}:\x1b[0m {}", multi_line_padding, item.0, value_to_string).expect("Issue formatting Collection");
                     s
@@ -115,3 +168,40 @@ fn stringify_box_value(
         },
     }
 }
+
+/// Format a decimal value, always including at least one fractional digit.
+fn format_decimal(val: f64) -> String {
+    let s = val.to_string();
+    if s.contains('.') { s } else { s + ".0" }
+}
+
+/// Decode a packed ISO-639-2/T language code: three characters, each
+/// represented as its ASCII value minus 0x60 on 5 bits.
+fn decode_language(val: u16) -> String {
+    [(val >> 10) & 0x1F, (val >> 5) & 0x1F, val & 0x1F]
+        .iter()
+        .map(|c| (*c as u8 + 0x60) as char)
+        .collect()
+}
+
+/// Describe the transformation applied by a matrix of already-converted
+/// decimal values, in terms of rotation, scale and translation.
+fn describe_matrix(m: &[f64]) -> String {
+    let (a, b, c, d, x, y) = (m[0], m[1], m[3], m[4], m[6], m[7]);
+    let scale_x = (a * a + b * b).sqrt();
+    let mut scale_y = (c * c + d * d).sqrt();
+    if a * d - b * c < 0. {
+        // mirrored on the vertical axis
+        scale_y = -scale_y;
+    }
+    let mut rotation = b.atan2(a).to_degrees();
+    if rotation < 0. {
+        rotation += 360.;
+    }
+    format!("rotation: {}°, scale: {} x {}, translation: {} x {}",
+        format_decimal((rotation * 1000.).round() / 1000.),
+        format_decimal(scale_x),
+        format_decimal(scale_y),
+        format_decimal(x),
+        format_decimal(y))
+}
@@ -0,0 +1,103 @@
+use std::convert::TryFrom;
+
+/// Number of seconds between the ISOBMFF epoch (midnight, Jan. 1, 1904, UTC)
+/// and the Unix epoch (midnight, Jan. 1, 1970, UTC).
+const MP4_TO_UNIX_EPOCH_OFFSET: i64 = 2_082_844_800;
+
+/// Number of seconds between the NTP epoch (midnight, Jan. 1, 1900, UTC) and
+/// the Unix epoch.
+const NTP_TO_UNIX_EPOCH_OFFSET: i64 = 2_208_988_800;
+
+/// Format a date expressed in seconds since the ISOBMFF epoch into an
+/// ISO-8601 string in UTC time.
+/// 64-bit values too large to be converted are returned as is.
+pub fn format_mp4_date(secs: u64) -> String {
+    match i64::try_from(secs).ok().and_then(|secs| secs.checked_sub(MP4_TO_UNIX_EPOCH_OFFSET)) {
+        Some(unix_secs) => format_unix_date(unix_secs, 0),
+        None => secs.to_string(),
+    }
+}
+
+/// Format a 64-bit NTP timestamp (32 bits of seconds, 32 bits of fraction)
+/// into an ISO-8601 string in UTC time.
+pub fn format_ntp_timestamp(timestamp: u64) -> String {
+    let secs = (timestamp >> 32) as i64;
+    let millis = ((timestamp & 0xFFFFFFFF) * 1000) >> 32;
+    format_unix_date(secs - NTP_TO_UNIX_EPOCH_OFFSET, millis as u32)
+}
+
+/// Format a date expressed in seconds (and additional milliseconds) since the
+/// Unix epoch into an ISO-8601 string in UTC time.
+pub fn format_unix_date(secs: i64, millis: u32) -> String {
+    let days = secs.div_euclid(86400);
+    let secs_of_day = secs.rem_euclid(86400);
+    let (year, month, day) = civil_from_days(days);
+    let time = format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year, month, day,
+        secs_of_day / 3600,
+        (secs_of_day % 3600) / 60,
+        secs_of_day % 60);
+    if millis == 0 {
+        time + "Z"
+    } else {
+        format!("{}.{:03}Z", time, millis)
+    }
+}
+
+/// Format a number of ticks in the given timescale into both a number of
+/// seconds and a `HH:MM:SS.mmm` timecode.
+pub fn format_ticks(ticks: i128, timescale: u32) -> String {
+    let sign = if ticks < 0 { "-" } else { "" };
+    let abs_ticks = ticks.abs();
+    let timescale = timescale as i128;
+    let total_millis = (abs_ticks * 1000 + timescale / 2) / timescale;
+    let millis = total_millis % 1000;
+    let total_secs = total_millis / 1000;
+    format!("{}{:.3}s, {}{:02}:{:02}:{:02}.{:03}",
+        sign,
+        abs_ticks as f64 / timescale as f64,
+        sign,
+        total_secs / 3600,
+        (total_secs % 3600) / 60,
+        total_secs % 60,
+        millis)
+}
+
+/// Convert a number of days since the Unix epoch into a (year, month, day)
+/// date in the proleptic Gregorian calendar.
+///
+/// This is Howard Hinnant's `civil_from_days` algorithm.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_mp4_date, format_ntp_timestamp, format_ticks};
+
+    #[test]
+    fn format_dates() {
+        assert_eq!(format_mp4_date(0), "1904-01-01T00:00:00Z");
+        assert_eq!(format_mp4_date(3_786_912_000), "2024-01-01T00:00:00Z");
+        assert_eq!(format_mp4_date(u64::MAX), u64::MAX.to_string());
+        assert_eq!(format_ntp_timestamp((3_913_056_000 << 32) | 0x8000_0000),
+            "2024-01-01T00:00:00.500Z");
+        assert_eq!(format_ntp_timestamp(0), "1900-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn format_tick_counts() {
+        assert_eq!(format_ticks(90_000, 90_000), "1.000s, 00:00:01.000");
+        assert_eq!(format_ticks(-1_500, 1_000), "-1.500s, -00:00:01.500");
+        assert_eq!(format_ticks(3_723_004, 1_000), "3723.004s, 01:02:03.004");
+    }
+}